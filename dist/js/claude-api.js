@@ -49,6 +49,7 @@
  *   - restoreClaudeState()
  *   - initClaudeHandlers()
  *   - startProject(uuid, name, ownerTab)
+ *   - registerProjectInBackend(uuid, name, ownerTab)
  *   - finishProject()
  *   - getProjectUUIDFromUrl(url)
  *   - restoreProjectState()
@@ -951,7 +952,10 @@ async function sendTextToClaude(text, tab) {
             showToast(`Чат ${targetTab}: отправлено`);
         }
     } catch (e) {
-        showToast(`Чат ${targetTab}: ошибка отправки`);
+        // Превышение бюджета usage показывается по событию usage-budget-exceeded
+        if (!String(e).includes('usage_budget_exceeded')) {
+            showToast(`Чат ${targetTab}: ошибка отправки`);
+        }
     }
 }
 
@@ -1217,7 +1221,8 @@ async function newChatInTab(tab, clearName = true) {
 
 /**
  * Восстановление состояния Claude
- * Все три таба создаются при старте, здесь восстанавливаем URL и настройки
+ * Вызывается после init_claude_webviews: видимость панели бэкенд уже
+ * восстановил из window_state.json, здесь восстанавливаем URL и настройки
  */
 async function restoreClaudeState() {
     const saved = loadClaudeSettings();
@@ -1229,55 +1234,56 @@ async function restoreClaudeState() {
     CdpTimeout.reset();
     
     try {
-        // Если есть сохранённые настройки
-        if (saved) {
-            // Восстанавливаем ratio
-            if (saved.panelRatio && saved.panelRatio !== 50) {
-                panelRatio = saved.panelRatio;
-                await window.__TAURI__.core.invoke('set_panel_ratio', { ratio: panelRatio });
-            }
+        // Восстанавливаем ratio
+        if (saved?.panelRatio && saved.panelRatio !== 50) {
+            panelRatio = saved.panelRatio;
+            await window.__TAURI__.core.invoke('set_panel_ratio', { ratio: panelRatio });
+        }
+        
+        // Claude открыт, только если был открыт ранее (решает бэкенд).
+        // saved.visible — флаг старых версий, пропадает при следующем сохранении
+        let [visible] = await window.__TAURI__.core.invoke('get_claude_state');
+        if (!visible && saved?.visible === true) {
+            visible = await window.__TAURI__.core.invoke('toggle_claude');
+        }
+        isClaudeVisible = visible;
+        if (visible) {
+            // Даём время на инициализацию
+            await delay(300);
             
-            // Claude открывается только если был открыт ранее
-            if (saved.visible === true) {
-                isClaudeVisible = await window.__TAURI__.core.invoke('toggle_claude');
-                
-                // Даём время на инициализацию
-                await delay(300);
-                
-                // Восстанавливаем URL для всех табов с сохранёнными URL
-                for (const tab of [1, 2, 3]) {
-                    const tabUrl = saved.tabUrls?.[tab];
-                    if (tabUrl && tabUrl !== 'about:blank' && tabUrl.startsWith('https://claude.ai')) {
-                        await window.__TAURI__.core.invoke('switch_claude_tab_with_url', { tab, url: tabUrl });
-                        await delay(100);
-                    }
-                }
-                
-                // Переключаемся на сохранённый активный таб
-                if (saved.activeTab) {
-                    await window.__TAURI__.core.invoke('switch_claude_tab', { tab: saved.activeTab });
-                }
-                
-                // Восстанавливаем названия табов
-                if (saved.tabNames) {
-                    Object.assign(tabNames, saved.tabNames);
-                }
-                
-                await updateClaudeState();
-                
-                // Ждём следующий frame перед обновлением resizer для гарантии синхронизации DOM
-                await new Promise(resolve => requestAnimationFrame(resolve));
-                updateResizer();
-                
-                // Смещаем workflow на ширину resizer
-                const workflowContainer = getWorkflowContainer();
-                if (workflowContainer) {
-                    workflowContainer.style.width = 'calc(100% - 6px)';
+            // Восстанавливаем URL для всех табов с сохранёнными URL
+            for (const tab of [1, 2, 3]) {
+                const tabUrl = saved?.tabUrls?.[tab];
+                if (tabUrl && tabUrl !== 'about:blank' && tabUrl.startsWith('https://claude.ai')) {
+                    await window.__TAURI__.core.invoke('switch_claude_tab_with_url', { tab, url: tabUrl });
+                    await delay(100);
                 }
-                
-                // Запускаем монитор генерации
-                startGenerationMonitor();
             }
+            
+            // Переключаемся на сохранённый активный таб
+            if (saved?.activeTab) {
+                await window.__TAURI__.core.invoke('switch_claude_tab', { tab: saved.activeTab });
+            }
+            
+            // Восстанавливаем названия табов
+            if (saved?.tabNames) {
+                Object.assign(tabNames, saved.tabNames);
+            }
+            
+            await updateClaudeState();
+            
+            // Ждём следующий frame перед обновлением resizer для гарантии синхронизации DOM
+            await new Promise(resolve => requestAnimationFrame(resolve));
+            updateResizer();
+            
+            // Смещаем workflow на ширину resizer
+            const workflowContainer = getWorkflowContainer();
+            if (workflowContainer) {
+                workflowContainer.style.width = 'calc(100% - 6px)';
+            }
+            
+            // Запускаем монитор генерации
+            startGenerationMonitor();
         }
     } catch (e) {
         console.error('[Claude] Failed to restore state:', e);
@@ -1319,9 +1325,6 @@ function initClaudeHandlers() {
     // Ресайзер
     createResizer();
     
-    // Восстанавливаем сохранённое состояние
-    restoreClaudeState();
-    
     // Обновляем UI сразу
     updateClaudeUI();
     
@@ -1353,6 +1356,7 @@ function getProjectUUIDFromUrl(url) {
  */
 function startProject(uuid, name, ownerTab) {
     ProjectFSM.bind(uuid, name, ownerTab, activeClaudeTab);
+    registerProjectInBackend(uuid, name, ownerTab);
     
     // Скрываем кнопку "Продолжить" (если была видна)
     hideContinueButton();
@@ -1369,6 +1373,24 @@ function startProject(uuid, name, ownerTab) {
     showToast(`Проект "${name}" привязан`);
 }
 
+/**
+ * Сохраняет проект в реестре Rust (имя для лога архивов, URL чатов)
+ * keyword/GEO берутся из скрапера вкладки-владельца
+ */
+function registerProjectInBackend(uuid, name, ownerTab) {
+    const items = getAllTabs()[ownerTab]?.items || [];
+    const scraper = items.find(i => i.type === 'scraper' && i.keyword);
+    const keyword = scraper ? resolveMarkersToText(scraper.keyword, currentLanguage, currentCountry) : null;
+    window.__TAURI__.core.invoke('register_project', {
+        uuid,
+        name,
+        claudeTab: activeClaudeTab,
+        promptTab: ownerTab != null ? String(ownerTab) : null,
+        keyword,
+        geo: currentCountry || currentLanguage || null
+    }).catch(() => {});
+}
+
 /**
  * Завершает привязку к проекту (через FSM)
  */
@@ -1510,15 +1532,7 @@ function initProjectUrlTracking() {
         // Переинжектим UI helpers после полной загрузки страницы
         if (tab) {
             injectGenerationMonitor(tab).catch(() => {});
-            
-            // Синхронизируем auto-continue в загрузившийся таб
-            try {
-                const acEnabled = getSettings().autoContinue === true;
-                // Ставим pending-флаг + пробуем вызвать setEnabled. Если IIFE
-                // автоконтинью ещё не успел создать window._ac (гонка с DOMContentLoaded),
-                // флаг подхватится при инициализации IIFE.
-                evalInClaude(tab, `window._acWantEnabled=${!!acEnabled};if(window._ac)window._ac.setEnabled(${!!acEnabled})`);
-            } catch(e) {}
+            // Auto-continue: настройки пушит Rust из on_page_load
         }
         
         // FSM: валидируем URL для привязки к проекту
@@ -1588,11 +1602,14 @@ function initProjectUrlTracking() {
 // ═══════════════════════════════════════════════════════════════════════════
 
 /**
- * Загрузить файл в knowledge активного проекта через Claude API
+ * Загрузить файл в knowledge активного проекта
+ * 
+ * Загрузка идёт через Rust (upload_project_knowledge): содержимое хэшируется,
+ * и файл, который уже есть в проекте, повторно не загружается.
  * 
  * @param {string} filePath - путь к файлу на диске
  * @param {string} filename - имя файла
- * @returns {Promise<{success: boolean, error?: string}>}
+ * @returns {Promise<{success: boolean, skipped?: boolean, error?: string}>}
  */
 async function uploadToProjectKnowledge(filePath, filename) {
     // Проверяем наличие активного проекта
@@ -1603,74 +1620,26 @@ async function uploadToProjectKnowledge(filePath, filename) {
     
     const claudeTab = ProjectFSM.claudeTab || activeClaudeTab;
     
-    // Получаем org_id
-    const orgId = await getOrganizationId(claudeTab);
-    if (!orgId) {
-        return { success: false, error: 'No organization ID' };
-    }
-    
-    // Читаем файл через Rust
-    let fileData;
+    let result;
     try {
-        fileData = await window.__TAURI__.core.invoke('read_file_for_attachment', { path: filePath });
+        result = await window.__TAURI__.core.invoke('upload_project_knowledge', {
+            projectUuid,
+            path: filePath,
+            fileName: filename,
+            tab: claudeTab
+        });
     } catch (e) {
-        return { success: false, error: `Read failed: ${e}` };
+        return { success: false, error: String(e) };
     }
     
-    // Загружаем в knowledge через CDP eval + fetch
-    // Декодируем base64 в текст на стороне JS (файл маркдаун — текстовый)
-    const script = `
-        (async function() {
-            try {
-                const base64 = ${JSON.stringify(fileData.data)};
-                const binary = atob(base64);
-                const content = decodeURIComponent(escape(binary));
-                
-                const response = await fetch(
-                    '/api/organizations/${orgId}/projects/${projectUuid}/docs',
-                    {
-                        method: 'POST',
-                        credentials: 'include',
-                        headers: { 'Content-Type': 'application/json' },
-                        body: JSON.stringify({
-                            file_name: ${JSON.stringify(filename)},
-                            content: content
-                        })
-                    }
-                );
-                
-                if (!response.ok) {
-                    const text = await response.text().catch(() => '');
-                    return { success: false, status: response.status, error: text };
-                }
-                
-                return { success: true };
-            } catch (e) {
-                return { success: false, error: e.message };
-            }
-        })();
-    `;
-    
+    // Удаляем файл с диска (содержимое уже в knowledge)
     try {
-        const resultStr = await cdpEval(claudeTab, script, {
-            timeoutType: 'slow', maxRetries: 2
-        });
-        const result = JSON.parse(resultStr);
-        
-        if (result?.success) {
-            // Удаляем файл с диска
-            try {
-                await window.__TAURI__.core.invoke('delete_download', { filePath });
-            } catch (e) {
-                // Не критично — файл уже в knowledge
-            }
-            return { success: true };
-        }
-        
-        return { success: false, error: result?.error || `HTTP ${result?.status}` };
+        await window.__TAURI__.core.invoke('delete_download', { filePath });
     } catch (e) {
-        return { success: false, error: `CDP failed: ${e}` };
+        // Не критично — файл уже в knowledge
     }
+    
+    return { success: true, skipped: result.skipped };
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -1878,6 +1847,7 @@ async function onScrapeComplete(scraperItem) {
 
 // Экспорт
 window.initClaudeHandlers = initClaudeHandlers;
+window.restoreClaudeState = restoreClaudeState;
 window.sendNodeToClaude = sendNodeToClaude;
 window.abortSendToClaude = abortSendToClaude;
 window.SendCheckpoint = SendCheckpoint;
@@ -180,6 +180,10 @@ const ProjectFSM = {
         
         const projectName = this._data?.name || 'Проект';
         
+        if (this._data?.uuid) {
+            window.__TAURI__?.core?.invoke('finish_project', { uuid: this._data.uuid }).catch(() => {});
+        }
+        
         // Анимация кнопки
         const btn = document.getElementById('finish-project-btn');
         if (btn) {
@@ -239,6 +243,11 @@ const ProjectFSM = {
         
         const isDefinitelyAway = isDifferentProject || isHomePage;
         
+        // Чат внутри проекта — в реестр проектов (повторы Rust игнорирует)
+        if (isInChat && this._state === 'bound') {
+            window.__TAURI__?.core?.invoke('add_project_chat', { uuid: this._data.uuid, url: cleanUrl }).catch(() => {});
+        }
+        
         if (this._state === 'bound' && !isOnProject && !isInChat && isDefinitelyAway) {
             this.detach();
         } else if (this._state === 'detached' && isOnProject) {
@@ -383,8 +392,8 @@ async function saveClaudeSettings() {
         // Обновляем URL всех табов перед сохранением
         await updateAllTabUrls();
         
+        // Видимость панели хранит бэкенд (window_state.json)
         const claudeSettings = {
-            visible: isClaudeVisible,
             activeTab: activeClaudeTab,
             panelRatio: panelRatio,
             tabUrls: tabUrls,
@@ -132,13 +132,13 @@ function setupDownloadListeners() {
     });
     
     window.__TAURI__.event.listen('download-finished', async (event) => {
-        const { filename, tab, url, file_path } = event.payload;
+        const { filename, tab, url, file_path, deduplicated } = event.payload;
         
         // MD файлы в активном проекте → в knowledge (без промежуточного тоста "Скачан")
         const isKnowledgeUpload = filename.toLowerCase().endsWith('.md') && file_path && isProjectActive();
         
         if (!isKnowledgeUpload) {
-            showToast(`✅ Скачан: ${filename}`, 3000);
+            showToast(deduplicated ? `♻️ Уже скачан: ${filename}` : `✅ Скачан: ${filename}`, 3000);
         }
         
         // Извлекаем префикс из названия текущей вкладки
@@ -164,7 +164,9 @@ function setupDownloadListeners() {
         if (isKnowledgeUpload) {
             try {
                 const result = await uploadToProjectKnowledge(file_path, filename);
-                if (result.success) {
+                if (result.success && result.skipped) {
+                    showToast(`📎 ${filename}: уже в knowledge`, 3500);
+                } else if (result.success) {
                     showToast(`📎 ${filename} → knowledge ✓`, 3500);
                 } else {
                     showToast(`⚠️ ${filename}: knowledge upload failed`, 4000);
@@ -190,7 +192,9 @@ function setupDownloadListeners() {
     });
     
     window.__TAURI__.event.listen('download-failed', (event) => {
-        showToast(`❌ Ошибка загрузки: ${event.payload}`, 3000);
+        const { filename, reason } = event.payload;
+        const details = reason && reason !== 'unknown' ? ` (${reason})` : '';
+        showToast(`❌ Ошибка загрузки: ${filename}${details}`, 3000);
     });
 }
 
@@ -1295,16 +1299,24 @@ function initAdvancedSettings() {
     document.getElementById('export-diagnostics-btn')?.addEventListener('click', async () => {
         try {
             if (window.__TAURI__) {
+                showToast('Собираем диагностику…');
                 const path = await window.__TAURI__.core.invoke('export_diagnostics');
                 showToast('Экспортировано: ' + path.split(/[/\\]/).pop());
             }
         } catch (e) {
-            const msg = e?.toString() || '';
-            if (msg.includes('пуст')) {
-                showToast('Лог диагностики пуст');
-            } else {
-                showToast('Ошибка экспорта');
+            showToast('Ошибка экспорта');
+        }
+    });
+    
+    // Таймлайн этапов текущей сессии
+    document.getElementById('export-timeline-btn')?.addEventListener('click', async () => {
+        try {
+            if (window.__TAURI__) {
+                const path = await window.__TAURI__.core.invoke('export_metrics_timeline');
+                showToast('Экспортировано: ' + path.split(/[/\\]/).pop());
             }
+        } catch (e) {
+            showToast('Ошибка экспорта');
         }
     });
 }
@@ -1362,22 +1374,6 @@ function initArchiveLogHandlers() {
         await showArchiveLogModal();
     });
     
-    document.getElementById('export-diagnostics-btn')?.addEventListener('click', async () => {
-        try {
-            if (window.__TAURI__) {
-                const path = await window.__TAURI__.core.invoke('export_diagnostics');
-                showToast(`Экспортировано: ${path.split(/[/\\]/).pop()}`);
-            }
-        } catch (e) {
-            const msg = e?.toString() || '';
-            if (msg.includes('пуст')) {
-                showToast('Лог диагностики пуст');
-            } else {
-                showToast('Не удалось экспортировать');
-            }
-        }
-    });
-    
     document.getElementById('clear-archive-log-btn')?.addEventListener('click', async function() {
         const btn = this;
         
@@ -1450,6 +1446,10 @@ function initModalHandlers() {
         if (e.target.id === 'reset-modal') hideResetModal();
     });
     
+    // Отчёт о падении
+    document.getElementById('include-crash-btn')?.addEventListener('click', () => resolvePendingCrashes(true));
+    document.getElementById('dismiss-crash-btn')?.addEventListener('click', () => resolvePendingCrashes(false));
+    
     // Импорт
     document.getElementById('import-confirm-btn')?.addEventListener('click', () => hideImportConfirm(true));
     document.getElementById('import-cancel-btn')?.addEventListener('click', () => hideImportConfirm(false));
@@ -1624,6 +1624,41 @@ function initApp() {
         window.__TAURI__.event.listen('auto-continue-toast', (event) => {
             if (event.payload) showToast(event.payload, 3000);
         });
+        
+        // Состояние кнопок auto-continue — из Rust (источник правды);
+        // при первом запуске Rust-настройки создаются из settings.autoContinue
+        const settings = getSettings();
+        window.__TAURI__.core.invoke('migrate_auto_continue_config', { enabled: !!settings.autoContinue }).then(config => {
+            const enabled = (config.enabled || []).some(Boolean);
+            if (settings.autoContinue !== enabled) {
+                settings.autoContinue = enabled;
+                saveSettings(settings);
+            }
+            updateAutoContinueButtons(enabled);
+        }).catch(() => {});
+    }
+    
+    // 2.2. Бюджет usage: отправка остановлена у лимита
+    if (window.__TAURI__?.event?.listen) {
+        window.__TAURI__.event.listen('usage-budget-exceeded', (event) => {
+            const p = event.payload || {};
+            const windowName = p.window === 'weekly' ? 'недельный' : '5-часовой';
+            const action = p.mode === 'defer' ? 'отправка отложена' : 'отправка остановлена';
+            showToast(`Чат ${p.tab}: ${windowName} лимит ${Math.round(p.utilization)}% ≥ ${Math.round(p.threshold)}%, ${action}`, 6000);
+        });
+        window.__TAURI__.event.listen('usage-budget-resumed', (event) => {
+            showToast(`Чат ${event.payload?.tab}: usage в пределах бюджета, отправка продолжена`, 3000);
+        });
+    }
+    
+    // 2.3. Синхронизация папки с knowledge проекта
+    if (window.__TAURI__?.event?.listen) {
+        window.__TAURI__.event.listen('knowledge-sync-progress', (event) => {
+            const p = event.payload || {};
+            if (p.status === 'uploaded') showToast(`📎 ${p.file_name} → knowledge`, 2000);
+            else if (p.status === 'deleted') showToast(`📎 ${p.file_name}: удалён из knowledge`, 2000);
+            else if (p.status === 'error') showToast(`⚠️ Knowledge sync: ${p.file_name}: ${p.error}`, 4000);
+        });
     }
     
     // 2.5. Инициализируем гибридное хранение (file + localStorage)
@@ -1753,7 +1788,8 @@ function initApp() {
     
     // 10.5. Инициализация Claude WebView (только если не оффлайн)
     if (!isOfflineMode() && window.__TAURI__) {
-        window.__TAURI__.core.invoke('init_claude_webviews').catch(e => {
+        // Состояние восстанавливается после создания webview (видимость — из бэкенда)
+        window.__TAURI__.core.invoke('init_claude_webviews').then(() => restoreClaudeState()).catch(e => {
             console.warn('[Init] Claude webviews init failed:', e);
         });
         
@@ -1808,6 +1844,35 @@ function initApp() {
             }
         }
     }, 1000);
+    
+    // 14. Отчёты о падениях прошлого запуска
+    checkPendingCrashes();
+}
+
+/**
+ * Предлагает добавить отчёты о падениях прошлого запуска в диагностику
+ */
+async function checkPendingCrashes() {
+    try {
+        const crashes = await window.__TAURI__.core.invoke('list_pending_crashes');
+        if (crashes.length > 0) showCrashModal(crashes);
+    } catch (e) {
+        console.warn('[Init] Crash reports check failed:', e);
+    }
+}
+
+/**
+ * Решение по отчётам о падениях: добавить в диагностику или удалить
+ * @param {boolean} include
+ */
+async function resolvePendingCrashes(include) {
+    hideCrashModal();
+    try {
+        await window.__TAURI__.core.invoke('resolve_pending_crashes', { include });
+        showToast(include ? '✅ Отчёт добавлен в диагностику' : '🗑️ Отчёт удалён', 2000);
+    } catch (e) {
+        showToast(`✗ Ошибка: ${String(e).slice(0, 50)}`, 3000);
+    }
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
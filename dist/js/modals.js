@@ -48,6 +48,27 @@ function showResetModal() {
  */
 const hideResetModal = () => hideModal('reset-modal');
 
+// --- МОДАЛЬНОЕ ОКНО ОТЧЁТА О ПАДЕНИИ ---
+
+/**
+ * Показывает модальное окно с отчётами о падениях прошлого запуска
+ * @param {Array} crashes - отчёты из list_pending_crashes
+ */
+function showCrashModal(crashes) {
+    closeAllModals();
+    const modal = document.getElementById('crash-modal');
+    const detailsEl = document.getElementById('crash-modal-details');
+    const last = crashes[crashes.length - 1];
+    
+    if (detailsEl && last) {
+        const more = crashes.length > 1 ? ` (+${crashes.length - 1})` : '';
+        detailsEl.textContent = `${last.timestamp}: ${last.message}${more}`;
+    }
+    if (modal) modal.classList.add('open');
+}
+
+const hideCrashModal = () => hideModal('crash-modal');
+
 // --- МОДАЛЬНОЕ ОКНО ПОДТВЕРЖДЕНИЯ РЕЖИМА РЕДАКТИРОВАНИЯ ---
 
 async function showEditModeConfirmModal() {
@@ -9,6 +9,7 @@
  *   - fetchRemoteManifest() - загрузка манифеста
  *   - fetchRemoteTab(tabId) - загрузка данных вкладки
  *   - checkForPromptsUpdate() - проверка обновлений
+ *   - syncRemoteSelectors(manifest) - обновление селекторов Claude.ai
  *   - applyPromptsUpdate() - применение обновлений
  *   - initializeRemotePrompts() - инициализация при первом запуске
  *   - showPromptsUpdateModal() - показать модалку обновлений
@@ -124,6 +125,30 @@ function cacheManifest(manifest) {
     localStorage.setItem(REMOTE_PROMPTS_CONFIG.STORAGE.LAST_CHECK, Date.now().toString());
 }
 
+/**
+ * Передаёт в Rust новый selectors.json из фида (если в манифесте версия новее)
+ * Rust валидирует схему, сохраняет override и переинжектит в Claude табы
+ * @param {Object} manifest
+ */
+async function syncRemoteSelectors(manifest) {
+    const remoteVersion = manifest?.selectors?.version;
+    if (!remoteVersion || !window.__TAURI__) return;
+    
+    try {
+        const info = await window.__TAURI__.core.invoke('get_selectors_info');
+        if (info.version === remoteVersion) return;
+        
+        const url = `${REMOTE_PROMPTS_CONFIG.BASE_URL}/selectors.json`;
+        const response = await fetchWithTimeout(url);
+        if (!response.ok) {
+            throw new Error(`HTTP ${response.status}: ${response.statusText}`);
+        }
+        await window.__TAURI__.core.invoke('update_selectors', { json: await response.text() });
+    } catch (e) {
+        console.error('[RemotePrompts] Failed to sync selectors:', e);
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // ПРОВЕРКА ОБНОВЛЕНИЙ
 // ═══════════════════════════════════════════════════════════════════════════
@@ -142,6 +167,9 @@ async function checkForPromptsUpdate(showModal = false) {
         return { hasUpdates: false, newTabs: [], updatedTabs: [], removedTabs: [] };
     }
     
+    // Селекторы обновляются независимо от вкладок (без модалки)
+    syncRemoteSelectors(remoteManifest);
+    
     const cachedManifest = getCachedManifest();
     const newTabs = [];
     const updatedTabs = [];
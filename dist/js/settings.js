@@ -875,15 +875,12 @@ function setAutoContinue(enabled) {
 
 /**
  * Синхронизировать auto-continue во все Claude WebView
+ * Настройки хранит Rust (autocontinue_settings.json) и сам пушит их в табы
  * @param {boolean} enabled
  */
 function syncAutoContinueToWebViews(enabled) {
-    // Ставим pending-флаг + вызываем setEnabled. Флаг перехватит IIFE автоконтинью
-    // если он ещё не успел создать window._ac (при свежей загрузке таба).
-    const script = `window._acWantEnabled=${!!enabled};if(window._ac)window._ac.setEnabled(${!!enabled})`;
-    for (let tab = 1; tab <= 3; tab++) {
-        try { evalInClaude(tab, script); } catch(e) {}
-    }
+    window.__TAURI__?.core.invoke('set_auto_continue_enabled', { enabled: !!enabled })
+        .catch(e => console.warn('[AutoContinue] sync failed:', e));
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -119,14 +119,14 @@ window.generateItemId = generateItemId;
 
 /**
  * Записать событие в лог диагностики (fire-and-forget)
- * @param {string} eventType - тип события (selector_broken, cdp_timeout, send_error, storage_error)
- * @param {Object} details - объект с деталями
+ * @param {string} eventType - тип события (selector_broken, cdp_timeout, send_error, storage)
+ * @param {Object} details - объект с деталями (`tab` — номер Claude таба)
  */
 function writeDiagnostic(eventType, details = {}) {
     if (!window.__TAURI__?.core?.invoke) return;
     window.__TAURI__.core.invoke('write_diagnostic', {
         eventType,
-        details: JSON.stringify(details)
+        details
     }).catch(() => {}); // Молча — диагностика не должна ломать основной flow
 }
 
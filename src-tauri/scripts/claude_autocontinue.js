@@ -3,12 +3,15 @@
 // No monkey-patching, no execCommand, no extension APIs.
 // Uses button.click() — same as APM's sendButton click in claude.rs.
 // Toast via Tauri emit → Main WebView showToast().
+// Config (enabled, maxPerChat, delayMs, extraPhrases) is pushed from Rust
+// on page load via window._acConfig; each click is registered through
+// register_auto_continue, which enforces the per-chat cap.
 
 (function() {
     'use strict';
     if (window._ac) return;
 
-    var LIMIT_PHRASES = [
+    var BASE_PHRASES = [
         'tool-use limit',
         'tool use limit',
         'reached its tool',
@@ -16,6 +19,7 @@
         'tool call limit',
         'continuation needed'
     ];
+    var LIMIT_PHRASES = BASE_PHRASES.slice();
 
     var MSG_SELECTORS = [
         '[data-testid="assistant-message"]',
@@ -27,8 +31,11 @@
     // ── State ──────────────────────────────────────────────────────────
     window._ac = {
         enabled: false,
+        maxPerChat: 0,
+        delayMs: 1500,
         _timer: null,
-        _pending: false
+        _pending: false,
+        _capped: {}
     };
 
     // ── Detection ──────────────────────────────────────────────────────
@@ -75,6 +82,20 @@
         return Math.floor(Math.random() * (max - min + 1)) + min;
     }
 
+    // null вне чата (/new, /project/…) — там автопродолжение не работает
+    function chatId() {
+        var m = location.pathname.match(/\/chat\/([^\/?#]+)/);
+        return m ? m[1] : null;
+    }
+
+    // Rust считает продолжения по чату и отказывает по лимиту.
+    // Без IPC (нет _inv) — кликаем как раньше, без лимита.
+    function registerContinue(chat) {
+        if (!window._inv) return Promise.resolve({ allowed: true, count: 0, max: 0 });
+        return window._inv('register_auto_continue', { tab: window._t || 0, chatId: chat })
+            .catch(function() { return { allowed: true, count: 0, max: 0 }; });
+    }
+
     function emitToast(message) {
         if (window._emit) {
             try { window._emit('auto-continue-toast', message); } catch(e) {}
@@ -85,24 +106,41 @@
 
     function poll() {
         if (!window._ac.enabled || window._ac._pending) return;
+        var current = chatId();
+        if (!current || window._ac._capped[current]) return;
 
         var btn = detectToolUseLimit();
         if (!btn) return;
 
         window._ac._pending = true;
 
-        var clickDelay = randomInt(1500, 3000);
+        var clickDelay = randomInt(window._ac.delayMs, window._ac.delayMs * 2);
         setTimeout(function() {
             if (!window._ac.enabled) {
                 window._ac._pending = false;
                 return;
             }
             var freshBtn = detectToolUseLimit();
-            if (freshBtn) {
-                freshBtn.click();
-                emitToast('Auto-continue \u2022 Chat ' + (window._t || '?'));
+            if (!freshBtn) {
+                window._ac._pending = false;
+                return;
             }
-            window._ac._pending = false;
+            var chat = chatId();
+            if (!chat) {
+                window._ac._pending = false;
+                return;
+            }
+            registerContinue(chat).then(function(res) {
+                if (res && res.allowed) {
+                    freshBtn.click();
+                    var counter = res.max ? ' (' + res.count + '/' + res.max + ')' : '';
+                    emitToast('Auto-continue \u2022 Chat ' + (window._t || '?') + counter);
+                } else {
+                    window._ac._capped[chat] = true;
+                    emitToast('Auto-continue: limit reached \u2022 Chat ' + (window._t || '?'));
+                }
+                window._ac._pending = false;
+            });
         }, clickDelay);
     }
 
@@ -134,9 +172,21 @@
         }
     };
 
-    // Pending-flag: если Main WebView уже прислал команду включения ДО того как
-    // этот IIFE успел выполниться (race c claude-page-loaded), подхватываем её
-    if (window._acWantEnabled === true) {
+    window._ac.applyConfig = function(cfg) {
+        cfg = cfg || {};
+        var extra = Array.isArray(cfg.extraPhrases) ? cfg.extraPhrases : [];
+        LIMIT_PHRASES = BASE_PHRASES.concat(extra.map(function(p) { return String(p).toLowerCase(); }));
+        window._ac.maxPerChat = cfg.maxPerChat || 0;
+        if (cfg.delayMs > 0) window._ac.delayMs = cfg.delayMs;
+        window._ac._capped = {};
+        window._ac.setEnabled(!!cfg.enabled);
+    };
+
+    // Pending-config: если Rust уже прислал настройки ДО того как
+    // этот IIFE успел выполниться (race c on_page_load), подхватываем их
+    if (window._acConfig) {
+        window._ac.applyConfig(window._acConfig);
+    } else if (window._acWantEnabled === true) {
         window._ac.setEnabled(true);
     }
 
@@ -539,6 +539,19 @@
 		usageResetMs.five_hour = normalized.five_hour?.resets_at ? Date.parse(normalized.five_hour.resets_at) : null;
 		usageResetMs.seven_day = normalized.seven_day?.resets_at ? Date.parse(normalized.seven_day.resets_at) : null;
 		ui.setUsage(normalized);
+		recordUsageSnapshot(normalized);
+	}
+
+	// Local usage history in APM (usage_log.jsonl), deduplicated on the Rust side
+	function recordUsageSnapshot(normalized) {
+		if (!window._inv) return;
+		try {
+			window._inv('record_usage_snapshot', {
+				tab: window._t || 0,
+				fiveHour: normalized.five_hour,
+				sevenDay: normalized.seven_day
+			}).catch(() => {});
+		} catch { /* ignore */ }
 	}
 
 	function updateOrgIdIfNeeded(newOrgId) {
@@ -0,0 +1,74 @@
+// === Claude Editor ===
+// Поиск редактора, вставка текста и поиск кнопки Send.
+// Подставляется внутрь скриптов вставки и отправки (commands/claude.rs,
+// commands/send.rs); селекторы — из window._s (selectors.json).
+
+/**
+ * Первый элемент по одному селектору или списку альтернатив
+ * @param {string|string[]|undefined} selectors
+ * @returns {Element|null}
+ */
+function findBySelectors(selectors) {
+    if (!selectors) return null;
+    const arr = Array.isArray(selectors) ? selectors : [selectors];
+    for (const sel of arr) {
+        try {
+            const el = document.querySelector(sel);
+            if (el) return el;
+        } catch(e) {}
+    }
+    return null;
+}
+
+/**
+ * Редактор ProseMirror в поле ввода
+ * @returns {Element|null}
+ */
+function findEditor() {
+    return document.querySelector(window._s?.input?.proseMirror || '.ProseMirror');
+}
+
+/**
+ * Вставляет текст штатным insertContent() редактора,
+ * без него — абзацем с событием input
+ * @param {Element} pm - редактор ProseMirror
+ * @param {string} text
+ */
+function insertIntoEditor(pm, text) {
+    pm.focus();
+    const editor = pm.editor;
+    if (editor && editor.commands && typeof editor.commands.insertContent === 'function') {
+        editor.commands.insertContent({ type: 'text', text: text });
+    } else {
+        const p = document.createElement('p');
+        p.textContent = text;
+        pm.appendChild(p);
+        pm.dispatchEvent(new Event('input', { bubbles: true }));
+    }
+}
+
+/**
+ * Кнопка Send: по селекторам, иначе — последняя кнопка с иконкой
+ * в правой половине поля ввода (кроме кнопки прикрепления).
+ * Может быть disabled — это проверяет вызывающий.
+ * @param {Element} pm - редактор ProseMirror
+ * @returns {Element|null}
+ */
+function findSendButton(pm) {
+    const SEL = window._s;
+    const btn = findBySelectors(SEL?.input?.sendButton);
+    if (btn) return btn;
+    const area = pm.closest('fieldset') || pm.closest('form') || pm.parentElement?.parentElement;
+    if (!area) return null;
+    const attachPattern = SEL?.attachments?.attachButtonAriaPattern || 'attach';
+    const pmRect = pm.getBoundingClientRect();
+    let found = null;
+    for (const b of area.querySelectorAll('button')) {
+        const isAttach = b.getAttribute('aria-label')?.toLowerCase().includes(attachPattern);
+        if (b.querySelector('svg') && !isAttach) {
+            const rect = b.getBoundingClientRect();
+            if (rect.left > pmRect.left + pmRect.width / 2) found = b;
+        }
+    }
+    return found;
+}
@@ -8,15 +8,17 @@
 use std::fs;
 use tauri::AppHandle;
 
+use crate::commands::logs::compact_logs;
 use crate::downloads::paths::{get_app_data_dir, get_archive_log_path, get_downloads_settings_path, get_diagnostics_log_path};
+use crate::utils::logger;
 use crate::utils::platform::open_directory_in_system;
 
 /// Сбрасывает данные приложения (кроме логов и настроек загрузок)
 ///
 /// Удаляет папку данных приложения, но сохраняет:
-/// - `archive_log.json` — история скачанных файлов
+/// - `archive_log.jsonl` — история скачанных файлов
 /// - `downloads_settings.json` — настройки пути загрузок
-/// - `diagnostics.json` — лог диагностики
+/// - `diagnostics.jsonl` — лог диагностики
 ///
 /// # Returns
 /// * `Ok(())` — данные успешно сброшены
@@ -31,7 +33,11 @@ pub fn reset_app_data() -> Result<(), String> {
         return Ok(());
     }
     
-    // Сохраняем archive_log.json перед удалением
+    // Сжимаем логи: ротированные части сливаются в основные файлы
+    compact_logs();
+    logger::close();
+    
+    // Сохраняем archive_log.jsonl перед удалением
     let archive_log_path = get_archive_log_path();
     let archive_log_backup = archive_log_path
         .as_ref()
@@ -45,7 +51,7 @@ pub fn reset_app_data() -> Result<(), String> {
         .filter(|p| p.exists())
         .and_then(|p| fs::read(p).ok());
     
-    // Сохраняем diagnostics.json перед удалением
+    // Сохраняем diagnostics.jsonl перед удалением
     let diagnostics_log_path = get_diagnostics_log_path();
     let diagnostics_log_backup = diagnostics_log_path
         .as_ref()
@@ -61,9 +67,9 @@ pub fn reset_app_data() -> Result<(), String> {
                 let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
                 
                 // Не удаляем защищённые файлы
-                if filename != "archive_log.json" 
+                if filename != "archive_log.jsonl" 
                     && filename != "downloads_settings.json"
-                    && filename != "diagnostics.json" 
+                    && filename != "diagnostics.jsonl" 
                 {
                     let _ = if path.is_dir() {
                         fs::remove_dir_all(&path)
@@ -75,7 +81,7 @@ pub fn reset_app_data() -> Result<(), String> {
         }
     }
     
-    // Восстанавливаем archive_log.json
+    // Восстанавливаем archive_log.jsonl
     if let (Some(backup), Some(path)) = (archive_log_backup, archive_log_path) {
         let _ = fs::create_dir_all(&app_folder);
         let _ = fs::write(&path, &backup);
@@ -87,7 +93,7 @@ pub fn reset_app_data() -> Result<(), String> {
         let _ = fs::write(&path, &backup);
     }
     
-    // Восстанавливаем diagnostics.json
+    // Восстанавливаем diagnostics.jsonl
     if let (Some(backup), Some(path)) = (diagnostics_log_backup, diagnostics_log_path) {
         let _ = fs::create_dir_all(&app_folder);
         let _ = fs::write(&path, &backup);
@@ -6,6 +6,8 @@
 //! - Прикрепления файлов к Claude через инжекцию в input[type="file"]
 
 use std::fs;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Manager};
 use base64::Engine;
 
@@ -13,23 +15,22 @@
 use crate::utils::mime::get_mime_type;
 use crate::utils::dimensions::limits::MAX_ATTACHMENT_SIZE;
 use crate::state::UPLOAD_COUNTERS;
+use crate::utils::metrics::{Span, stage};
 
-/// Читает файл и подготавливает для отправки в Claude
+/// Сколько ждать подтверждения загрузки для замера `upload` (секунды)
+const UPLOAD_CONFIRM_TIMEOUT_SECS: u64 = 60;
+/// Интервал проверки счётчика загрузок (мс)
+const UPLOAD_CONFIRM_POLL_MS: u64 = 200;
+
+/// Читает файл с проверкой существования и размера (лимит аттачментов)
 ///
-/// # Arguments
-/// * `path` - путь к файлу
-///
-/// # Returns
-/// Структура FileData с именем, MIME-типом и base64 содержимым
+/// Общая часть чтения для аттачментов и загрузок в knowledge проекта.
 ///
 /// # Errors
 /// - Файл не найден
 /// - Файл слишком большой (>50 MB)
 /// - Ошибка чтения
-#[tauri::command]
-pub async fn read_file_for_attachment(path: String) -> Result<FileData, String> {
-    let path = std::path::Path::new(&path);
-    
+pub fn read_attachment_bytes(path: &std::path::Path) -> Result<Vec<u8>, String> {
     // Проверяем существование файла
     if !path.exists() {
         return Err(format!("Файл не найден: {}", path.display()));
@@ -47,9 +48,28 @@ pub async fn read_file_for_attachment(path: String) -> Result<FileData, String>
         ));
     }
     
+    fs::read(path)
+        .map_err(|e| format!("Ошибка чтения файла: {}", e))
+}
+
+/// Читает файл и подготавливает для отправки в Claude
+///
+/// # Arguments
+/// * `path` - путь к файлу
+///
+/// # Returns
+/// Структура FileData с именем, MIME-типом и base64 содержимым
+///
+/// # Errors
+/// - Файл не найден
+/// - Файл слишком большой (>50 MB)
+/// - Ошибка чтения
+#[tauri::command]
+pub async fn read_file_for_attachment(path: String) -> Result<FileData, String> {
+    let path = std::path::Path::new(&path);
+    
     // Читаем файл
-    let data = fs::read(path)
-        .map_err(|e| format!("Ошибка чтения файла: {}", e))?;
+    let data = read_attachment_bytes(path)?;
     
     // Определяем MIME-тип
     let mime_type = get_mime_type(path.extension().and_then(|e| e.to_str())).to_string();
@@ -122,6 +142,9 @@ pub async fn write_temp_file(
 /// Все файлы отправляются одним eval — последовательная обработка
 /// гарантирована внутри скрипта (await между каждым файлом).
 ///
+/// Замер `upload` длится до подтверждения загрузки всех файлов
+/// перехватчиком `upload-file` (счётчик таба, только Windows).
+///
 /// # Arguments
 /// * `app` - handle приложения
 /// * `tab` - номер таба Claude
@@ -135,6 +158,9 @@ pub async fn attach_files_batch(
     if paths.is_empty() {
         return Ok(());
     }
+    // Счётчик загрузок ведёт только нативный перехватчик Windows
+    let span = (cfg!(windows) && (1..=3).contains(&tab))
+        .then(|| Span::start(stage::UPLOAD, Some(tab)));
     
     // Собираем данные всех файлов
     let mut files_js = Vec::new();
@@ -142,7 +168,7 @@ pub async fn attach_files_batch(
         let file_path = std::path::Path::new(path_str);
         
         if !file_path.exists() {
-            eprintln!("[Attach] File not found: {}", file_path.display());
+            log::warn!("Attachment file not found: {}", file_path.display());
             continue;
         }
         
@@ -217,11 +243,32 @@ pub async fn attach_files_batch(
     let webview = app.get_webview(&label)
         .ok_or_else(|| format!("Webview {} not found", label))?;
     
+    let expected = UPLOAD_COUNTERS.get((tab as usize).wrapping_sub(1))
+        .map(|counter| counter.load(Ordering::SeqCst) + files_js.len() as u32);
+    
     webview.eval(&script).map_err(|e| e.to_string())?;
+    if let (Some(span), Some(expected)) = (span, expected) {
+        tauri::async_runtime::spawn(confirm_upload(span, tab, expected));
+    }
     
     Ok(())
 }
 
+/// Завершает замер `upload`, когда счётчик таба дошёл до `expected`
+/// (ошибка — если не дошёл за UPLOAD_CONFIRM_TIMEOUT_SECS)
+async fn confirm_upload(span: Span, tab: u8, expected: u32) {
+    let counter = &UPLOAD_COUNTERS[(tab - 1) as usize];
+    let start = Instant::now();
+    while counter.load(Ordering::SeqCst) < expected {
+        if start.elapsed() >= Duration::from_secs(UPLOAD_CONFIRM_TIMEOUT_SECS) {
+            span.end(false);
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(UPLOAD_CONFIRM_POLL_MS)).await;
+    }
+    span.end(true);
+}
+
 /// Прикрепляет один файл к сообщению Claude (обратная совместимость)
 #[tauri::command]
 pub async fn attach_file_to_claude(
@@ -0,0 +1,370 @@
+//! Auto-Continue: настройки, лимиты и статистика
+//!
+//! Сам поллинг DOM и нажатие Continue живут в `scripts/claude_autocontinue.js`.
+//! Rust хранит настройки (вкл/выкл по табам, лимит на чат, задержка,
+//! дополнительные фразы) и пушит их в табы при загрузке страницы.
+//!
+//! Перед каждым нажатием скрипт вызывает `register_auto_continue` —
+//! Rust ведёт счётчик продолжений по чатам и отказывает, когда лимит
+//! исчерпан (с событием `auto-continue-limit-reached`). Счётчики
+//! сохраняются в `autocontinue_counts.json` и переживают перезапуск;
+//! хранятся последние MAX_AUTO_CONTINUE_CHATS чатов.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Webview};
+
+use crate::commands::logs::write_diagnostic;
+use crate::downloads::paths::get_app_data_dir;
+use crate::state::{AUTO_CONTINUE_CONFIG, AUTO_CONTINUE_COUNTS};
+use crate::types::{AutoContinueConfig, DiagnosticEvent};
+use crate::utils::dimensions::limits::MAX_AUTO_CONTINUE_CHATS;
+
+/// Границы задержки перед нажатием Continue (мс)
+const MIN_DELAY_MS: u64 = 500;
+const MAX_DELAY_MS: u64 = 60_000;
+
+/// Решение по запросу на продолжение
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoContinueDecision {
+    pub allowed: bool,
+    /// Количество продолжений в чате (с учётом текущего, если разрешено)
+    pub count: u32,
+    /// Лимит на чат (0 = без ограничений)
+    pub max: u32,
+}
+
+/// Статистика продолжений по чату
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoContinueChatStats {
+    pub chat_id: String,
+    pub count: u32,
+}
+
+/// Путь к файлу настроек Auto-Continue
+fn get_autocontinue_settings_path() -> Option<PathBuf> {
+    get_app_data_dir().map(|dir| dir.join("autocontinue_settings.json"))
+}
+
+/// Путь к файлу счётчиков продолжений по чатам
+fn get_autocontinue_counts_path() -> Option<PathBuf> {
+    get_app_data_dir().map(|dir| dir.join("autocontinue_counts.json"))
+}
+
+/// Читает настройки из файла (None — файла нет или он повреждён)
+fn read_config(path: &Path) -> Option<AutoContinueConfig> {
+    fs::read_to_string(path).ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .map(normalize_config)
+}
+
+/// Читает счётчики из файла (пустые — если файла нет или он повреждён)
+///
+/// Старый формат — объект `{chat_id: count}` без порядка использования.
+fn read_counts(path: &Path) -> Vec<(String, u32)> {
+    let Ok(content) = fs::read_to_string(path) else { return Vec::new() };
+    serde_json::from_str(&content)
+        .or_else(|_| serde_json::from_str::<HashMap<String, u32>>(&content).map(|map| map.into_iter().collect()))
+        .unwrap_or_default()
+}
+
+/// Записывает значение в JSON-файл (с созданием папки)
+fn write_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| e.to_string())
+}
+
+/// Загружает настройки и счётчики Auto-Continue в состояние (при старте)
+pub fn load_auto_continue_config() {
+    let config = get_autocontinue_settings_path()
+        .and_then(|path| read_config(&path))
+        .unwrap_or_else(|| normalize_config(AutoContinueConfig::default()));
+
+    if let Ok(mut state) = AUTO_CONTINUE_CONFIG.lock() {
+        *state = config;
+    }
+
+    if let Some(path) = get_autocontinue_counts_path() {
+        if let Ok(mut counts) = AUTO_CONTINUE_COUNTS.lock() {
+            *counts = read_counts(&path);
+        }
+    }
+}
+
+/// Сохраняет настройки Auto-Continue
+fn save_auto_continue_config(config: &AutoContinueConfig) -> Result<(), String> {
+    let path = get_autocontinue_settings_path().ok_or("Cannot get app data dir")?;
+    write_json(&path, config)
+}
+
+/// Сохраняет счётчики продолжений (снимок состояния)
+fn save_auto_continue_counts(counts: &[(String, u32)]) -> Result<(), String> {
+    let path = get_autocontinue_counts_path().ok_or("Cannot get app data dir")?;
+    write_json(&path, counts)
+}
+
+/// Учитывает запрос на продолжение в счётчиках
+///
+/// Чат переносится в конец (последний использованный); при превышении
+/// MAX_AUTO_CONTINUE_CHATS забываются давно не использованные чаты.
+fn register_continue(counts: &mut Vec<(String, u32)>, chat_id: &str, max: u32) -> AutoContinueDecision {
+    let mut count = match counts.iter().position(|(id, _)| id == chat_id) {
+        Some(index) => counts.remove(index).1,
+        None => 0,
+    };
+    let allowed = max == 0 || count < max;
+    if allowed {
+        count += 1;
+    }
+
+    counts.push((chat_id.to_string(), count));
+    if counts.len() > MAX_AUTO_CONTINUE_CHATS {
+        counts.drain(..counts.len() - MAX_AUTO_CONTINUE_CHATS);
+    }
+    AutoContinueDecision { allowed, count, max }
+}
+
+/// Ограничивает задержку и чистит список фраз
+fn normalize_config(mut config: AutoContinueConfig) -> AutoContinueConfig {
+    config.delay_ms = config.delay_ms.clamp(MIN_DELAY_MS, MAX_DELAY_MS);
+    config.extra_phrases = config.extra_phrases.iter()
+        .map(|p| p.trim().to_lowercase())
+        .filter(|p| !p.is_empty())
+        .collect();
+    config.extra_phrases.sort();
+    config.extra_phrases.dedup();
+    config
+}
+
+/// Формирует скрипт применения настроек в табе
+///
+/// `window._acConfig` подхватывается IIFE автоконтинью, если он ещё
+/// не успел выполниться (та же схема, что и `_acWantEnabled`).
+fn build_config_script(config: &AutoContinueConfig, tab: u8) -> String {
+    let tab_config = serde_json::json!({
+        "enabled": config.enabled.get((tab as usize).saturating_sub(1)).copied().unwrap_or(false),
+        "maxPerChat": config.max_per_chat,
+        "delayMs": config.delay_ms,
+        "extraPhrases": config.extra_phrases,
+    });
+    format!(
+        "window._acConfig={cfg};if(window._ac&&window._ac.applyConfig)window._ac.applyConfig(window._acConfig)",
+        cfg = tab_config
+    )
+}
+
+/// Пушит настройки Auto-Continue в webview таба (из on_page_load)
+pub fn push_auto_continue_config(webview: &Webview, tab: u8) {
+    let Ok(config) = AUTO_CONTINUE_CONFIG.lock().map(|c| c.clone()) else { return };
+    let _ = webview.eval(build_config_script(&config, tab));
+}
+
+/// Сохраняет настройки и пушит их во все открытые табы
+fn apply_config(app: &AppHandle, config: AutoContinueConfig) -> Result<AutoContinueConfig, String> {
+    let config = normalize_config(config);
+    save_auto_continue_config(&config)?;
+
+    if let Ok(mut state) = AUTO_CONTINUE_CONFIG.lock() {
+        *state = config.clone();
+    }
+
+    for tab in 1u8..=3 {
+        if let Some(webview) = app.get_webview(&format!("claude_{}", tab)) {
+            let _ = webview.eval(build_config_script(&config, tab));
+        }
+    }
+
+    Ok(config)
+}
+
+/// Получает настройки Auto-Continue
+#[tauri::command]
+pub fn get_auto_continue_config() -> AutoContinueConfig {
+    AUTO_CONTINUE_CONFIG.lock()
+        .map(|c| c.clone())
+        .unwrap_or_default()
+}
+
+/// Одноразовая миграция настроек из фронтенда
+///
+/// До появления `autocontinue_settings.json` флаг жил только в
+/// `settings.autoContinue` (localStorage). Если файла ещё нет — он создаётся
+/// с этим флагом для всех табов; иначе возвращаются сохранённые настройки.
+#[tauri::command]
+pub fn migrate_auto_continue_config(app: AppHandle, enabled: bool) -> Result<AutoContinueConfig, String> {
+    let exists = get_autocontinue_settings_path().is_some_and(|path| path.exists());
+    if exists {
+        return Ok(get_auto_continue_config());
+    }
+
+    let mut config = get_auto_continue_config();
+    config.enabled = [enabled; 3];
+    apply_config(&app, config)
+}
+
+/// Устанавливает настройки Auto-Continue целиком
+///
+/// Задержка ограничивается 500–60000 мс, фразы приводятся к нижнему регистру.
+/// Настройки сразу применяются во всех открытых табах.
+#[tauri::command]
+pub fn set_auto_continue_config(app: AppHandle, config: AutoContinueConfig) -> Result<AutoContinueConfig, String> {
+    apply_config(&app, config)
+}
+
+/// Включает/выключает Auto-Continue
+///
+/// # Arguments
+/// * `enabled` - новое состояние
+/// * `tab` - номер таба (1-3); без него — для всех табов
+#[tauri::command]
+pub fn set_auto_continue_enabled(app: AppHandle, enabled: bool, tab: Option<u8>) -> Result<AutoContinueConfig, String> {
+    let mut config = get_auto_continue_config();
+    match tab {
+        Some(tab) if (1..=3).contains(&tab) => config.enabled[(tab - 1) as usize] = enabled,
+        Some(tab) => return Err(format!("Invalid tab: {}", tab)),
+        None => config.enabled = [enabled; 3],
+    }
+    apply_config(&app, config)
+}
+
+/// Регистрирует продолжение в чате (вызывается из Claude WebView перед кликом)
+///
+/// Если лимит на чат исчерпан — продолжение запрещается и эмитится
+/// `auto-continue-limit-reached` с `{tab, chat_id, count, max}`.
+#[tauri::command]
+pub fn register_auto_continue(app: AppHandle, tab: u8, chat_id: String) -> Result<AutoContinueDecision, String> {
+    let max = get_auto_continue_config().max_per_chat;
+
+    let (decision, snapshot) = {
+        let mut counts = AUTO_CONTINUE_COUNTS.lock().map_err(|e| e.to_string())?;
+        let decision = register_continue(&mut counts, &chat_id, max);
+        let snapshot = decision.allowed.then(|| counts.clone());
+        (decision, snapshot)
+    };
+
+    if let Some(counts) = snapshot {
+        if let Err(e) = save_auto_continue_counts(&counts) {
+            log::warn!("Failed to save auto-continue counts: {}", e);
+        }
+    }
+
+    if !decision.allowed {
+        let payload = serde_json::json!({
+            "tab": tab,
+            "chat_id": chat_id,
+            "count": decision.count,
+            "max": decision.max,
+        });
+        let _ = write_diagnostic(DiagnosticEvent::AutoContinueLimit, payload.clone());
+        let _ = app.emit("auto-continue-limit-reached", payload);
+    }
+
+    Ok(decision)
+}
+
+/// Получает счётчики продолжений по чатам (по убыванию количества)
+#[tauri::command]
+pub fn get_auto_continue_stats() -> Vec<AutoContinueChatStats> {
+    let mut stats: Vec<AutoContinueChatStats> = AUTO_CONTINUE_COUNTS.lock()
+        .map(|counts| counts.iter()
+            .map(|(chat_id, count)| AutoContinueChatStats { chat_id: chat_id.clone(), count: *count })
+            .collect())
+        .unwrap_or_default();
+    stats.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.chat_id.cmp(&b.chat_id)));
+    stats
+}
+
+/// Сбрасывает счётчик продолжений
+///
+/// # Arguments
+/// * `chat_id` - чат; без него — все счётчики
+#[tauri::command]
+pub fn reset_auto_continue_stats(chat_id: Option<String>) -> Result<(), String> {
+    let snapshot = {
+        let mut counts = AUTO_CONTINUE_COUNTS.lock().map_err(|e| e.to_string())?;
+        match chat_id {
+            Some(chat_id) => counts.retain(|(id, _)| *id != chat_id),
+            None => counts.clear(),
+        }
+        counts.clone()
+    };
+    save_auto_continue_counts(&snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_config_missing_and_partial() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("autocontinue_settings.json");
+        assert!(read_config(&path).is_none());
+
+        fs::write(&path, r#"{"enabled":[true,false,true],"delay_ms":10}"#).unwrap();
+        let config = read_config(&path).unwrap();
+        assert_eq!(config.enabled, [true, false, true]);
+        assert_eq!(config.max_per_chat, 20);
+        assert_eq!(config.delay_ms, MIN_DELAY_MS);
+
+        fs::write(&path, "not json").unwrap();
+        assert!(read_config(&path).is_none());
+    }
+
+    #[test]
+    fn test_config_and_counts_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("nested").join("autocontinue_settings.json");
+        let config = AutoContinueConfig {
+            enabled: [false, true, false],
+            max_per_chat: 5,
+            delay_ms: 120_000,
+            extra_phrases: vec![" Keep Going ".to_string(), "keep going".to_string(), " ".to_string()],
+        };
+        write_json(&config_path, &normalize_config(config)).unwrap();
+
+        let loaded = read_config(&config_path).unwrap();
+        assert_eq!(loaded.enabled, [false, true, false]);
+        assert_eq!((loaded.max_per_chat, loaded.delay_ms), (5, MAX_DELAY_MS));
+        assert_eq!(loaded.extra_phrases, vec!["keep going".to_string()]);
+
+        let counts_path = dir.path().join("autocontinue_counts.json");
+        assert!(read_counts(&counts_path).is_empty());
+        let counts = vec![("chat-b".to_string(), 20), ("chat-a".to_string(), 3)];
+        write_json(&counts_path, &counts).unwrap();
+        assert_eq!(read_counts(&counts_path), counts);
+
+        // Старый формат — объект
+        fs::write(&counts_path, r#"{"chat-a":3}"#).unwrap();
+        assert_eq!(read_counts(&counts_path), vec![("chat-a".to_string(), 3)]);
+    }
+
+    #[test]
+    fn test_register_continue_caps_chat_and_forgets_oldest() {
+        let mut counts = Vec::new();
+        assert!(register_continue(&mut counts, "chat-a", 2).allowed);
+        assert!(register_continue(&mut counts, "chat-a", 2).allowed);
+        let denied = register_continue(&mut counts, "chat-a", 2);
+        assert_eq!((denied.allowed, denied.count), (false, 2));
+        assert!(register_continue(&mut counts, "chat-a", 0).allowed);
+
+        for i in 0..MAX_AUTO_CONTINUE_CHATS {
+            register_continue(&mut counts, &format!("chat-{}", i), 2);
+        }
+        assert_eq!(counts.len(), MAX_AUTO_CONTINUE_CHATS);
+        assert!(!counts.iter().any(|(id, _)| id == "chat-a"));
+
+        // Использованный чат переносится в конец и не забывается первым
+        register_continue(&mut counts, "chat-0", 2);
+        register_continue(&mut counts, "chat-new", 2);
+        assert!(counts.iter().any(|(id, _)| id == "chat-0"));
+        assert!(!counts.iter().any(|(id, _)| id == "chat-1"));
+    }
+}
@@ -0,0 +1,312 @@
+//! Бюджет usage: остановка автоматизации у лимита
+//!
+//! Перед каждой отправкой бэкенд запрашивает `/api/organizations/{orgId}/usage`
+//! через сессию таба (тот же endpoint, что и Claude Counter) и сравнивает
+//! session/weekly utilization с порогами из `usage_budget.json`.
+//!
+//! При превышении эмитится `usage-budget-exceeded`, а отправка:
+//! - `refuse` — сразу отклоняется структурированной ошибкой
+//! - `defer` — ждёт снижения usage (опрос раз в минуту, до `defer_max_secs`),
+//!   после чего эмитится `usage-budget-resumed` или возвращается ошибка
+//!
+//! Если usage получить не удалось, отправка не блокируется (ошибка пишется
+//! в диагностику как `usage_budget_error`).
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::claude::fetch_claude_api;
+use crate::commands::logs::write_diagnostic;
+use crate::commands::usage::append_usage_snapshot;
+use crate::downloads::paths::get_app_data_dir;
+use crate::state::USAGE_BUDGET;
+use crate::types::{DiagnosticEvent, UsageBudget, UsageBudgetMode, UsageSnapshot, UsageWindow};
+
+/// Код структурированной ошибки при превышении бюджета
+pub const BUDGET_ERROR_CODE: &str = "usage_budget_exceeded";
+
+/// Таймаут запроса `/usage` (секунды)
+const FETCH_TIMEOUT_SECS: u64 = 15;
+
+/// Интервал повторной проверки в режиме defer (секунды)
+const DEFER_POLL_SECS: u64 = 60;
+
+/// Верхняя граница ожидания в режиме defer (секунды)
+const MAX_DEFER_SECS: u64 = 6 * 60 * 60;
+
+/// Превышенный порог
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct BudgetViolation {
+    /// Окно лимита: `session` (5 часов) или `weekly` (7 дней)
+    pub window: String,
+    pub utilization: f64,
+    pub threshold: f64,
+    /// Время сброса окна (ISO 8601)
+    pub resets_at: Option<String>,
+}
+
+/// Результат `check_usage_budget`
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageBudgetStatus {
+    pub tab: u8,
+    pub enabled: bool,
+    pub five_hour: Option<UsageWindow>,
+    pub seven_day: Option<UsageWindow>,
+    /// Превышенный порог (None — в пределах бюджета)
+    pub violation: Option<BudgetViolation>,
+}
+
+/// Путь к файлу бюджета usage
+fn get_usage_budget_path() -> Option<PathBuf> {
+    get_app_data_dir().map(|dir| dir.join("usage_budget.json"))
+}
+
+/// Загружает бюджет usage в состояние (при старте)
+pub fn load_usage_budget() {
+    let budget: UsageBudget = get_usage_budget_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+
+    if let Ok(mut state) = USAGE_BUDGET.lock() {
+        *state = normalize_budget(budget);
+    }
+}
+
+/// Сохраняет бюджет usage
+fn save_usage_budget(budget: &UsageBudget) -> Result<(), String> {
+    let path = get_usage_budget_path().ok_or("Cannot get app data dir")?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let json = serde_json::to_string_pretty(budget).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Ограничивает пороги 0–100% и время ожидания
+fn normalize_budget(mut budget: UsageBudget) -> UsageBudget {
+    let clamp_pct = |pct: Option<f64>| pct.filter(|p| p.is_finite()).map(|p| p.clamp(0.0, 100.0));
+    budget.session_max_pct = clamp_pct(budget.session_max_pct);
+    budget.weekly_max_pct = clamp_pct(budget.weekly_max_pct);
+    budget.defer_max_secs = budget.defer_max_secs.min(MAX_DEFER_SECS);
+    budget
+}
+
+/// Сравнивает usage с порогами бюджета
+///
+/// Weekly проверяется первым: его сброс обычно дальше, и ожидание
+/// сброса session при исчерпанном weekly не поможет.
+pub fn evaluate_budget(
+    budget: &UsageBudget,
+    five_hour: Option<&UsageWindow>,
+    seven_day: Option<&UsageWindow>,
+) -> Option<BudgetViolation> {
+    let check = |name: &str, threshold: Option<f64>, window: Option<&UsageWindow>| {
+        match (threshold, window) {
+            (Some(threshold), Some(w)) if w.utilization >= threshold => Some(BudgetViolation {
+                window: name.to_string(),
+                utilization: w.utilization,
+                threshold,
+                resets_at: w.resets_at.clone(),
+            }),
+            _ => None,
+        }
+    };
+
+    check("weekly", budget.weekly_max_pct, seven_day)
+        .or_else(|| check("session", budget.session_max_pct, five_hour))
+}
+
+/// Payload события и структурированной ошибки
+fn violation_payload(tab: u8, mode: UsageBudgetMode, violation: &BudgetViolation) -> serde_json::Value {
+    serde_json::json!({
+        "code": BUDGET_ERROR_CODE,
+        "tab": tab,
+        "mode": mode,
+        "window": violation.window,
+        "utilization": violation.utilization,
+        "threshold": violation.threshold,
+        "resets_at": violation.resets_at,
+    })
+}
+
+/// Достаёт окно из ответа `/usage` (utilization ограничивается 0–100)
+fn parse_window(value: &serde_json::Value, key: &str) -> Option<UsageWindow> {
+    let mut window: UsageWindow = serde_json::from_value(value.get(key)?.clone()).ok()?;
+    if !window.utilization.is_finite() {
+        return None;
+    }
+    window.utilization = window.utilization.clamp(0.0, 100.0);
+    Some(window)
+}
+
+/// Запрашивает текущий usage через сессию таба и дописывает снимок в историю
+async fn fetch_usage(app: &AppHandle, tab: u8) -> Result<(Option<UsageWindow>, Option<UsageWindow>), String> {
+    let value = fetch_claude_api(app, tab, "GET", "/usage", None, None, FETCH_TIMEOUT_SECS).await?;
+
+    let five_hour = parse_window(&value, "five_hour");
+    let seven_day = parse_window(&value, "seven_day");
+    if five_hour.is_none() && seven_day.is_none() {
+        return Err("Usage response has no limit windows".to_string());
+    }
+
+    let _ = append_usage_snapshot(&UsageSnapshot {
+        timestamp: Local::now().to_rfc3339(),
+        tab,
+        five_hour: five_hour.clone(),
+        seven_day: seven_day.clone(),
+    });
+
+    Ok((five_hour, seven_day))
+}
+
+fn current_budget() -> UsageBudget {
+    USAGE_BUDGET.lock()
+        .map(|b| b.clone())
+        .unwrap_or_default()
+}
+
+/// Проверяет бюджет перед отправкой (этап `budget_check` и `insert_text_to_claude`)
+///
+/// # Returns
+/// Описание текущего usage, либо JSON структурированной ошибки
+/// (`code: "usage_budget_exceeded"`, окно, utilization, порог, время сброса)
+pub async fn enforce_usage_budget(app: &AppHandle, tab: u8) -> Result<String, String> {
+    let budget = current_budget();
+    if !budget.enabled {
+        return Ok("budget disabled".to_string());
+    }
+
+    let started = Instant::now();
+    let mut notified = false;
+
+    loop {
+        let (five_hour, seven_day) = match fetch_usage(app, tab).await {
+            Ok(usage) => usage,
+            Err(e) => {
+                let _ = write_diagnostic(
+                    DiagnosticEvent::UsageBudgetError,
+                    serde_json::json!({ "tab": tab, "error": e }),
+                );
+                return Ok(format!("usage unavailable, not enforced: {}", e));
+            }
+        };
+
+        let Some(violation) = evaluate_budget(&budget, five_hour.as_ref(), seven_day.as_ref()) else {
+            if notified {
+                let _ = app.emit("usage-budget-resumed", serde_json::json!({ "tab": tab }));
+            }
+            let pct = |w: Option<&UsageWindow>| w
+                .map(|w| format!("{:.0}%", w.utilization))
+                .unwrap_or_else(|| "n/a".to_string());
+            return Ok(format!(
+                "within budget: session {}, weekly {}",
+                pct(five_hour.as_ref()), pct(seven_day.as_ref())
+            ));
+        };
+
+        let payload = violation_payload(tab, budget.mode, &violation);
+        if !notified {
+            let _ = write_diagnostic(DiagnosticEvent::UsageBudgetExceeded, payload.clone());
+            let _ = app.emit("usage-budget-exceeded", payload.clone());
+            notified = true;
+        }
+
+        let waited = started.elapsed().as_secs();
+        if budget.mode == UsageBudgetMode::Refuse || waited >= budget.defer_max_secs {
+            return Err(payload.to_string());
+        }
+
+        let pause = DEFER_POLL_SECS.min(budget.defer_max_secs - waited).max(1);
+        tokio::time::sleep(Duration::from_secs(pause)).await;
+    }
+}
+
+/// Получает бюджет usage
+#[tauri::command]
+pub fn get_usage_budget() -> UsageBudget {
+    current_budget()
+}
+
+/// Устанавливает бюджет usage
+///
+/// Пороги ограничиваются 0–100%, ожидание — 6 часами.
+#[tauri::command]
+pub fn set_usage_budget(budget: UsageBudget) -> Result<UsageBudget, String> {
+    let budget = normalize_budget(budget);
+    save_usage_budget(&budget)?;
+
+    if let Ok(mut state) = USAGE_BUDGET.lock() {
+        *state = budget.clone();
+    }
+
+    Ok(budget)
+}
+
+/// Запрашивает текущий usage таба и сравнивает с бюджетом (без ожидания)
+///
+/// # Arguments
+/// * `tab` - номер таба (1-3)
+#[tauri::command]
+pub async fn check_usage_budget(app: AppHandle, tab: u8) -> Result<UsageBudgetStatus, String> {
+    if !(1..=3).contains(&tab) {
+        return Err(format!("Invalid tab: {}", tab));
+    }
+
+    let budget = current_budget();
+    let (five_hour, seven_day) = fetch_usage(&app, tab).await?;
+    let violation = evaluate_budget(&budget, five_hour.as_ref(), seven_day.as_ref());
+
+    Ok(UsageBudgetStatus { tab, enabled: budget.enabled, five_hour, seven_day, violation })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(utilization: f64) -> UsageWindow {
+        UsageWindow { utilization, resets_at: Some("2026-03-02T15:00:00+00:00".to_string()) }
+    }
+
+    #[test]
+    fn test_session_over_threshold() {
+        let budget = UsageBudget { enabled: true, ..UsageBudget::default() };
+        let violation = evaluate_budget(&budget, Some(&window(86.0)), Some(&window(40.0))).unwrap();
+        assert_eq!(violation.window, "session");
+        assert_eq!(violation.threshold, 85.0);
+
+        assert!(evaluate_budget(&budget, Some(&window(84.9)), Some(&window(94.0))).is_none());
+    }
+
+    #[test]
+    fn test_weekly_reported_first_and_disabled_thresholds() {
+        let budget = UsageBudget { enabled: true, ..UsageBudget::default() };
+        let violation = evaluate_budget(&budget, Some(&window(90.0)), Some(&window(97.0))).unwrap();
+        assert_eq!(violation.window, "weekly");
+
+        let budget = UsageBudget { session_max_pct: None, weekly_max_pct: None, ..budget };
+        assert!(evaluate_budget(&budget, Some(&window(100.0)), Some(&window(100.0))).is_none());
+    }
+
+    #[test]
+    fn test_normalize_clamps_values() {
+        let budget = normalize_budget(UsageBudget {
+            session_max_pct: Some(150.0),
+            weekly_max_pct: Some(f64::NAN),
+            defer_max_secs: u64::MAX,
+            ..UsageBudget::default()
+        });
+        assert_eq!(budget.session_max_pct, Some(100.0));
+        assert_eq!(budget.weekly_max_pct, None);
+        assert_eq!(budget.defer_max_secs, MAX_DEFER_SECS);
+    }
+}
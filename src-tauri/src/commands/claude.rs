@@ -2,6 +2,9 @@
 //!
 //! Этот модуль содержит Tauri команды для:
 //! - Управления табами Claude (переключение, открытие, закрытие)
+//! - Выноса панели Claude в отдельное окно
+//! - Раскладки табов (single / columns / grid)
+//! - Обновления селекторов Claude.ai без релиза
 //! - Навигации внутри Claude
 //! - Выполнения JavaScript в Claude webview
 //! - Вставки текста и отправки сообщений
@@ -10,43 +13,58 @@
 use std::sync::atomic::Ordering;
 use tauri::{AppHandle, Emitter, Manager};
 
-use crate::state::{CLAUDE_VISIBLE, ACTIVE_TAB, PANEL_RATIO};
-use crate::webview::scripts::get_generation_monitor_script;
+use crate::state::{CLAUDE_VISIBLE, CLAUDE_DETACHED, ACTIVE_TAB, PANEL_RATIO, CLAUDE_LAYOUT, WINDOW_STATE};
+use crate::types::{ClaudeLayoutMode, DiagnosticEvent, LayoutSettings};
+use crate::webview::layout::save_layout_settings;
+use crate::webview::selectors::{apply_selectors, selectors_info, SelectorsInfo};
+use crate::webview::scripts::{get_generation_monitor_script, CLAUDE_EDITOR_JS};
 use crate::webview::manager::{
     ensure_claude_webview, create_claude_webview, raise_toolbar_zorder,
     suspend_claude_tab, resume_claude_tab, resize_webviews,
-    ensure_toolbar,
+    ensure_toolbar, detach_claude_panel, attach_claude_panel,
+    set_claude_window_visible,
 };
 use crate::utils::dimensions::animation::{ANIMATION_STEPS, ANIMATION_DELAY_MS};
+use crate::utils::crash::untrack_webview;
 
 /// Инициализация всех Claude webview и toolbar
 ///
 /// Вызывается из JS только если offlineMode выключен.
 /// Создаёт 3 Claude webview, toolbar, suspend неактивные табы.
+/// Если панель была открыта в прошлой сессии — открывает её снова.
 #[tauri::command]
 pub async fn init_claude_webviews(app: AppHandle) -> Result<(), String> {
     use crate::commands::logs;
     
     for tab in 1u8..=3 {
         if let Err(e) = create_claude_webview(&app, tab, None) {
-            eprintln!("[init_claude_webviews] Failed to create claude_{}: {}", tab, e);
+            log::error!("Failed to create claude_{} webview: {}", tab, e);
             let _ = logs::write_diagnostic(
-                "startup_error".to_string(),
-                format!("{{\"tab\":{},\"error\":\"{}\"}}", tab, e),
+                DiagnosticEvent::StartupError,
+                serde_json::json!({ "tab": tab, "error": e }),
             );
         }
     }
     
     if let Err(e) = ensure_toolbar(&app) {
-        eprintln!("[init_claude_webviews] Failed to create toolbar: {}", e);
+        log::error!("Failed to create toolbar: {}", e);
         let _ = logs::write_diagnostic(
-            "startup_error".to_string(),
-            format!("{{\"component\":\"toolbar\",\"error\":\"{}\"}}", e),
+            DiagnosticEvent::StartupError,
+            serde_json::json!({ "component": "toolbar", "error": e }),
         );
     }
     
     suspend_claude_tab(&app, 2);
     suspend_claude_tab(&app, 3);
+    
+    // Видимость панели из window_state.json (см. utils::window_state)
+    let restore_visible = WINDOW_STATE.lock()
+        .map(|s| s.claude_visible)
+        .unwrap_or(false);
+    if restore_visible {
+        CLAUDE_VISIBLE.store(true, Ordering::SeqCst);
+    }
+    
     raise_toolbar_zorder(&app);
     let _ = resize_webviews(&app);
     
@@ -71,6 +89,20 @@ pub async fn toggle_claude(app: AppHandle) -> Result<bool, String> {
         ensure_claude_webview(&app, 1, None)?;
     }
     
+    // В отдельном окне анимировать нечего — сворачиваем/разворачиваем окно
+    if CLAUDE_DETACHED.load(Ordering::SeqCst) {
+        CLAUDE_VISIBLE.store(new_state, Ordering::SeqCst);
+        set_claude_window_visible(&app, new_state);
+        resize_webviews(&app)?;
+        let active_tab = ACTIVE_TAB.load(Ordering::SeqCst);
+        if new_state {
+            resume_claude_tab(&app, active_tab);
+        } else {
+            suspend_claude_tab(&app, active_tab);
+        }
+        return Ok(new_state);
+    }
+    
     // Анимация: плавное изменение размера за несколько шагов
     let ratio = PANEL_RATIO.load(Ordering::SeqCst) as f64 / 100.0;
     
@@ -142,8 +174,11 @@ pub async fn switch_claude_tab(app: AppHandle, tab: u8) -> Result<(), String> {
         }
     }
     
-    // Убеждаемся что Claude видим
+    // Убеждаемся что Claude видим (в detached режиме — разворачиваем окно)
     CLAUDE_VISIBLE.store(true, Ordering::SeqCst);
+    if CLAUDE_DETACHED.load(Ordering::SeqCst) {
+        set_claude_window_visible(&app, true);
+    }
     
     // Suspend предыдущий таб, resume новый
     let prev_tab = ACTIVE_TAB.swap(tab, Ordering::SeqCst);
@@ -182,8 +217,11 @@ pub async fn switch_claude_tab_with_url(app: AppHandle, tab: u8, url: String) ->
         ensure_claude_webview(&app, tab, Some(&url))?;
     }
     
-    // Убеждаемся что Claude видим
+    // Убеждаемся что Claude видим (в detached режиме — разворачиваем окно)
     CLAUDE_VISIBLE.store(true, Ordering::SeqCst);
+    if CLAUDE_DETACHED.load(Ordering::SeqCst) {
+        set_claude_window_visible(&app, true);
+    }
     
     // Suspend предыдущий таб
     let prev_tab = ACTIVE_TAB.swap(tab, Ordering::SeqCst);
@@ -237,6 +275,7 @@ pub async fn recreate_claude_tab(app: AppHandle, tab: u8) -> Result<(), String>
     // Закрываем существующий webview
     if let Some(webview) = app.get_webview(&label) {
         let _ = webview.close();
+        untrack_webview(&label);
     }
     
     // Ждём чтобы webview успел закрыться
@@ -245,10 +284,8 @@ pub async fn recreate_claude_tab(app: AppHandle, tab: u8) -> Result<(), String>
     // Создаём заново (ensure_claude_webview поднимает z-order toolbar)
     ensure_claude_webview(&app, tab, None)?;
     
-    // Если это активный таб — обновляем layout
-    if ACTIVE_TAB.load(Ordering::SeqCst) == tab {
-        resize_webviews(&app)?;
-    }
+    // Таб может быть видим и не будучи активным (columns / grid) — обновляем layout
+    resize_webviews(&app)?;
     
     Ok(())
 }
@@ -284,16 +321,17 @@ pub async fn reset_claude_state(app: AppHandle) -> Result<(), String> {
         let label = format!("claude_{}", i);
         if let Some(webview) = app.get_webview(&label) {
             let _ = webview.close();
+            untrack_webview(&label);
         }
         // Небольшая задержка для закрытия
         std::thread::sleep(std::time::Duration::from_millis(100));
         
         let url = if i == 1 { None } else { Some("about:blank") };
         if let Err(e) = create_claude_webview(&app, i, url) {
-            eprintln!("[Reset] Failed to create claude_{}: {}", i, e);
+            log::error!("Failed to recreate claude_{} after reset: {}", i, e);
             let _ = super::logs::write_diagnostic(
-                "reset_error".to_string(),
-                format!("{{\"tab\":{},\"error\":\"{}\"}}", i, e),
+                DiagnosticEvent::ResetError,
+                serde_json::json!({ "tab": i, "error": e }),
             );
         }
     }
@@ -330,6 +368,107 @@ pub async fn get_panel_ratio() -> u32 {
     PANEL_RATIO.load(Ordering::SeqCst)
 }
 
+/// Выносит панель Claude (табы + toolbar) в отдельное окно
+///
+/// Удобно на двух мониторах: холст промптов на одном экране, Claude на другом.
+/// Эмитит `claude-panel-mode-changed` с `{detached: true}`.
+#[tauri::command]
+pub async fn detach_claude(app: AppHandle) -> Result<(), String> {
+    ensure_claude_webview(&app, 1, None)?;
+    detach_claude_panel(&app)
+}
+
+/// Возвращает панель Claude обратно в главное окно
+///
+/// Эмитит `claude-panel-mode-changed` с `{detached: false}`.
+#[tauri::command]
+pub async fn attach_claude(app: AppHandle) -> Result<(), String> {
+    attach_claude_panel(&app)
+}
+
+/// Проверяет, вынесена ли панель Claude в отдельное окно
+#[tauri::command]
+pub fn is_claude_detached() -> bool {
+    CLAUDE_DETACHED.load(Ordering::SeqCst)
+}
+
+/// Устанавливает раскладку Claude табов
+///
+/// # Arguments
+/// * `mode` - single (один активный таб), columns или grid
+/// * `visible_tabs` - табы для одновременного показа (по умолчанию — прежний набор)
+///
+/// Несуществующие табы создаются, настройки сохраняются между запусками.
+#[tauri::command]
+pub async fn set_claude_layout(app: AppHandle, mode: ClaudeLayoutMode,
+                               visible_tabs: Option<Vec<u8>>) -> Result<LayoutSettings, String> {
+    let mut settings = CLAUDE_LAYOUT.lock()
+        .map(|s| s.clone())
+        .unwrap_or_default();
+    
+    settings.mode = mode;
+    if let Some(tabs) = visible_tabs {
+        if let Some(bad) = tabs.iter().find(|t| !(1..=3).contains(*t)) {
+            return Err(format!("Invalid tab: {}", bad));
+        }
+        settings.visible_tabs = tabs;
+    }
+    
+    // Видимые табы должны существовать, иначе плитка останется пустой
+    if settings.mode != ClaudeLayoutMode::Single {
+        for &tab in &settings.visible_tabs {
+            ensure_claude_webview(&app, tab, None)?;
+            resume_claude_tab(&app, tab);
+        }
+    }
+    
+    save_layout_settings(&settings)?;
+    if let Ok(mut layout) = CLAUDE_LAYOUT.lock() {
+        *layout = settings.clone();
+    }
+    
+    resize_webviews(&app)?;
+    raise_toolbar_zorder(&app);
+    Ok(settings)
+}
+
+/// Получает текущую раскладку Claude табов
+#[tauri::command]
+pub fn get_claude_layout() -> LayoutSettings {
+    CLAUDE_LAYOUT.lock()
+        .map(|s| s.clone())
+        .unwrap_or_default()
+}
+
+/// Применяет набор селекторов из фида промптов
+///
+/// Набор валидируется по схеме встроенного `selectors.json` и принимается
+/// только если `_meta.version` новее активного. Сохраняется в app data
+/// и переинжектится в открытые Claude табы.
+///
+/// # Returns
+/// Информацию об активном наборе после применения
+#[tauri::command]
+pub fn update_selectors(app: AppHandle, json: String) -> Result<SelectorsInfo, String> {
+    use crate::commands::logs;
+    
+    let applied = apply_selectors(&app, &json)?;
+    let info = selectors_info();
+    if applied {
+        let _ = logs::write_diagnostic(
+            DiagnosticEvent::SelectorsUpdated,
+            serde_json::json!({ "version": info.version }),
+        );
+    }
+    Ok(info)
+}
+
+/// Показывает, какой набор селекторов сейчас активен (встроенный или override)
+#[tauri::command]
+pub fn get_selectors_info() -> SelectorsInfo {
+    selectors_info()
+}
+
 /// Выполняет JavaScript в Claude webview (без результата)
 #[tauri::command]
 pub async fn eval_in_claude(app: AppHandle, tab: u8, script: String) -> Result<(), String> {
@@ -366,6 +505,10 @@ pub async fn eval_in_claude_with_result(
         use std::sync::{Arc, Mutex};
         use webview2_com::CallDevToolsProtocolMethodCompletedHandler;
         use webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2;
+        use crate::utils::metrics::{Span, stage};
+        
+        // Замер до ответа CDP (таймаут записывается как ошибка)
+        let span = Span::start(stage::CDP_EVAL, Some(tab));
         
         let result: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
         let result_clone = Arc::clone(&result);
@@ -452,13 +595,19 @@ pub async fn eval_in_claude_with_result(
                 if *d { break; }
             }
             if start.elapsed().as_secs() > timeout {
+                let _ = super::logs::write_diagnostic(
+                    DiagnosticEvent::CdpTimeout,
+                    serde_json::json!({ "tab": tab, "timeout_secs": timeout }),
+                );
                 return Err(format!("Timeout after {} seconds", timeout));
             }
             std::thread::sleep(std::time::Duration::from_millis(10));
         }
         
         let r = result.lock().unwrap_or_else(|e| e.into_inner());
-        Ok(r.clone().unwrap_or_else(|| "null".to_string()))
+        let value = r.clone().unwrap_or_else(|| "null".to_string());
+        span.end(!value.starts_with("{\"error\":"));
+        Ok(value)
     }
     
     #[cfg(not(windows))]
@@ -468,6 +617,82 @@ pub async fn eval_in_claude_with_result(
     }
 }
 
+/// Выполняет запрос к API Claude через авторизованную сессию таба
+///
+/// Запрос идёт `fetch` внутри страницы claude.ai (cookies сессии),
+/// orgId берётся из cookie `lastActiveOrg`. `path` — путь относительно
+/// `/api/organizations/{orgId}`, `map_js` — необязательная (в том числе async)
+/// JS-функция, сокращающая ответ до отправки в Rust (например, без содержимого файлов).
+///
+/// # Returns
+/// JSON ответа (`null` для пустого тела) или ошибку вида `HTTP 404: ...`
+pub async fn fetch_claude_api(
+    app: &AppHandle,
+    tab: u8,
+    method: &str,
+    path: &str,
+    body: Option<&serde_json::Value>,
+    map_js: Option<&str>,
+    timeout_secs: u64,
+) -> Result<serde_json::Value, String> {
+    let body_literal = body.map(|b| b.to_string()).unwrap_or_else(|| "null".to_string());
+    let script = format!(r#"
+        (async function() {{
+            try {{
+                const org = document.cookie.split('; ')
+                    .find(row => row.startsWith('lastActiveOrg='))?.split('=')[1];
+                if (!org) return {{ ok: false, status: 0, message: 'organization id not found' }};
+                const body = {body};
+                const res = await fetch('/api/organizations/' + org + {path}, {{
+                    method: {method},
+                    credentials: 'include',
+                    headers: body !== null ? {{ 'Content-Type': 'application/json' }} : {{}},
+                    body: body !== null ? JSON.stringify(body) : undefined
+                }});
+                const text = await res.text();
+                let data = null;
+                try {{ data = text ? JSON.parse(text) : null; }} catch (e) {{ data = text; }}
+                if (!res.ok) {{
+                    const message = typeof data === 'string' ? data : JSON.stringify(data);
+                    return {{ ok: false, status: res.status, message: String(message).slice(0, 500) }};
+                }}
+                const map = {map};
+                return {{ ok: true, status: res.status, data: map ? await map(data) : data }};
+            }} catch (e) {{
+                return {{ ok: false, status: 0, message: String(e && e.message || e) }};
+            }}
+        }})()
+    "#,
+        body = body_literal,
+        path = serde_json::to_string(path).map_err(|e| e.to_string())?,
+        method = serde_json::to_string(method).map_err(|e| e.to_string())?,
+        map = map_js.unwrap_or("null"),
+    );
+
+    let raw = eval_in_claude_with_result(app.clone(), tab, script, Some(timeout_secs)).await?;
+    let value: serde_json::Value = serde_json::from_str(&raw)
+        .map_err(|e| format!("Invalid API response '{}': {}", raw, e))?;
+
+    if value.get("ok").and_then(|v| v.as_bool()) == Some(true) {
+        return Ok(value.get("data").cloned().unwrap_or(serde_json::Value::Null));
+    }
+    if let Some(err) = value.get("error") {
+        return Err(format!("Script exception: {}", err));
+    }
+
+    let status = value.get("status").and_then(|v| v.as_u64()).unwrap_or(0);
+    let message = value.get("message").and_then(|v| v.as_str()).unwrap_or_default();
+    let _ = super::logs::write_diagnostic(
+        DiagnosticEvent::ClaudeApiError,
+        serde_json::json!({ "tab": tab, "method": method, "path": path, "status": status, "message": message }),
+    );
+    Err(if status > 0 {
+        format!("HTTP {}: {}", status, message)
+    } else {
+        message.to_string()
+    })
+}
+
 /// Инжектит монитор генерации в Claude webview
 #[tauri::command]
 pub async fn inject_generation_monitor(app: AppHandle, tab: u8) -> Result<(), String> {
@@ -516,6 +741,9 @@ pub async fn set_generation_state(_app: AppHandle, tab: u8, generating: bool) ->
 /// * `tab` - номер таба
 /// * `text` - текст для вставки
 /// * `auto_send` - автоматически отправить после вставки
+///
+/// При `auto_send` сначала проверяется бюджет usage: при превышении
+/// возвращается JSON-ошибка `usage_budget_exceeded` (см. `commands/budget.rs`).
 #[tauri::command]
 pub async fn insert_text_to_claude(
     app: AppHandle, 
@@ -528,6 +756,10 @@ pub async fn insert_text_to_claude(
     let webview = app.get_webview(&label)
         .ok_or("Claude tab not found")?;
     
+    if auto_send {
+        crate::commands::budget::enforce_usage_budget(&app, tab).await?;
+    }
+    
     // Экранируем текст для JS
     let escaped_text = text
         .replace("\\", "\\\\")
@@ -538,26 +770,15 @@ pub async fn insert_text_to_claude(
     
     let script = format!(r#"
         (function() {{
+            {editor}
             const AUTO_SEND = {auto_send};
             const text = `{text}`;
             const SEL = window._s;
             
-            const pmSelector = SEL?.input?.proseMirror || '.ProseMirror';
-            const pmElement = document.querySelector(pmSelector);
-            
+            const pmElement = findEditor();
             if (!pmElement) return false;
             
-            pmElement.focus();
-            const editor = pmElement.editor;
-            
-            if (editor && editor.commands && typeof editor.commands.insertContent === 'function') {{
-                editor.commands.insertContent({{ type: 'text', text: text }});
-            }} else {{
-                const p = document.createElement('p');
-                p.textContent = text;
-                pmElement.appendChild(p);
-                pmElement.dispatchEvent(new Event('input', {{ bubbles: true }}));
-            }}
+            insertIntoEditor(pmElement, text);
             
             if (AUTO_SEND) {{
                 let attempts = 0;
@@ -565,18 +786,6 @@ pub async fn insert_text_to_claude(
                 let sent = false;
                 const initialContent = pmElement.textContent?.trim() || '';
                 
-                const findBySelectors = (selectors) => {{
-                    if (!selectors) return null;
-                    const arr = Array.isArray(selectors) ? selectors : [selectors];
-                    for (const sel of arr) {{
-                        try {{
-                            const el = document.querySelector(sel);
-                            if (el) return el;
-                        }} catch(e) {{}}
-                    }}
-                    return null;
-                }};
-                
                 const tryToSend = () => {{
                     if (sent) return;
                     attempts++;
@@ -592,26 +801,7 @@ pub async fn insert_text_to_claude(
                         return;
                     }}
                     
-                    let sendBtn = findBySelectors(SEL?.input?.sendButton);
-                    
-                    if (!sendBtn) {{
-                        const fieldset = pmElement.closest('fieldset') || pmElement.closest('form') || pmElement.parentElement?.parentElement;
-                        if (fieldset) {{
-                            const btns = fieldset.querySelectorAll('button');
-                            const attachPattern = SEL?.attachments?.attachButtonAriaPattern || 'attach';
-                            for (const btn of btns) {{
-                                const svg = btn.querySelector('svg');
-                                const isAttachBtn = btn.getAttribute('aria-label')?.toLowerCase().includes(attachPattern);
-                                if (svg && !btn.disabled && !isAttachBtn) {{
-                                    const rect = btn.getBoundingClientRect();
-                                    const pmRect = pmElement.getBoundingClientRect();
-                                    if (rect.left > pmRect.left + pmRect.width / 2) {{
-                                        sendBtn = btn;
-                                    }}
-                                }}
-                            }}
-                        }}
-                    }}
+                    const sendBtn = findSendButton(pmElement);
                     
                     if (sendBtn && !sendBtn.disabled) {{
                         sendBtn.click();
@@ -633,7 +823,7 @@ pub async fn insert_text_to_claude(
             
             return true;
         }})();
-    "#, auto_send = auto_send_js, text = escaped_text);
+    "#, editor = CLAUDE_EDITOR_JS, auto_send = auto_send_js, text = escaped_text);
     
     webview.eval(&script).map_err(|e| e.to_string())?;
     Ok(())
@@ -0,0 +1,312 @@
+//! Пакет диагностики для поддержки
+//!
+//! `export_diagnostics` собирает в один zip в папке загрузок:
+//! - `diagnostics.json` — лог диагностики
+//! - `backend.log` — последние строки лога бэкенда
+//! - `archive_summary.json` / `downloads_summary.json` — сводки логов
+//!   (количество, табы, расширения, период — без списка файлов)
+//! - `system.json` — версии приложения, Tauri, selectors.json и ОС
+//! - `tab_checks.json` — проверка селекторов в открытых табах
+//! - `settings.json` — настройки бэкенда
+//! - `metrics.json` — перцентили времени этапов текущей сессии
+//! - `crashes/*.json` — отчёты о падениях, которые пользователь разрешил добавить
+//! - `redaction_report.json` — что заменено при редактировании
+//!
+//! Всё, кроме отчёта, проходит через `utils/redact.rs`: email, org id,
+//! UUID проектов и чатов, пути к файлам заменяются метками.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use chrono::Local;
+use log::Level;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::commands::logs::{get_archive_log, read_diagnostics, read_downloads_log, write_diagnostic};
+use crate::commands::selectors::run_selector_check;
+use crate::downloads::index::LogRecord;
+use crate::downloads::paths::{get_app_data_dir, get_custom_downloads_path, load_downloads_settings};
+use crate::state::{
+    AUTO_CONTINUE_CONFIG, CLAUDE_LAYOUT, KNOWLEDGE_WATCHES, METRICS, USAGE_BUDGET, WINDOW_STATE,
+};
+use crate::types::{CrashReport, DiagnosticEvent};
+use crate::utils::crash::{included_dir, list_reports, pending_dir, prune_reports, save_report};
+use crate::utils::dimensions::limits::{DIAGNOSTICS_BUNDLE_LOG_LINES, MAX_CRASH_REPORTS};
+use crate::utils::logger::{self, format_line, BackendLogQuery};
+use crate::utils::platform::os_version;
+use crate::utils::redact::Redactor;
+use crate::webview::selectors::selectors_info;
+
+/// Сводка лога загрузок или архивов (без имён и путей файлов)
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct LogSummary {
+    pub entries: usize,
+    pub first: Option<String>,
+    pub last: Option<String>,
+    /// Записей по табам (`"none"` — таб неизвестен)
+    pub by_tab: BTreeMap<String, usize>,
+    /// Записей по расширениям (`""` — без расширения)
+    pub by_extension: BTreeMap<String, usize>,
+    /// Разных проектов
+    pub projects: usize,
+    /// Суммарный размер известных размеров (байты)
+    pub total_size: u64,
+}
+
+/// Считает сводку по записям лога
+pub fn summarize_log<T: LogRecord>(entries: &[T]) -> LogSummary {
+    let mut summary = LogSummary { entries: entries.len(), ..Default::default() };
+    let mut projects = BTreeSet::new();
+
+    for entry in entries {
+        let timestamp = entry.timestamp().to_string();
+        if summary.first.as_ref().is_none_or(|first| timestamp < *first) {
+            summary.first = Some(timestamp.clone());
+        }
+        if summary.last.as_ref().is_none_or(|last| timestamp > *last) {
+            summary.last = Some(timestamp);
+        }
+
+        let tab = entry.tab().map(|t| t.to_string()).unwrap_or_else(|| "none".to_string());
+        *summary.by_tab.entry(tab).or_default() += 1;
+
+        let ext = Path::new(entry.filename())
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        *summary.by_extension.entry(ext).or_default() += 1;
+
+        if let Some(project) = entry.project() {
+            projects.insert(project.to_lowercase());
+        }
+        summary.total_size += entry.size().unwrap_or(0);
+    }
+
+    summary.projects = projects.len();
+    summary
+}
+
+/// Версии и окружение
+fn system_info(app: &AppHandle) -> Value {
+    serde_json::json!({
+        "generated_at": Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        "app_version": app.package_info().version.to_string(),
+        "tauri_version": tauri::VERSION,
+        "os": std::env::consts::OS,
+        "os_version": os_version(),
+        "os_family": std::env::consts::FAMILY,
+        "arch": std::env::consts::ARCH,
+        "debug_build": cfg!(debug_assertions),
+        "selectors": selectors_info(),
+    })
+}
+
+/// Настройки бэкенда
+fn settings_snapshot() -> Value {
+    fn snapshot<T: Serialize>(value: Option<T>) -> Value {
+        value.and_then(|v| serde_json::to_value(v).ok()).unwrap_or(Value::Null)
+    }
+
+    serde_json::json!({
+        "downloads": snapshot(Some(load_downloads_settings())),
+        "layout": snapshot(CLAUDE_LAYOUT.lock().ok().map(|l| l.clone())),
+        "window": snapshot(WINDOW_STATE.lock().ok().map(|w| w.clone())),
+        "auto_continue": snapshot(AUTO_CONTINUE_CONFIG.lock().ok().map(|c| c.clone())),
+        "usage_budget": snapshot(USAGE_BUDGET.lock().ok().map(|b| b.clone())),
+        "knowledge_watches": snapshot(KNOWLEDGE_WATCHES.lock().ok().map(|w| w.clone())),
+        "log": snapshot(Some(logger::current_settings())),
+    })
+}
+
+/// Проверка селекторов в каждом созданном табе
+///
+/// Без записи `selector_broken` — экспорт не должен менять лог, который выгружает.
+async fn tab_checks(app: &AppHandle) -> Value {
+    let mut checks = Vec::new();
+    for tab in 1..=3u8 {
+        if app.get_webview(&format!("claude_{}", tab)).is_none() {
+            checks.push(serde_json::json!({ "tab": tab, "created": false }));
+            continue;
+        }
+        let check = match run_selector_check(app, tab, false).await {
+            Ok(report) => serde_json::to_value(report).unwrap_or(Value::Null),
+            Err(e) => serde_json::json!({ "tab": tab, "error": e }),
+        };
+        checks.push(check);
+    }
+    Value::Array(checks)
+}
+
+/// Последние строки лога бэкенда
+fn backend_log_tail() -> String {
+    let query = BackendLogQuery { limit: Some(DIAGNOSTICS_BUNDLE_LOG_LINES), ..Default::default() };
+    match logger::read_lines(&query) {
+        Ok(lines) => lines.iter()
+            .map(|l| format_line(&l.timestamp, l.level.parse().unwrap_or(Level::Info), &l.target, &l.message) + "\n")
+            .collect(),
+        Err(e) => format!("Backend log unavailable: {}\n", e),
+    }
+}
+
+/// Пути, которые редактируются целиком (могут содержать пробелы)
+fn known_paths() -> Vec<String> {
+    [dirs::home_dir(), dirs::download_dir(), get_app_data_dir()]
+        .into_iter()
+        .flatten()
+        .map(|p| p.to_string_lossy().to_string())
+        .chain(get_custom_downloads_path())
+        .collect()
+}
+
+/// Собирает zip пакета
+fn write_bundle(target: &Path, system: Value, tabs: Value) -> Result<(), String> {
+    let mut redactor = Redactor::new(known_paths());
+
+    let diagnostics = serde_json::to_value(read_diagnostics()?).map_err(|e| e.to_string())?;
+    let archive = summarize_log(&get_archive_log()?);
+    let downloads = summarize_log(&read_downloads_log()?);
+
+    let mut files: Vec<(String, String)> = Vec::new();
+    let mut add_json = |redactor: &mut Redactor, name: String, value: Value| -> Result<(), String> {
+        let value = redactor.redact_value(&name, value);
+        files.push((name, serde_json::to_string_pretty(&value).map_err(|e| e.to_string())?));
+        Ok(())
+    };
+    let crashes = included_dir().map(|dir| list_reports(&dir)).unwrap_or_default();
+
+    add_json(&mut redactor, "system.json".into(), system)?;
+    add_json(&mut redactor, "diagnostics.json".into(), diagnostics)?;
+    add_json(&mut redactor, "archive_summary.json".into(), serde_json::to_value(archive).map_err(|e| e.to_string())?)?;
+    add_json(&mut redactor, "downloads_summary.json".into(), serde_json::to_value(downloads).map_err(|e| e.to_string())?)?;
+    add_json(&mut redactor, "tab_checks.json".into(), tabs)?;
+    add_json(&mut redactor, "settings.json".into(), settings_snapshot())?;
+    let stage_metrics = METRICS.lock().map_err(|e| e.to_string())?.stats(None);
+    add_json(&mut redactor, "metrics.json".into(), serde_json::to_value(stage_metrics).map_err(|e| e.to_string())?)?;
+    for crash in crashes {
+        let name = format!("crashes/{}.json", crash.id);
+        add_json(&mut redactor, name, serde_json::to_value(crash).map_err(|e| e.to_string())?)?;
+    }
+    files.push(("backend.log".into(), redactor.redact_text("backend.log", &backend_log_tail())));
+    files.push((
+        "redaction_report.json".into(),
+        serde_json::to_string_pretty(&redactor.report()).map_err(|e| e.to_string())?,
+    ));
+
+    let file = File::create(target).map_err(|e| e.to_string())?;
+    let mut zip = ZipWriter::new(file);
+    for (name, content) in files {
+        zip.start_file(name.as_str(), SimpleFileOptions::default()).map_err(|e| e.to_string())?;
+        zip.write_all(content.as_bytes()).map_err(|e| e.to_string())?;
+    }
+    zip.finish().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Экспортирует пакет диагностики в папку загрузок пользователя
+///
+/// Перед сборкой проверяет селекторы в открытых табах — результат
+/// попадает только в `tab_checks.json`, лог диагностики не меняется.
+///
+/// # Returns
+/// * `Ok(path)` - путь к `apm-diagnostics-*.zip`
+/// * `Err(String)` - ошибка экспорта
+#[tauri::command]
+pub async fn export_diagnostics(app: AppHandle) -> Result<String, String> {
+    let tabs = tab_checks(&app).await;
+    let system = system_info(&app);
+
+    // Целевая папка — Downloads пользователя
+    let downloads_dir = dirs::download_dir()
+        .ok_or("Cannot find Downloads directory")?;
+    let filename = format!("apm-diagnostics-{}.zip", Local::now().format("%Y%m%d-%H%M%S"));
+    let target_path = downloads_dir.join(&filename);
+
+    let target = target_path.clone();
+    tauri::async_runtime::spawn_blocking(move || write_bundle(&target, system, tabs))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    log::info!("Diagnostics bundle exported: {}", filename);
+    Ok(target_path.to_string_lossy().to_string())
+}
+
+/// Отчёты о падениях прошлых запусков, которые пользователь ещё не видел
+#[tauri::command]
+pub fn list_pending_crashes() -> Vec<CrashReport> {
+    pending_dir().map(|dir| list_reports(&dir)).unwrap_or_default()
+}
+
+/// Решение пользователя об отчётах о падениях
+///
+/// # Arguments
+/// * `include` - `true`: отчёты попадут в пакет диагностики (и событие
+///   `crash` — в лог диагностики); `false`: отчёты удаляются
+///
+/// # Returns
+/// Количество обработанных отчётов
+#[tauri::command]
+pub fn resolve_pending_crashes(include: bool) -> Result<usize, String> {
+    let pending = pending_dir().ok_or("Cannot get crash reports path")?;
+    let included = included_dir().ok_or("Cannot get crash reports path")?;
+    let reports = list_reports(&pending);
+
+    for report in &reports {
+        if include {
+            save_report(&included, report)?;
+            let _ = write_diagnostic(DiagnosticEvent::Crash, serde_json::json!({
+                "id": report.id,
+                "message": report.message,
+                "location": report.location,
+                "tab": report.active_tab,
+                "app_version": report.app_version,
+            }));
+        }
+        std::fs::remove_file(pending.join(format!("{}.json", report.id))).map_err(|e| e.to_string())?;
+    }
+
+    if include {
+        prune_reports(&included, MAX_CRASH_REPORTS);
+    }
+    Ok(reports.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DownloadEntry;
+
+    fn download(timestamp: &str, filename: &str, tab: Option<u8>, project: Option<&str>, size: Option<u64>) -> DownloadEntry {
+        DownloadEntry {
+            timestamp: timestamp.to_string(),
+            filename: filename.to_string(),
+            file_path: format!("C:/Downloads/{}", filename),
+            tab,
+            project: project.map(str::to_string),
+            size,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_summarize_log() {
+        let summary = summarize_log(&[
+            download("2026-03-02 10:00:00", "plan.MD", Some(1), Some("Alpha"), Some(10)),
+            download("2026-03-01 09:00:00", "notes.md", Some(2), Some("alpha"), None),
+            download("2026-03-03 08:00:00", "Makefile", None, None, Some(5)),
+        ]);
+
+        assert_eq!(summary.entries, 3);
+        assert_eq!(summary.first.as_deref(), Some("2026-03-01 09:00:00"));
+        assert_eq!(summary.last.as_deref(), Some("2026-03-03 08:00:00"));
+        assert_eq!(summary.by_tab, BTreeMap::from([("1".into(), 1), ("2".into(), 1), ("none".into(), 1)]));
+        assert_eq!(summary.by_extension, BTreeMap::from([("".into(), 1), ("md".into(), 2)]));
+        assert_eq!((summary.projects, summary.total_size), (1, 15));
+    }
+}
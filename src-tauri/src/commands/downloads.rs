@@ -3,12 +3,34 @@
 //! Этот модуль содержит Tauri команды для:
 //! - Получения и установки пути загрузок
 //! - Выбора папки через диалог
+//! - Настройки распаковки архивов `claude_files.zip`
+//! - Правил раскладки загрузок по подпапкам и их предпросмотра
+//! - Дедупликации загрузок по содержимому
+//! - Активных и неудачных загрузок, повтора загрузки
 //! - Открытия файлов
 
 use std::fs;
-use tauri::AppHandle;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
 
-use crate::downloads::paths::{get_custom_downloads_path, save_custom_downloads_path};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::commands::logs::remove_download_entries;
+use crate::downloads::paths::{
+    get_custom_downloads_path, save_custom_downloads_path,
+    load_downloads_settings, save_downloads_settings,
+    get_unique_filepath,
+};
+use crate::downloads::dedup::{scan_duplicates, DuplicateGroup};
+use crate::downloads::routing::{download_context, resolve_destination, validate_template};
+use crate::downloads::tracker::{active_downloads, clear_failed, failed_downloads, take_failed_download};
+use crate::state::ACTIVE_TAB;
+use crate::types::{
+    ArchiveExtractFolder, DownloadDuplicates, DownloadRule, DownloadsSettings,
+    FailedDownload, InFlightDownload,
+};
+use crate::webview::manager::resume_claude_tab;
 use crate::utils::platform::open_file_in_system;
 
 /// Получает текущий путь для загрузок
@@ -48,6 +70,184 @@ pub async fn pick_downloads_folder(app: AppHandle) -> Result<String, String> {
     }
 }
 
+/// Получает настройки загрузок (путь и распаковка архивов)
+#[tauri::command]
+pub fn get_downloads_settings() -> DownloadsSettings {
+    load_downloads_settings()
+}
+
+/// Включает/выключает распаковку скачанных `claude_files.zip`
+///
+/// # Arguments
+/// * `enabled` - распаковывать архивы после скачивания
+/// * `into` - `project` (папка проекта) или `chat` (папка чата); без него — не меняется
+#[tauri::command]
+pub fn set_archive_extraction(enabled: bool, into: Option<ArchiveExtractFolder>) -> Result<DownloadsSettings, String> {
+    let mut settings = load_downloads_settings();
+    settings.extract_archives = enabled;
+    if let Some(into) = into {
+        settings.extract_into = into;
+    }
+    save_downloads_settings(&settings)?;
+    Ok(settings)
+}
+
+/// Результат `preview_download_route`
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadRoutePreview {
+    /// Итоговый путь файла (с учётом уже существующих файлов)
+    pub path: String,
+    /// Индекс сработавшего правила (None — корень папки загрузок)
+    pub rule: Option<usize>,
+    pub template: Option<String>,
+}
+
+/// Сохраняет правила раскладки загрузок
+///
+/// Правила проверяются по порядку, срабатывает первое подходящее.
+/// Шаблоны должны быть относительными и содержать только известные
+/// переменные: `{tab}`, `{project}`, `{chat}`, `{date}`, `{ext}`, `{filename}`, `{stem}`.
+#[tauri::command]
+pub fn set_download_rules(rules: Vec<DownloadRule>) -> Result<Vec<DownloadRule>, String> {
+    let rules: Vec<DownloadRule> = rules.into_iter()
+        .map(|rule| DownloadRule {
+            pattern: rule.pattern.trim().to_string(),
+            template: rule.template.trim().to_string(),
+        })
+        .collect();
+    for rule in &rules {
+        validate_template(&rule.template)?;
+    }
+
+    let mut settings = load_downloads_settings();
+    settings.rules = rules.clone();
+    save_downloads_settings(&settings)?;
+    Ok(rules)
+}
+
+/// Показывает, куда будет сохранён файл с таким именем
+///
+/// # Arguments
+/// * `filename` - имя загружаемого файла
+/// * `tab` - Claude таб для контекста (по умолчанию активный)
+/// * `url` - URL страницы Claude (по умолчанию текущий URL таба)
+/// * `rules` - проверить несохранённые правила вместо сохранённых
+#[tauri::command]
+pub fn preview_download_route(
+    app: AppHandle,
+    filename: String,
+    tab: Option<u8>,
+    url: Option<String>,
+    rules: Option<Vec<DownloadRule>>,
+) -> Result<DownloadRoutePreview, String> {
+    let tab = tab.unwrap_or_else(|| ACTIVE_TAB.load(Ordering::SeqCst));
+    let url = url.unwrap_or_else(|| {
+        app.get_webview(&format!("claude_{}", tab))
+            .and_then(|webview| webview.url().ok())
+            .map(|u| u.to_string())
+            .unwrap_or_default()
+    });
+    let rules = match rules {
+        Some(rules) => rules,
+        None => load_downloads_settings().rules,
+    };
+
+    let base = get_custom_downloads_path()
+        .map(PathBuf::from)
+        .or_else(dirs::download_dir)
+        .ok_or("Cannot determine downloads folder")?;
+
+    let (target, rule) = resolve_destination(&base, &rules, &download_context(tab, &url), &filename);
+    let dir = target.parent().unwrap_or(&base);
+    let name = target.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or(filename);
+
+    Ok(DownloadRoutePreview {
+        path: get_unique_filepath(dir, &name).to_string_lossy().to_string(),
+        rule,
+        template: rule.map(|index| rules[index].template.clone()),
+    })
+}
+
+/// Задаёт обработку загрузок, содержимое которых уже есть в папке
+///
+/// # Arguments
+/// * `mode` - `keep` (оставить с пометкой `duplicate_of`) или `drop` (удалить дубль)
+#[tauri::command]
+pub fn set_download_duplicates(mode: DownloadDuplicates) -> Result<DownloadsSettings, String> {
+    let mut settings = load_downloads_settings();
+    settings.duplicates = mode;
+    save_downloads_settings(&settings)?;
+    Ok(settings)
+}
+
+/// Ищет группы одинаковых по содержимому файлов в папке загрузок
+///
+/// # Arguments
+/// * `folder` - папка для сканирования (по умолчанию папка загрузок)
+#[tauri::command]
+pub async fn find_duplicate_downloads(folder: Option<String>) -> Result<Vec<DuplicateGroup>, String> {
+    let root = folder
+        .or_else(get_custom_downloads_path)
+        .map(PathBuf::from)
+        .or_else(dirs::download_dir)
+        .ok_or("Cannot determine downloads folder")?;
+    if !root.is_dir() {
+        return Err(format!("Folder not found: {}", root.display()));
+    }
+
+    // Хэширование может занять время — вне потока команд
+    tauri::async_runtime::spawn_blocking(move || scan_duplicates(&root))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Загрузки в процессе
+///
+/// Размер и полученные байты известны только на Windows (WebView2).
+#[tauri::command]
+pub fn list_active_downloads() -> Vec<InFlightDownload> {
+    active_downloads()
+}
+
+/// Неудачные загрузки (URL, таб, причина), новые в конце
+#[tauri::command]
+pub fn list_failed_downloads() -> Vec<FailedDownload> {
+    failed_downloads()
+}
+
+/// Повторяет неудачную загрузку
+///
+/// Claude таб, из которого она была начата, переходит по URL загрузки —
+/// cookies сессии те же, страница чата остаётся открытой.
+/// Запись убирается из списка; при новой ошибке она добавится снова.
+///
+/// # Arguments
+/// * `id` - ID из `list_failed_downloads`
+#[tauri::command]
+pub fn retry_download(app: AppHandle, id: u64) -> Result<FailedDownload, String> {
+    let failed = failed_downloads().into_iter()
+        .find(|f| f.id == id)
+        .ok_or_else(|| format!("Failed download not found: {}", id))?;
+
+    let webview = app.get_webview(&format!("claude_{}", failed.tab))
+        .ok_or_else(|| format!("Claude tab {} is not open", failed.tab))?;
+    let url = failed.url.parse()
+        .map_err(|e| format!("Invalid URL '{}': {}", failed.url, e))?;
+
+    resume_claude_tab(&app, failed.tab);
+    webview.navigate(url).map_err(|e| e.to_string())?;
+
+    take_failed_download(id)
+}
+
+/// Очищает список неудачных загрузок
+#[tauri::command]
+pub fn clear_failed_downloads() -> Result<(), String> {
+    clear_failed()
+}
+
 /// Открывает файл в системном приложении по умолчанию
 ///
 /// # Arguments
@@ -71,15 +271,6 @@ pub fn open_file(file_path: String) -> Result<(), String> {
 /// * `Err(String)` - ошибка удаления
 #[tauri::command]
 pub fn delete_download(file_path: String) -> Result<bool, String> {
-    use crate::downloads::paths::get_downloads_log_path;
-    use crate::types::DownloadEntry;
-    
-    let log_path = get_downloads_log_path().ok_or("Cannot get log path")?;
-    
-    if !log_path.exists() {
-        return Ok(false);
-    }
-    
     // Удаляем файл с диска
     let path = std::path::Path::new(&file_path);
     if path.exists() {
@@ -87,16 +278,7 @@ pub fn delete_download(file_path: String) -> Result<bool, String> {
     }
     
     // Удаляем запись из лога
-    let content = fs::read_to_string(&log_path).map_err(|e| e.to_string())?;
-    let mut entries: Vec<DownloadEntry> = serde_json::from_str(&content).unwrap_or_default();
-    
-    let original_len = entries.len();
-    entries.retain(|e| e.file_path != file_path);
-    
-    if entries.len() < original_len {
-        let json = serde_json::to_string_pretty(&entries).map_err(|e| e.to_string())?;
-        fs::write(&log_path, json).map_err(|e| e.to_string())?;
-    }
+    remove_download_entries(|e| e.file_path == file_path)?;
     
     Ok(true)
 }
@@ -108,17 +290,8 @@ pub fn delete_download(file_path: String) -> Result<bool, String> {
 /// * `Err(String)` - ошибка
 #[tauri::command]
 pub fn delete_all_downloads() -> Result<u32, String> {
-    use crate::downloads::paths::get_downloads_log_path;
-    use crate::types::DownloadEntry;
-    
-    let log_path = get_downloads_log_path().ok_or("Cannot get log path")?;
-    
-    if !log_path.exists() {
-        return Ok(0);
-    }
-    
-    let content = fs::read_to_string(&log_path).map_err(|e| e.to_string())?;
-    let entries: Vec<DownloadEntry> = serde_json::from_str(&content).unwrap_or_default();
+    // Очищаем лог, получая все записи
+    let entries = remove_download_entries(|_| true)?;
     
     let mut deleted_count = 0u32;
     
@@ -126,16 +299,11 @@ pub fn delete_all_downloads() -> Result<u32, String> {
     for entry in &entries {
         if !entry.file_path.is_empty() {
             let path = std::path::Path::new(&entry.file_path);
-            if path.exists() {
-                if fs::remove_file(path).is_ok() {
-                    deleted_count += 1;
-                }
+            if path.exists() && fs::remove_file(path).is_ok() {
+                deleted_count += 1;
             }
         }
     }
     
-    // Очищаем лог
-    fs::remove_file(&log_path).map_err(|e| e.to_string())?;
-    
     Ok(deleted_count)
 }
@@ -0,0 +1,600 @@
+//! Управление knowledge проекта Claude
+//!
+//! Этот модуль содержит Tauri команды для:
+//! - Списка файлов в knowledge проекта
+//! - Удаления файлов (например, устаревших research-файлов перед новым прогоном)
+//! - Замены файла новой версией с диска
+//! - Загрузки с дедупликацией по SHA-256 содержимого
+//!
+//! Для каждого проекта ведётся манифест загруженных файлов
+//! (`knowledge/{project_uuid}.json`: хэш, имя, UUID документа).
+//! Файл, содержимое которого уже есть в проекте, не загружается повторно.
+//! `reconcile_knowledge_manifest` сверяет манифест с живым списком knowledge.
+//!
+//! Все запросы идут через `fetch_claude_api` — `fetch` внутри Claude таба
+//! с сессией пользователя. Содержимое документов в Rust не передаётся,
+//! только метаданные.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+
+use crate::commands::attachments::read_attachment_bytes;
+use crate::commands::claude::fetch_claude_api;
+use crate::commands::projects::{get_project, is_valid_uuid};
+use crate::downloads::paths::get_app_data_dir;
+use crate::state::{ACTIVE_TAB, KNOWLEDGE_MANIFEST_LOCK};
+
+/// Таймаут списка и удаления (секунды)
+const LIST_TIMEOUT_SECS: u64 = 20;
+
+/// Таймаут загрузки файла (секунды)
+const UPLOAD_TIMEOUT_SECS: u64 = 60;
+
+/// JS-функция, оставляющая от документа только метаданные
+const DOC_META_JS: &str = "d => ({ uuid: d.uuid, file_name: d.file_name, created_at: d.created_at || null, size: (d.content || '').length })";
+
+/// То же с SHA-256 содержимого (UTF-8), для сверки с манифестом
+const DOC_META_HASH_JS: &str = r#"async d => {
+    const bytes = new TextEncoder().encode(d.content || '');
+    const digest = new Uint8Array(await crypto.subtle.digest('SHA-256', bytes));
+    const hash = Array.from(digest, b => b.toString(16).padStart(2, '0')).join('');
+    return { uuid: d.uuid, file_name: d.file_name, created_at: d.created_at || null, size: (d.content || '').length, hash };
+}"#;
+
+/// Файл в knowledge проекта
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnowledgeFile {
+    /// UUID документа в Claude
+    pub uuid: String,
+    pub file_name: String,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    /// Размер содержимого в символах
+    #[serde(default)]
+    pub size: u64,
+    /// SHA-256 содержимого (только при сверке манифеста)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+}
+
+/// Запись манифеста загруженных в проект файлов
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KnowledgeManifestEntry {
+    /// SHA-256 содержимого (hex)
+    pub hash: String,
+    pub file_name: String,
+    /// UUID документа в Claude
+    #[serde(default)]
+    pub doc_uuid: Option<String>,
+    /// Временная метка загрузки в формате "YYYY-MM-DD HH:MM:SS"
+    pub uploaded_at: String,
+}
+
+/// Результат `upload_project_knowledge`
+#[derive(Debug, Clone, Serialize)]
+pub struct KnowledgeUploadResult {
+    pub file_name: String,
+    pub hash: String,
+    /// Содержимое уже есть в проекте — загрузка пропущена
+    pub skipped: bool,
+    /// Имя уже загруженного файла с тем же содержимым
+    pub duplicate_of: Option<String>,
+    /// UUID нового документа (None если пропущен)
+    pub doc_uuid: Option<String>,
+    /// UUID удалённых документов с тем же именем (при замене)
+    pub replaced: Vec<String>,
+}
+
+/// Результат `reconcile_knowledge_manifest`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct KnowledgeReconcileResult {
+    /// Записи, подтверждённые живым списком
+    pub kept: usize,
+    /// Записи, которых больше нет в knowledge (удалены из манифеста)
+    pub removed: Vec<KnowledgeManifestEntry>,
+    /// Документы из knowledge, добавленные в манифест
+    pub added: Vec<KnowledgeManifestEntry>,
+}
+
+/// Ошибка операции над одним документом
+#[derive(Debug, Clone, Serialize)]
+pub struct KnowledgeError {
+    pub uuid: String,
+    pub error: String,
+}
+
+/// Результат `delete_project_knowledge`
+#[derive(Debug, Clone, Serialize)]
+pub struct KnowledgeDeleteResult {
+    pub deleted: Vec<String>,
+    pub failed: Vec<KnowledgeError>,
+}
+
+/// Результат `replace_project_knowledge`
+#[derive(Debug, Clone, Serialize)]
+pub struct KnowledgeReplaceResult {
+    /// Загруженный файл
+    pub file: KnowledgeFile,
+    /// UUID удалённых документов с тем же именем
+    pub replaced: Vec<String>,
+    /// Документы, которые не удалось удалить (остались дублями)
+    pub failed: Vec<KnowledgeError>,
+}
+
+/// Определяет Claude таб проекта
+///
+/// Явно указанный таб → таб из реестра проектов → активный таб.
+pub fn resolve_tab(project_uuid: &str, tab: Option<u8>) -> Result<u8, String> {
+    if !is_valid_uuid(project_uuid) {
+        return Err(format!("Invalid project uuid: {}", project_uuid));
+    }
+    let tab = match tab {
+        Some(tab) => tab,
+        None => get_project(project_uuid.to_string())?
+            .map(|p| p.claude_tab)
+            .unwrap_or_else(|| ACTIVE_TAB.load(Ordering::SeqCst)),
+    };
+    if !(1..=3).contains(&tab) {
+        return Err(format!("Invalid tab: {}", tab));
+    }
+    Ok(tab)
+}
+
+fn docs_path(project_uuid: &str) -> String {
+    format!("/projects/{}/docs", project_uuid)
+}
+
+/// Список документов проекта (`with_hash` — с SHA-256 содержимого)
+async fn list_docs(app: &AppHandle, tab: u8, project_uuid: &str, with_hash: bool) -> Result<Vec<KnowledgeFile>, String> {
+    let map = if with_hash {
+        format!("docs => Promise.all((Array.isArray(docs) ? docs : []).map({}))", DOC_META_HASH_JS)
+    } else {
+        format!("docs => (Array.isArray(docs) ? docs : []).map({})", DOC_META_JS)
+    };
+    let value = fetch_claude_api(app, tab, "GET", &docs_path(project_uuid), None, Some(&map), LIST_TIMEOUT_SECS).await?;
+    serde_json::from_value(value).map_err(|e| format!("Invalid docs list: {}", e))
+}
+
+/// Удаляет документы по одному, собирая ошибки
+async fn delete_docs(app: &AppHandle, tab: u8, project_uuid: &str, doc_uuids: &[String]) -> KnowledgeDeleteResult {
+    let mut result = KnowledgeDeleteResult { deleted: Vec::new(), failed: Vec::new() };
+
+    for uuid in doc_uuids {
+        if !is_valid_uuid(uuid) {
+            result.failed.push(KnowledgeError { uuid: uuid.clone(), error: "Invalid document uuid".to_string() });
+            continue;
+        }
+        let path = format!("{}/{}", docs_path(project_uuid), uuid);
+        match fetch_claude_api(app, tab, "DELETE", &path, None, None, LIST_TIMEOUT_SECS).await {
+            Ok(_) => result.deleted.push(uuid.clone()),
+            Err(error) => result.failed.push(KnowledgeError { uuid: uuid.clone(), error }),
+        }
+    }
+
+    result
+}
+
+/// Загружает текстовый документ в knowledge проекта
+pub async fn upload_doc(
+    app: &AppHandle,
+    tab: u8,
+    project_uuid: &str,
+    file_name: &str,
+    content: &str,
+) -> Result<KnowledgeFile, String> {
+    let body = serde_json::json!({ "file_name": file_name, "content": content });
+    let value = fetch_claude_api(
+        app, tab, "POST", &docs_path(project_uuid), Some(&body), Some(DOC_META_JS), UPLOAD_TIMEOUT_SECS,
+    ).await?;
+    serde_json::from_value(value).map_err(|e| format!("Invalid upload response: {}", e))
+}
+
+/// Читает текстовый файл для knowledge (проверки — как у аттачментов)
+pub fn read_knowledge_file(path: &Path) -> Result<String, String> {
+    let data = read_attachment_bytes(path)?;
+    String::from_utf8(data).map_err(|e| format!("Ошибка чтения файла (нужен UTF-8 текст): {}", e))
+}
+
+/// SHA-256 содержимого (hex)
+pub fn content_hash(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+/// Путь к манифесту проекта
+fn get_manifest_path(project_uuid: &str) -> Option<PathBuf> {
+    get_app_data_dir().map(|dir| dir.join("knowledge").join(format!("{}.json", project_uuid)))
+}
+
+/// Читает манифест проекта (пустой, если файла нет)
+fn load_manifest(project_uuid: &str) -> Vec<KnowledgeManifestEntry> {
+    let _guard = KNOWLEDGE_MANIFEST_LOCK.lock();
+    get_manifest_path(project_uuid)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Изменяет манифест проекта под блокировкой
+fn update_manifest<F>(project_uuid: &str, change: F) -> Result<(), String>
+where
+    F: FnOnce(&mut Vec<KnowledgeManifestEntry>),
+{
+    let _guard = KNOWLEDGE_MANIFEST_LOCK.lock().map_err(|_| "Knowledge manifest lock poisoned")?;
+    let path = get_manifest_path(project_uuid).ok_or("Cannot get app data dir")?;
+
+    let mut entries: Vec<KnowledgeManifestEntry> = fs::read_to_string(&path).ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    change(&mut entries);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(&entries).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Убирает из манифеста записи удалённых документов
+fn forget_docs(project_uuid: &str, doc_uuids: &[String]) -> Result<(), String> {
+    if doc_uuids.is_empty() {
+        return Ok(());
+    }
+    update_manifest(project_uuid, |entries| {
+        entries.retain(|e| e.doc_uuid.as_ref().is_none_or(|uuid| !doc_uuids.contains(uuid)));
+    })
+}
+
+fn now() -> String {
+    Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Ищет в манифесте уже загруженное содержимое
+///
+/// `same_name_only` — учитывать только записи с тем же именем: изменённый
+/// файл синхронизируемой папки, совпавший по содержимому с другим файлом,
+/// всё равно загружается, чтобы заменить свою старую версию.
+pub fn find_uploaded<'a>(
+    manifest: &'a [KnowledgeManifestEntry],
+    hash: &str,
+    file_name: &str,
+    same_name_only: bool,
+) -> Option<&'a KnowledgeManifestEntry> {
+    manifest.iter()
+        .find(|e| e.hash == hash && (!same_name_only || e.file_name == file_name))
+}
+
+/// Сверяет манифест с живым списком knowledge
+///
+/// Запись сохраняется, если её документ есть в списке (по UUID документа,
+/// для записей без UUID — по хэшу). Документы без записи добавляются
+/// в манифест с хэшем их содержимого.
+///
+/// # Returns
+/// Новый манифест и результат сверки
+pub fn reconcile_manifest(
+    manifest: Vec<KnowledgeManifestEntry>,
+    live: &[KnowledgeFile],
+) -> (Vec<KnowledgeManifestEntry>, KnowledgeReconcileResult) {
+    let mut matched: Vec<&str> = Vec::new();
+    let mut kept = Vec::new();
+    let mut removed = Vec::new();
+
+    for mut entry in manifest {
+        let doc = match &entry.doc_uuid {
+            Some(uuid) => live.iter().find(|d| &d.uuid == uuid),
+            None => live.iter().find(|d| d.hash.as_deref() == Some(entry.hash.as_str())
+                && !matched.contains(&d.uuid.as_str())),
+        };
+        match doc {
+            Some(doc) => {
+                matched.push(&doc.uuid);
+                entry.doc_uuid = Some(doc.uuid.clone());
+                kept.push(entry);
+            }
+            None => removed.push(entry),
+        }
+    }
+
+    let added: Vec<KnowledgeManifestEntry> = live.iter()
+        .filter(|d| !matched.contains(&d.uuid.as_str()))
+        .filter_map(|d| d.hash.clone().map(|hash| KnowledgeManifestEntry {
+            hash,
+            file_name: d.file_name.clone(),
+            doc_uuid: Some(d.uuid.clone()),
+            uploaded_at: d.created_at.clone().unwrap_or_else(now),
+        }))
+        .collect();
+
+    let result = KnowledgeReconcileResult { kept: kept.len(), removed, added: added.clone() };
+    kept.extend(added);
+    (kept, result)
+}
+
+/// Получает список файлов в knowledge проекта
+///
+/// # Arguments
+/// * `project_uuid` - UUID проекта
+/// * `tab` - Claude таб (по умолчанию — таб проекта из реестра или активный)
+#[tauri::command]
+pub async fn list_project_knowledge(
+    app: AppHandle,
+    project_uuid: String,
+    tab: Option<u8>,
+) -> Result<Vec<KnowledgeFile>, String> {
+    let tab = resolve_tab(&project_uuid, tab)?;
+    list_docs(&app, tab, &project_uuid, false).await
+}
+
+/// Удаляет файлы из knowledge проекта
+///
+/// # Arguments
+/// * `project_uuid` - UUID проекта
+/// * `doc_uuids` - UUID документов из `list_project_knowledge`
+/// * `tab` - Claude таб (опционально)
+///
+/// # Returns
+/// Удалённые документы и ошибки по тем, что удалить не удалось
+#[tauri::command]
+pub async fn delete_project_knowledge(
+    app: AppHandle,
+    project_uuid: String,
+    doc_uuids: Vec<String>,
+    tab: Option<u8>,
+) -> Result<KnowledgeDeleteResult, String> {
+    let tab = resolve_tab(&project_uuid, tab)?;
+    let result = delete_docs(&app, tab, &project_uuid, &doc_uuids).await;
+    forget_docs(&project_uuid, &result.deleted)?;
+    Ok(result)
+}
+
+/// Заменяет файл в knowledge проекта версией с диска
+///
+/// Сначала загружается новая версия, затем удаляются документы
+/// с тем же именем — при ошибке загрузки старая версия остаётся.
+/// Если файла с таким именем нет, это обычная загрузка.
+///
+/// # Arguments
+/// * `project_uuid` - UUID проекта
+/// * `path` - путь к текстовому файлу
+/// * `file_name` - имя в knowledge (по умолчанию — имя файла)
+/// * `tab` - Claude таб (опционально)
+#[tauri::command]
+pub async fn replace_project_knowledge(
+    app: AppHandle,
+    project_uuid: String,
+    path: String,
+    file_name: Option<String>,
+    tab: Option<u8>,
+) -> Result<KnowledgeReplaceResult, String> {
+    let tab = resolve_tab(&project_uuid, tab)?;
+    let path = Path::new(&path);
+    let file_name = file_name
+        .or_else(|| path.file_name().map(|n| n.to_string_lossy().to_string()))
+        .ok_or("Cannot determine file name")?;
+    let content = read_knowledge_file(path)?;
+
+    let existing: Vec<String> = list_docs(&app, tab, &project_uuid, false).await?
+        .into_iter()
+        .filter(|doc| doc.file_name == file_name)
+        .map(|doc| doc.uuid)
+        .collect();
+
+    let file = upload_doc(&app, tab, &project_uuid, &file_name, &content).await?;
+    let removed = delete_docs(&app, tab, &project_uuid, &existing).await;
+
+    forget_docs(&project_uuid, &removed.deleted)?;
+    let entry = KnowledgeManifestEntry {
+        hash: content_hash(content.as_bytes()),
+        file_name: file.file_name.clone(),
+        doc_uuid: Some(file.uuid.clone()),
+        uploaded_at: now(),
+    };
+    update_manifest(&project_uuid, |entries| entries.push(entry))?;
+
+    Ok(KnowledgeReplaceResult { file, replaced: removed.deleted, failed: removed.failed })
+}
+
+/// Загружает файл с дедупликацией по манифесту
+///
+/// `replace_same_name` — после загрузки удалить документы с тем же именем
+/// (изменённый файл в синхронизируемой папке); пропускается только
+/// содержимое, уже загруженное под этим же именем.
+pub async fn upload_file(
+    app: &AppHandle,
+    tab: u8,
+    project_uuid: &str,
+    path: &Path,
+    file_name: String,
+    replace_same_name: bool,
+) -> Result<KnowledgeUploadResult, String> {
+    let content = read_knowledge_file(path)?;
+    let hash = content_hash(content.as_bytes());
+
+    let manifest = load_manifest(project_uuid);
+    if let Some(existing) = find_uploaded(&manifest, &hash, &file_name, replace_same_name) {
+        return Ok(KnowledgeUploadResult {
+            duplicate_of: Some(existing.file_name.clone()),
+            file_name,
+            hash,
+            skipped: true,
+            doc_uuid: None,
+            replaced: Vec::new(),
+        });
+    }
+
+    let existing: Vec<String> = if replace_same_name {
+        list_docs(app, tab, project_uuid, false).await?
+            .into_iter()
+            .filter(|doc| doc.file_name == file_name)
+            .map(|doc| doc.uuid)
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let file = upload_doc(app, tab, project_uuid, &file_name, &content).await?;
+    let removed = delete_docs(app, tab, project_uuid, &existing).await;
+
+    forget_docs(project_uuid, &removed.deleted)?;
+    let entry = KnowledgeManifestEntry {
+        hash: hash.clone(),
+        file_name: file.file_name.clone(),
+        doc_uuid: Some(file.uuid.clone()),
+        uploaded_at: now(),
+    };
+    update_manifest(project_uuid, |entries| entries.push(entry))?;
+
+    Ok(KnowledgeUploadResult {
+        file_name: file.file_name,
+        hash,
+        skipped: false,
+        duplicate_of: None,
+        doc_uuid: Some(file.uuid),
+        replaced: removed.deleted,
+    })
+}
+
+/// Удаляет из knowledge документы, загруженные под этим именем (по манифесту)
+pub async fn delete_file_by_name(
+    app: &AppHandle,
+    tab: u8,
+    project_uuid: &str,
+    file_name: &str,
+) -> Result<KnowledgeDeleteResult, String> {
+    let doc_uuids: Vec<String> = load_manifest(project_uuid).into_iter()
+        .filter(|e| e.file_name == file_name)
+        .filter_map(|e| e.doc_uuid)
+        .collect();
+    let result = delete_docs(app, tab, project_uuid, &doc_uuids).await;
+    forget_docs(project_uuid, &result.deleted)?;
+    Ok(result)
+}
+
+/// Загружает файл в knowledge проекта, пропуская уже загруженное содержимое
+///
+/// Содержимое хэшируется (SHA-256) и сверяется с манифестом проекта:
+/// повторно скачанный или пересобранный файл с тем же содержимым
+/// не расходует лимит файлов проекта.
+///
+/// # Arguments
+/// * `project_uuid` - UUID проекта
+/// * `path` - путь к текстовому файлу
+/// * `file_name` - имя в knowledge (по умолчанию — имя файла)
+/// * `tab` - Claude таб (опционально)
+#[tauri::command]
+pub async fn upload_project_knowledge(
+    app: AppHandle,
+    project_uuid: String,
+    path: String,
+    file_name: Option<String>,
+    tab: Option<u8>,
+) -> Result<KnowledgeUploadResult, String> {
+    let tab = resolve_tab(&project_uuid, tab)?;
+    let path = Path::new(&path);
+    let file_name = file_name
+        .or_else(|| path.file_name().map(|n| n.to_string_lossy().to_string()))
+        .ok_or("Cannot determine file name")?;
+    upload_file(&app, tab, &project_uuid, path, file_name, false).await
+}
+
+/// Сверяет манифест проекта с живым списком knowledge
+///
+/// Удалённые в Claude документы убираются из манифеста, загруженные
+/// в обход приложения — добавляются (хэш считается в табе по содержимому).
+#[tauri::command]
+pub async fn reconcile_knowledge_manifest(
+    app: AppHandle,
+    project_uuid: String,
+    tab: Option<u8>,
+) -> Result<KnowledgeReconcileResult, String> {
+    let tab = resolve_tab(&project_uuid, tab)?;
+    let live = list_docs(&app, tab, &project_uuid, true).await?;
+
+    let mut result = None;
+    update_manifest(&project_uuid, |entries| {
+        let (manifest, reconciled) = reconcile_manifest(std::mem::take(entries), &live);
+        *entries = manifest;
+        result = Some(reconciled);
+    })?;
+
+    result.ok_or_else(|| "Manifest reconcile failed".to_string())
+}
+
+/// Манифест загруженных в проект файлов
+#[tauri::command]
+pub fn get_knowledge_manifest(project_uuid: String) -> Result<Vec<KnowledgeManifestEntry>, String> {
+    if !is_valid_uuid(&project_uuid) {
+        return Err(format!("Invalid project uuid: {}", project_uuid));
+    }
+    Ok(load_manifest(&project_uuid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(hash: &str, name: &str, doc: Option<&str>) -> KnowledgeManifestEntry {
+        KnowledgeManifestEntry {
+            hash: hash.to_string(),
+            file_name: name.to_string(),
+            doc_uuid: doc.map(String::from),
+            uploaded_at: "2026-03-02 10:00:00".to_string(),
+        }
+    }
+
+    fn live(uuid: &str, name: &str, hash: &str) -> KnowledgeFile {
+        KnowledgeFile {
+            uuid: uuid.to_string(),
+            file_name: name.to_string(),
+            created_at: Some("2026-03-02T09:00:00Z".to_string()),
+            size: 10,
+            hash: Some(hash.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_content_hash_is_sha256_hex() {
+        assert_eq!(
+            content_hash(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_reconcile_drops_missing_and_adds_unknown() {
+        let manifest = vec![
+            entry("h1", "a.md", Some("d1")),
+            entry("h2", "b.md", Some("d2")),
+            entry("h3", "c.md", None),
+        ];
+        let docs = vec![live("d1", "a.md", "h1"), live("d3", "c.md", "h3"), live("d4", "x.md", "h4")];
+
+        let (updated, result) = reconcile_manifest(manifest, &docs);
+
+        assert_eq!(result.kept, 2);
+        assert_eq!(result.removed, vec![entry("h2", "b.md", Some("d2"))]);
+        assert_eq!(result.added.len(), 1);
+        assert_eq!(result.added[0].doc_uuid.as_deref(), Some("d4"));
+        assert_eq!(updated.len(), 3);
+        assert_eq!(updated[1].doc_uuid.as_deref(), Some("d3"));
+    }
+
+    #[test]
+    fn test_changed_synced_file_matching_other_doc_is_not_skipped() {
+        let manifest = vec![entry("h1", "a.md", Some("d1")), entry("h2", "b.md", Some("d2"))];
+
+        // a.md изменён на содержимое b.md: обычная загрузка пропускается,
+        // синхронизация папки загружает его, чтобы заменить старый a.md
+        assert_eq!(find_uploaded(&manifest, "h2", "a.md", false), Some(&manifest[1]));
+        assert_eq!(find_uploaded(&manifest, "h2", "a.md", true), None);
+        assert_eq!(find_uploaded(&manifest, "h1", "a.md", true), Some(&manifest[0]));
+    }
+}
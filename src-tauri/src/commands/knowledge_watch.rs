@@ -0,0 +1,501 @@
+//! Синхронизация локальной папки с knowledge проекта
+//!
+//! Для проекта можно выбрать папку: новые и изменённые файлы из неё
+//! ставятся в очередь на загрузку в knowledge (с дедупликацией по манифесту
+//! и заменой документа с тем же именем), удалённые — по желанию удаляются
+//! и из knowledge.
+//!
+//! Папки опрашиваются раз в `POLL_INTERVAL_SECS` (без подпапок). Файл
+//! синхронизируется, когда его размер и время изменения не менялись
+//! между двумя опросами — недописанные файлы не загружаются.
+//! Прогресс эмитится событием `knowledge-sync-progress`.
+//!
+//! Синхронизированное состояние файлов сохраняется в `knowledge_sync_state.json`,
+//! поэтому удаления, сделанные пока приложение было закрыто, тоже
+//! зеркалируются. Неудачные действия повторяются не чаще раза в
+//! `RETRY_INTERVAL_SECS`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::commands::knowledge::{delete_file_by_name, resolve_tab, upload_file};
+use crate::commands::logs::write_diagnostic;
+use crate::commands::projects::is_valid_uuid;
+use crate::downloads::paths::get_app_data_dir;
+use crate::state::KNOWLEDGE_WATCHES;
+use crate::types::{default_watch_extensions, DiagnosticEvent, KnowledgeWatch};
+
+/// Интервал опроса папок (секунды)
+const POLL_INTERVAL_SECS: u64 = 5;
+
+/// Пауза перед повтором неудачного действия с файлом (секунды)
+const RETRY_INTERVAL_SECS: u64 = 60;
+
+/// Размер и время изменения файла
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStamp {
+    pub len: u64,
+    pub modified_ms: u128,
+}
+
+/// Действие синхронизации
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncAction {
+    Upload(String),
+    Delete(String),
+}
+
+/// Событие `knowledge-sync-progress`
+#[derive(Debug, Clone, Serialize)]
+pub struct KnowledgeSyncProgress {
+    pub project_uuid: String,
+    pub file_name: String,
+    /// `upload` / `delete`
+    pub action: String,
+    /// `uploaded` / `skipped` / `deleted` / `error`
+    pub status: String,
+    pub error: Option<String>,
+    /// Сколько действий осталось в очереди проекта
+    pub queued: usize,
+}
+
+/// Синхронизированное состояние папки (сохраняется между запусками)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct SyncedFolder {
+    folder: String,
+    files: HashMap<String, FileStamp>,
+}
+
+/// Состояние папки между опросами
+#[derive(Default)]
+struct WatchSnapshot {
+    /// Папка и последнее синхронизированное состояние её файлов
+    synced: SyncedFolder,
+    /// Результат предыдущего опроса
+    seen: HashMap<String, FileStamp>,
+    /// Файлы с неудачным действием → когда можно повторить
+    retry_at: HashMap<String, Instant>,
+}
+
+/// Путь к файлу синхронизируемых папок
+fn get_knowledge_watches_path() -> Option<PathBuf> {
+    get_app_data_dir().map(|dir| dir.join("knowledge_watches.json"))
+}
+
+/// Путь к файлу синхронизированного состояния папок
+fn get_knowledge_sync_state_path() -> Option<PathBuf> {
+    get_app_data_dir().map(|dir| dir.join("knowledge_sync_state.json"))
+}
+
+/// Читает синхронизированное состояние папок (project_uuid → папка)
+fn read_sync_state(path: &Path) -> HashMap<String, SyncedFolder> {
+    fs::read_to_string(path).ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Сохраняет синхронизированное состояние папок
+fn write_sync_state(path: &Path, state: &HashMap<String, SyncedFolder>) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let json = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| e.to_string())
+}
+
+/// Загружает синхронизируемые папки в состояние (при старте)
+pub fn load_knowledge_watches() {
+    let watches: Vec<KnowledgeWatch> = get_knowledge_watches_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+
+    if let Ok(mut state) = KNOWLEDGE_WATCHES.lock() {
+        *state = watches;
+    }
+}
+
+/// Сохраняет синхронизируемые папки
+fn save_knowledge_watches(watches: &[KnowledgeWatch]) -> Result<(), String> {
+    let path = get_knowledge_watches_path().ok_or("Cannot get app data dir")?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let json = serde_json::to_string_pretty(watches).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Изменяет список папок и сохраняет его
+fn modify_watches<F, T>(change: F) -> Result<T, String>
+where
+    F: FnOnce(&mut Vec<KnowledgeWatch>) -> Result<T, String>,
+{
+    let mut watches = KNOWLEDGE_WATCHES.lock().map_err(|e| e.to_string())?;
+    let result = change(&mut watches)?;
+    save_knowledge_watches(&watches)?;
+    Ok(result)
+}
+
+/// Расширения без точки в нижнем регистре, без повторов
+fn normalize_extensions(extensions: Vec<String>) -> Vec<String> {
+    let mut extensions: Vec<String> = extensions.iter()
+        .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+        .filter(|e| !e.is_empty())
+        .collect();
+    extensions.sort();
+    extensions.dedup();
+    extensions
+}
+
+/// Читает файлы папки с подходящими расширениями
+///
+/// Скрытые и временные файлы (`.name`, `~name`) пропускаются.
+fn scan_folder(folder: &Path, extensions: &[String]) -> Result<HashMap<String, FileStamp>, String> {
+    let mut files = HashMap::new();
+
+    for entry in fs::read_dir(folder).map_err(|e| e.to_string())?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') || name.starts_with('~') {
+            continue;
+        }
+        let matches = Path::new(&name).extension()
+            .map(|ext| extensions.contains(&ext.to_string_lossy().to_lowercase()))
+            .unwrap_or(false);
+        if !matches {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else { continue };
+        if !metadata.is_file() {
+            continue;
+        }
+        let modified_ms = metadata.modified().ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_millis())
+            .unwrap_or(0);
+        files.insert(name, FileStamp { len: metadata.len(), modified_ms });
+    }
+
+    Ok(files)
+}
+
+/// Сравнивает текущий опрос с предыдущим и с синхронизированным состоянием
+///
+/// Загружаются файлы, не менявшиеся с прошлого опроса и отличающиеся от
+/// синхронизированных. Удаление ставится только при `mirror_deletions`.
+pub fn diff_folder(
+    seen: &HashMap<String, FileStamp>,
+    synced: &HashMap<String, FileStamp>,
+    current: &HashMap<String, FileStamp>,
+    mirror_deletions: bool,
+) -> Vec<SyncAction> {
+    let mut actions: Vec<SyncAction> = current.iter()
+        .filter(|(name, stamp)| seen.get(*name) == Some(*stamp) && synced.get(*name) != Some(*stamp))
+        .map(|(name, _)| SyncAction::Upload(name.clone()))
+        .collect();
+
+    if mirror_deletions {
+        actions.extend(synced.keys()
+            .filter(|name| !current.contains_key(*name))
+            .map(|name| SyncAction::Delete(name.clone())));
+    }
+
+    actions.sort_by(|a, b| {
+        let key = |action: &SyncAction| match action {
+            SyncAction::Upload(name) => (0, name.clone()),
+            SyncAction::Delete(name) => (1, name.clone()),
+        };
+        key(a).cmp(&key(b))
+    });
+    actions
+}
+
+/// Учитывает результат действия в синхронизированном состоянии
+///
+/// Файл считается синхронизированным только после успешной загрузки;
+/// после неудачного удаления он остаётся в состоянии, чтобы удаление повторилось.
+fn record_result(synced: &mut HashMap<String, FileStamp>, current: &HashMap<String, FileStamp>, action: &SyncAction, ok: bool) {
+    if !ok {
+        return;
+    }
+    match action {
+        SyncAction::Upload(name) => {
+            if let Some(stamp) = current.get(name) {
+                synced.insert(name.clone(), *stamp);
+            }
+        }
+        SyncAction::Delete(name) => { synced.remove(name); }
+    }
+}
+
+/// Выполняет одно действие синхронизации
+async fn run_action(app: &AppHandle, watch: &KnowledgeWatch, action: &SyncAction) -> Result<String, String> {
+    let tab = resolve_tab(&watch.project_uuid, None)?;
+    match action {
+        SyncAction::Upload(name) => {
+            let path = Path::new(&watch.folder).join(name);
+            let result = upload_file(app, tab, &watch.project_uuid, &path, name.clone(), true).await?;
+            Ok(if result.skipped { "skipped" } else { "uploaded" }.to_string())
+        }
+        SyncAction::Delete(name) => {
+            let result = delete_file_by_name(app, tab, &watch.project_uuid, name).await?;
+            match result.failed.first() {
+                Some(failed) => Err(failed.error.clone()),
+                None => Ok("deleted".to_string()),
+            }
+        }
+    }
+}
+
+/// Один опрос папки проекта: поиск изменений и их синхронизация
+///
+/// Возвращает `true`, если синхронизированное состояние изменилось.
+async fn sync_watch(app: &AppHandle, watch: &KnowledgeWatch, snapshot: &mut WatchSnapshot) -> bool {
+    // Папку сменили — начинаем заново
+    if snapshot.synced.folder != watch.folder {
+        *snapshot = WatchSnapshot::default();
+        snapshot.synced.folder = watch.folder.clone();
+    }
+
+    // Папка недоступна (отключённый диск) — не считаем файлы удалёнными
+    let Ok(current) = scan_folder(Path::new(&watch.folder), &watch.extensions) else { return false };
+
+    let before = snapshot.synced.files.clone();
+    let now = Instant::now();
+    snapshot.retry_at.retain(|_, at| *at > now);
+
+    let actions: Vec<SyncAction> = diff_folder(&snapshot.seen, &snapshot.synced.files, &current, watch.mirror_deletions)
+        .into_iter()
+        .filter(|action| {
+            let (SyncAction::Upload(name) | SyncAction::Delete(name)) = action;
+            !snapshot.retry_at.contains_key(name)
+        })
+        .collect();
+    if !watch.mirror_deletions {
+        snapshot.synced.files.retain(|name, _| current.contains_key(name));
+    }
+
+    for (index, action) in actions.iter().enumerate() {
+        let (kind, name) = match action {
+            SyncAction::Upload(name) => ("upload", name),
+            SyncAction::Delete(name) => ("delete", name),
+        };
+
+        let result = run_action(app, watch, action).await;
+        record_result(&mut snapshot.synced.files, &current, action, result.is_ok());
+
+        let (status, error) = match result {
+            Ok(status) => (status, None),
+            Err(e) => {
+                snapshot.retry_at.insert(name.clone(), Instant::now() + Duration::from_secs(RETRY_INTERVAL_SECS));
+                let _ = write_diagnostic(
+                    DiagnosticEvent::KnowledgeSyncError,
+                    serde_json::json!({
+                        "project_uuid": watch.project_uuid,
+                        "file_name": name,
+                        "action": kind,
+                        "error": e,
+                    }),
+                );
+                ("error".to_string(), Some(e))
+            }
+        };
+
+        let _ = app.emit("knowledge-sync-progress", KnowledgeSyncProgress {
+            project_uuid: watch.project_uuid.clone(),
+            file_name: name.clone(),
+            action: kind.to_string(),
+            status,
+            error,
+            queued: actions.len() - index - 1,
+        });
+    }
+
+    snapshot.seen = current;
+    snapshot.synced.files != before
+}
+
+/// Фоновый опрос синхронизируемых папок (запускается из setup)
+///
+/// Синхронизированное состояние загружается из `knowledge_sync_state.json`
+/// и сохраняется после опросов, в которых оно изменилось.
+pub async fn run_knowledge_watcher(app: AppHandle) {
+    let state_path = get_knowledge_sync_state_path();
+    let mut snapshots: HashMap<String, WatchSnapshot> = state_path.as_deref()
+        .map(read_sync_state)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(uuid, synced)| (uuid, WatchSnapshot { synced, ..WatchSnapshot::default() }))
+        .collect();
+
+    loop {
+        tokio::time::sleep(Duration::from_secs(POLL_INTERVAL_SECS)).await;
+
+        let watches: Vec<KnowledgeWatch> = KNOWLEDGE_WATCHES.lock()
+            .map(|w| w.clone())
+            .unwrap_or_default();
+
+        // Приостановленные папки сохраняют состояние до возобновления
+        let before = snapshots.len();
+        snapshots.retain(|uuid, _| watches.iter().any(|w| &w.project_uuid == uuid));
+        let mut changed = snapshots.len() != before;
+
+        for watch in watches.iter().filter(|w| w.enabled) {
+            let snapshot = snapshots.entry(watch.project_uuid.clone()).or_default();
+            changed |= sync_watch(&app, watch, snapshot).await;
+        }
+
+        if let (true, Some(path)) = (changed, state_path.as_deref()) {
+            let state: HashMap<String, SyncedFolder> = snapshots.iter()
+                .map(|(uuid, snapshot)| (uuid.clone(), snapshot.synced.clone()))
+                .collect();
+            if let Err(e) = write_sync_state(path, &state) {
+                log::warn!("Failed to save knowledge sync state: {}", e);
+            }
+        }
+    }
+}
+
+/// Добавляет (или заменяет) синхронизируемую папку проекта
+///
+/// # Arguments
+/// * `project_uuid` - UUID проекта
+/// * `folder` - папка на диске
+/// * `mirror_deletions` - удалять из knowledge удалённые файлы (по умолчанию нет)
+/// * `extensions` - расширения файлов (по умолчанию md, txt, html, csv, json)
+#[tauri::command]
+pub fn add_knowledge_watch(
+    project_uuid: String,
+    folder: String,
+    mirror_deletions: Option<bool>,
+    extensions: Option<Vec<String>>,
+) -> Result<KnowledgeWatch, String> {
+    if !is_valid_uuid(&project_uuid) {
+        return Err(format!("Invalid project uuid: {}", project_uuid));
+    }
+    if !Path::new(&folder).is_dir() {
+        return Err(format!("Folder not found: {}", folder));
+    }
+
+    let extensions = normalize_extensions(extensions.unwrap_or_else(default_watch_extensions));
+    if extensions.is_empty() {
+        return Err("No file extensions to watch".to_string());
+    }
+
+    let watch = KnowledgeWatch {
+        project_uuid,
+        folder,
+        mirror_deletions: mirror_deletions.unwrap_or(false),
+        extensions,
+        enabled: true,
+    };
+
+    modify_watches(|watches| {
+        watches.retain(|w| w.project_uuid != watch.project_uuid);
+        watches.push(watch.clone());
+        Ok(watch)
+    })
+}
+
+/// Убирает синхронизируемую папку проекта (knowledge не трогается)
+#[tauri::command]
+pub fn remove_knowledge_watch(project_uuid: String) -> Result<(), String> {
+    modify_watches(|watches| {
+        let before = watches.len();
+        watches.retain(|w| w.project_uuid != project_uuid);
+        if watches.len() == before {
+            return Err(format!("No watched folder for project: {}", project_uuid));
+        }
+        Ok(())
+    })
+}
+
+/// Список синхронизируемых папок
+#[tauri::command]
+pub fn list_knowledge_watches() -> Vec<KnowledgeWatch> {
+    KNOWLEDGE_WATCHES.lock()
+        .map(|w| w.clone())
+        .unwrap_or_default()
+}
+
+/// Приостанавливает или возобновляет синхронизацию папки проекта
+#[tauri::command]
+pub fn set_knowledge_watch_enabled(project_uuid: String, enabled: bool) -> Result<KnowledgeWatch, String> {
+    modify_watches(|watches| {
+        let watch = watches.iter_mut()
+            .find(|w| w.project_uuid == project_uuid)
+            .ok_or_else(|| format!("No watched folder for project: {}", project_uuid))?;
+        watch.enabled = enabled;
+        Ok(watch.clone())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stamps(files: &[(&str, u64)]) -> HashMap<String, FileStamp> {
+        files.iter()
+            .map(|(name, len)| (name.to_string(), FileStamp { len: *len, modified_ms: 1000 }))
+            .collect()
+    }
+
+    #[test]
+    fn test_upload_only_stable_changed_files() {
+        let seen = stamps(&[("a.md", 10), ("b.md", 5), ("c.md", 7)]);
+        let synced = stamps(&[("a.md", 10)]);
+        // b.md ещё пишется (размер изменился), d.md появился только сейчас
+        let current = stamps(&[("a.md", 10), ("b.md", 6), ("c.md", 7), ("d.md", 1)]);
+
+        let actions = diff_folder(&seen, &synced, &current, false);
+        assert_eq!(actions, vec![SyncAction::Upload("c.md".to_string())]);
+    }
+
+    #[test]
+    fn test_deletions_mirrored_only_when_enabled() {
+        let synced = stamps(&[("a.md", 10), ("gone.md", 3)]);
+        let current = stamps(&[("a.md", 10)]);
+
+        assert!(diff_folder(&current, &synced, &current, false).is_empty());
+        assert_eq!(
+            diff_folder(&current, &synced, &current, true),
+            vec![SyncAction::Delete("gone.md".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_only_successful_actions_recorded() {
+        let current = stamps(&[("a.md", 10), ("b.md", 4)]);
+        let mut synced = stamps(&[("gone.md", 3), ("kept.md", 2)]);
+
+        record_result(&mut synced, &current, &SyncAction::Upload("a.md".to_string()), true);
+        record_result(&mut synced, &current, &SyncAction::Upload("b.md".to_string()), false);
+        record_result(&mut synced, &current, &SyncAction::Delete("gone.md".to_string()), true);
+        record_result(&mut synced, &current, &SyncAction::Delete("kept.md".to_string()), false);
+
+        assert_eq!(synced, stamps(&[("a.md", 10), ("kept.md", 2)]));
+    }
+
+    #[test]
+    fn test_sync_state_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("knowledge_sync_state.json");
+        assert!(read_sync_state(&path).is_empty());
+
+        let state = HashMap::from([(
+            "project".to_string(),
+            SyncedFolder { folder: "/notes".to_string(), files: stamps(&[("a.md", 10)]) },
+        )]);
+        write_sync_state(&path, &state).unwrap();
+        assert_eq!(read_sync_state(&path), state);
+    }
+}
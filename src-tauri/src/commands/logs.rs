@@ -3,14 +3,114 @@
 //! Этот модуль содержит Tauri команды для:
 //! - Чтения и записи лога архивов (скачанные из Claude файлы)
 //! - Чтения и записи лога загрузок (все загруженные файлы)
+//! - Запросов к логам с фильтрами и страницами, очистки от удалённых файлов
+//! - Чтения лога бэкенда и смены уровней логирования
+//!
+//! Логи — append-only JSONL (`utils/jsonl.rs`): событие дописывает строку,
+//! повторы (тот же архив, тот же путь загрузки) сворачиваются при чтении.
+//! Когда лог превышает MAX_LOG_FILE_SIZE, он ротируется и сжимается в фоне
+//! до актуальных записей с прежними лимитами.
 
-use std::fs;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
 use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use crate::types::{ArchiveLogEntry, DownloadEntry, DiagnosticEntry};
-use crate::state::{ARCHIVE_LOG_LOCK, DIAGNOSTICS_LOG_LOCK};
+use crate::commands::projects::project_name_for_url;
+use crate::commands::usage::compact_usage_log;
+use crate::types::{ArchiveLogEntry, DownloadEntry, DiagnosticEntry, DiagnosticEvent, LogSettings};
+use crate::downloads::index::{query_log, LogPage, LogQuery};
+use crate::state::{
+    ARCHIVE_LOG_LOCK, DIAGNOSTICS_LOG_LOCK, DOWNLOADS_LOG_LOCK,
+    ARCHIVE_LOG_INDEX, DOWNLOADS_LOG_INDEX,
+};
 use crate::downloads::paths::{get_archive_log_path, get_downloads_log_path, get_diagnostics_log_path};
-use crate::utils::dimensions::limits::{MAX_ARCHIVE_LOG_ENTRIES, MAX_DIAGNOSTICS_ENTRIES};
+use crate::utils::dimensions::limits::{
+    DEFAULT_LOG_PAGE_SIZE, MAX_ARCHIVE_LOG_ENTRIES, MAX_DIAGNOSTICS_ENTRIES, MAX_DOWNLOADS_LOG_ENTRIES,
+    MAX_LOG_PAGE_SIZE,
+};
+use crate::utils::logger::{self, BackendLogLine, BackendLogQuery};
+use crate::utils::jsonl::{
+    append_record, migrate_json_array, read_records, remove_log, rewrite_records, rotated_path,
+};
+
+// ============================================================================
+// Сжатие и миграция
+// ============================================================================
+
+/// Сжимает лог: сворачивает записи и атомарно переписывает файл
+///
+/// Без `force` сжимает только ротированный лог (после `append_record`).
+pub fn compact_log<T>(
+    lock: &Mutex<()>,
+    log_path: Option<std::path::PathBuf>,
+    fold: fn(Vec<T>) -> Vec<T>,
+    force: bool,
+) -> Result<(), String>
+where
+    T: serde::de::DeserializeOwned + Serialize,
+{
+    let _guard = lock.lock().map_err(|_| "Log lock poisoned")?;
+    let log_path = log_path.ok_or("Cannot get log path")?;
+
+    if !force && !rotated_path(&log_path).exists() {
+        return Ok(());
+    }
+    rewrite_records(&log_path, &fold(read_records(&log_path)))
+}
+
+fn compact_archive_log(force: bool) -> Result<(), String> {
+    compact_log(&ARCHIVE_LOG_LOCK, get_archive_log_path(), fold_archive_log, force)
+}
+
+fn compact_downloads_log(force: bool) -> Result<(), String> {
+    compact_log(&DOWNLOADS_LOG_LOCK, get_downloads_log_path(), fold_downloads_log, force)
+}
+
+fn compact_diagnostics_log(force: bool) -> Result<(), String> {
+    compact_log(&DIAGNOSTICS_LOG_LOCK, get_diagnostics_log_path(), fold_diagnostics, force)
+}
+
+/// Запускает сжатие лога в фоне (не задерживает запись события)
+pub fn schedule_compaction(compact: fn(bool) -> Result<(), String>) {
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Err(e) = compact(false) {
+            log::error!("Compaction failed: {}", e);
+        }
+    });
+}
+
+/// Сжимает все логи (перед сбросом данных приложения)
+pub fn compact_logs() {
+    for compact in [compact_archive_log, compact_downloads_log, compact_diagnostics_log, compact_usage_log] {
+        if let Err(e) = compact(true) {
+            log::error!("Compaction failed: {}", e);
+        }
+    }
+}
+
+/// Переносит логи из JSON-массивов (`*.json`) в JSONL
+///
+/// Вызывается при старте; повторный вызов ничего не делает.
+pub fn migrate_legacy_logs() {
+    fn migrate<T>(lock: &Mutex<()>, log_path: Option<std::path::PathBuf>)
+    where
+        T: serde::de::DeserializeOwned + Serialize,
+    {
+        let (Ok(_guard), Some(log_path)) = (lock.lock(), log_path) else { return };
+        match migrate_json_array::<T>(&log_path) {
+            Ok(0) => {}
+            Ok(count) => log::info!("Migrated {} entries to {}", count, log_path.display()),
+            Err(e) => log::error!("Migration of {} failed: {}", log_path.display(), e),
+        }
+    }
+
+    migrate::<ArchiveLogEntry>(&ARCHIVE_LOG_LOCK, get_archive_log_path());
+    migrate::<DownloadEntry>(&DOWNLOADS_LOG_LOCK, get_downloads_log_path());
+    migrate::<DiagnosticEntry>(&DIAGNOSTICS_LOG_LOCK, get_diagnostics_log_path());
+}
 
 // ============================================================================
 // Лог архивов (скачанные из Claude файлы)
@@ -40,127 +140,86 @@ fn normalize_filename(name: &str) -> String {
     name.to_string()
 }
 
-/// Записывает запись в лог архивов
-///
-/// Потокобезопасная запись с использованием мьютекса.
-/// При совпадении filename + claude_url обновляет timestamp и увеличивает счётчик.
-/// Ограничивает размер лога до MAX_ARCHIVE_LOG_ENTRIES записей.
-///
-/// # Arguments
-/// * `entry` - запись для добавления
-///
-/// # Returns
-/// * `Ok(())` - запись успешно добавлена или обновлена
-/// * `Err(String)` - ошибка записи
-pub fn write_archive_log(entry: ArchiveLogEntry) -> Result<(), String> {
-    // Блокируем доступ к файлу для предотвращения race condition
-    let _guard = ARCHIVE_LOG_LOCK.lock()
-        .map_err(|_| "Archive log lock poisoned")?;
-    
-    let log_path = get_archive_log_path().ok_or("Cannot get log path")?;
-    
-    // Создаём директорию если нет
-    if let Some(parent) = log_path.parent() {
-        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-    }
-    
-    // Читаем существующий лог или создаём новый
-    let mut entries: Vec<ArchiveLogEntry> = if log_path.exists() {
-        let content = fs::read_to_string(&log_path).unwrap_or_default();
-        serde_json::from_str(&content).unwrap_or_default()
-    } else {
-        Vec::new()
-    };
-    
-    // Ищем дубль по нормализованному filename + claude_url
-    let normalized = normalize_filename(&entry.filename);
-    if let Some(existing) = entries.iter_mut().find(|e| {
-        normalize_filename(&e.filename) == normalized && e.claude_url == entry.claude_url
-    }) {
-        // Обновляем существующую запись
-        existing.timestamp = entry.timestamp;
-        existing.filename = normalized; // Сохраняем чистое имя без " (N)"
-        existing.download_count += 1;
-        if !entry.file_path.is_empty() {
-            existing.file_path = entry.file_path;
-        }
-    } else {
-        // Новая запись — сохраняем с нормализованным именем
-        let mut new_entry = entry;
-        new_entry.filename = normalized;
-        entries.push(new_entry);
-    }
-    
-    // Ограничиваем размер лога
-    if entries.len() > MAX_ARCHIVE_LOG_ENTRIES {
-        entries = entries.split_off(entries.len() - MAX_ARCHIVE_LOG_ENTRIES);
-    }
-    
-    // Сохраняем
-    let json = serde_json::to_string_pretty(&entries).map_err(|e| e.to_string())?;
-    fs::write(&log_path, json).map_err(|e| e.to_string())?;
-    
-    Ok(())
-}
-
-/// Получает все записи из лога архивов
+/// Сворачивает записи лога архивов
 ///
-/// При чтении выполняет миграцию: объединяет дубли (по filename + claude_url),
-/// сохраняя последний timestamp и суммируя download_count.
-#[tauri::command]
-pub fn get_archive_log() -> Result<Vec<ArchiveLogEntry>, String> {
-    let log_path = get_archive_log_path().ok_or("Cannot get log path")?;
-    
-    if !log_path.exists() {
-        return Ok(Vec::new());
-    }
-    
-    let content = fs::read_to_string(&log_path).map_err(|e| e.to_string())?;
-    let entries: Vec<ArchiveLogEntry> = serde_json::from_str(&content).unwrap_or_default();
-    let original_len = entries.len();
-    
-    // Дедупликация: объединяем записи с одинаковыми normalized filename + claude_url
+/// Записи с одинаковыми normalized filename + claude_url объединяются
+/// на месте первой: последний timestamp и file_path, сумма download_count.
+/// Остаются последние MAX_ARCHIVE_LOG_ENTRIES записей.
+fn fold_archive_log(entries: Vec<ArchiveLogEntry>) -> Vec<ArchiveLogEntry> {
     let mut deduped: Vec<ArchiveLogEntry> = Vec::new();
     for mut entry in entries {
         let normalized = normalize_filename(&entry.filename);
         if let Some(existing) = deduped.iter_mut().find(|e| {
-            normalize_filename(&e.filename) == normalized && e.claude_url == entry.claude_url
+            e.filename == normalized && e.claude_url == entry.claude_url
         }) {
             // Берём более позднюю дату
             if entry.timestamp > existing.timestamp {
                 existing.timestamp = entry.timestamp;
             }
-            existing.filename = normalized; // Чистое имя
             existing.download_count += entry.download_count;
             if !entry.file_path.is_empty() {
                 existing.file_path = entry.file_path;
             }
         } else {
-            entry.filename = normalized;
+            entry.filename = normalized; // Чистое имя без " (N)"
             deduped.push(entry);
         }
     }
-    
-    // Если были дубли — перезаписываем файл (одноразовая миграция)
-    if deduped.len() != original_len {
-        if let Ok(json) = serde_json::to_string_pretty(&deduped) {
-            let _ = fs::write(&log_path, &json);
-        }
+
+    if deduped.len() > MAX_ARCHIVE_LOG_ENTRIES {
+        deduped = deduped.split_off(deduped.len() - MAX_ARCHIVE_LOG_ENTRIES);
+    }
+    deduped
+}
+
+/// Записывает запись в лог архивов
+///
+/// Потокобезопасная запись с использованием мьютекса: строка дописывается
+/// в конец лога. Повтор filename + claude_url при чтении объединяется
+/// с прежней записью (новый timestamp, счётчик +1).
+///
+/// # Arguments
+/// * `entry` - запись для добавления
+///
+/// # Returns
+/// * `Ok(())` - запись успешно добавлена
+/// * `Err(String)` - ошибка записи
+pub fn write_archive_log(entry: ArchiveLogEntry) -> Result<(), String> {
+    let rotated = {
+        // Блокируем доступ к файлу для предотвращения race condition
+        let _guard = ARCHIVE_LOG_LOCK.lock()
+            .map_err(|_| "Archive log lock poisoned")?;
+        let log_path = get_archive_log_path().ok_or("Cannot get log path")?;
+        append_record(&log_path, &entry)?
+    };
+
+    if rotated {
+        schedule_compaction(compact_archive_log);
     }
-    
-    Ok(deduped)
+    Ok(())
+}
+
+/// Получает все записи из лога архивов
+///
+/// Дубли (по filename + claude_url) объединяются: последний timestamp,
+/// сумма download_count.
+#[tauri::command]
+pub fn get_archive_log() -> Result<Vec<ArchiveLogEntry>, String> {
+    let _guard = ARCHIVE_LOG_LOCK.lock()
+        .map_err(|_| "Archive log lock poisoned")?;
+    let log_path = get_archive_log_path().ok_or("Cannot get log path")?;
+
+    Ok(fold_archive_log(read_records(&log_path)))
 }
 
 /// Очищает лог архивов
 #[tauri::command]
 pub fn clear_archive_log() -> Result<(), String> {
+    let _guard = ARCHIVE_LOG_LOCK.lock()
+        .map_err(|_| "Archive log lock poisoned")?;
     let log_path = get_archive_log_path().ok_or("Cannot get log path")?;
-    
-    if log_path.exists() {
-        fs::remove_file(&log_path).map_err(|e| e.to_string())?;
-    }
-    
-    Ok(())
+
+    remove_log(&log_path)
 }
 
 /// Добавляет запись в лог архивов
@@ -172,18 +231,18 @@ pub fn clear_archive_log() -> Result<(), String> {
 /// * `file_path` - полный путь к файлу (опционально)
 #[tauri::command]
 pub fn add_archive_log_entry(
-    tab: u8, 
-    filename: String, 
-    claude_url: String, 
+    tab: u8,
+    filename: String,
+    claude_url: String,
     file_path: Option<String>
 ) -> Result<(), String> {
-    // Извлекаем имя проекта из URL
-    let project_name = if claude_url.contains("/project/") {
-        "Project".to_string()
-    } else {
-        "".to_string()
+    // Имя проекта из реестра (по UUID в URL или по URL чата проекта)
+    let project_name = match project_name_for_url(&claude_url) {
+        Some(name) => name,
+        None if claude_url.contains("/project/") => "Project".to_string(),
+        None => "".to_string(),
     };
-    
+
     let entry = ArchiveLogEntry {
         timestamp: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
         tab,
@@ -193,7 +252,7 @@ pub fn add_archive_log_entry(
         project_name,
         download_count: 1,
     };
-    
+
     write_archive_log(entry)
 }
 
@@ -201,21 +260,81 @@ pub fn add_archive_log_entry(
 // Лог загрузок (все загруженные файлы)
 // ============================================================================
 
+/// Сворачивает записи лога загрузок
+///
+/// Для каждого пути остаётся последняя запись — на месте последнего
+/// появления. Остаются последние MAX_DOWNLOADS_LOG_ENTRIES записей.
+fn fold_downloads_log(entries: Vec<DownloadEntry>) -> Vec<DownloadEntry> {
+    let mut seen = std::collections::HashSet::new();
+    let mut folded: Vec<DownloadEntry> = entries.into_iter()
+        .rev()
+        .filter(|e| seen.insert(e.file_path.clone()))
+        .take(MAX_DOWNLOADS_LOG_ENTRIES)
+        .collect();
+    folded.reverse();
+    folded
+}
+
+/// Дописывает запись о загрузке в лог
+///
+/// Запись для уже залогированного пути (дубль, указывающий на существующий
+/// файл) при чтении заменяет прежнюю и переносится в конец.
+pub fn append_download_entry(entry: &DownloadEntry) -> Result<(), String> {
+    let rotated = {
+        let _guard = DOWNLOADS_LOG_LOCK.lock()
+            .map_err(|_| "Downloads log lock poisoned")?;
+        let log_path = get_downloads_log_path().ok_or("Cannot get log path")?;
+        append_record(&log_path, entry)?
+    };
+
+    if rotated {
+        schedule_compaction(compact_downloads_log);
+    }
+    Ok(())
+}
+
+/// Удаляет из лога загрузок записи, подходящие под условие
+///
+/// # Returns
+/// Удалённые записи
+pub fn remove_download_entries<F>(remove: F) -> Result<Vec<DownloadEntry>, String>
+where
+    F: Fn(&DownloadEntry) -> bool,
+{
+    let _guard = DOWNLOADS_LOG_LOCK.lock()
+        .map_err(|_| "Downloads log lock poisoned")?;
+    let log_path = get_downloads_log_path().ok_or("Cannot get log path")?;
+
+    let (removed, kept): (Vec<DownloadEntry>, Vec<DownloadEntry>) =
+        fold_downloads_log(read_records(&log_path)).into_iter().partition(|e| remove(e));
+
+    if !removed.is_empty() {
+        rewrite_records(&log_path, &kept)?;
+    }
+    Ok(removed)
+}
+
+/// Читает лог загрузок (без проверки файлов на диске)
+pub fn read_downloads_log() -> Result<Vec<DownloadEntry>, String> {
+    let _guard = DOWNLOADS_LOG_LOCK.lock()
+        .map_err(|_| "Downloads log lock poisoned")?;
+    let log_path = get_downloads_log_path().ok_or("Cannot get log path")?;
+
+    Ok(fold_downloads_log(read_records(&log_path)))
+}
+
 /// Получает все записи из лога загрузок
 ///
 /// Автоматически фильтрует несуществующие файлы и обновляет лог.
 #[tauri::command]
 pub fn get_downloads_log() -> Result<Vec<DownloadEntry>, String> {
+    let _guard = DOWNLOADS_LOG_LOCK.lock()
+        .map_err(|_| "Downloads log lock poisoned")?;
     let log_path = get_downloads_log_path().ok_or("Cannot get log path")?;
-    
-    if !log_path.exists() {
-        return Ok(Vec::new());
-    }
-    
-    let content = fs::read_to_string(&log_path).map_err(|e| e.to_string())?;
-    let entries: Vec<DownloadEntry> = serde_json::from_str(&content).unwrap_or_default();
+
+    let entries = fold_downloads_log(read_records(&log_path));
     let original_len = entries.len();
-    
+
     // Фильтруем только существующие файлы
     let valid_entries: Vec<DownloadEntry> = entries
         .into_iter()
@@ -224,90 +343,360 @@ pub fn get_downloads_log() -> Result<Vec<DownloadEntry>, String> {
             path.exists()
         })
         .collect();
-    
+
     // Если что-то отфильтровали — перезаписываем лог
     if valid_entries.len() != original_len {
-        if let Ok(json) = serde_json::to_string_pretty(&valid_entries) {
-            let _ = fs::write(&log_path, &json);
-        }
+        let _ = rewrite_records(&log_path, &valid_entries);
     }
-    
+
     Ok(valid_entries)
 }
 
 // ============================================================================
-// Лог диагностики (технические события для отладки)
+// Запросы к логам
 // ============================================================================
 
-/// Записывает событие в лог диагностики
+/// Запрос к логу загрузок: фильтры, сортировка, страница
 ///
-/// Потокобезопасная запись с ротацией при превышении лимита.
+/// В отличие от `get_downloads_log`, не проверяет файлы на диске —
+/// записи об удалённых файлах убирает `prune_missing_log_entries`.
+#[tauri::command]
+pub fn query_downloads_log(query: LogQuery) -> Result<LogPage<DownloadEntry>, String> {
+    let log_path = get_downloads_log_path().ok_or("Cannot get log path")?;
+    query_log(&DOWNLOADS_LOG_INDEX, &log_path, || {
+        let _guard = DOWNLOADS_LOG_LOCK.lock();
+        fold_downloads_log(read_records(&log_path))
+    }, &query)
+}
+
+/// Запрос к логу архивов: фильтры, сортировка, страница
+#[tauri::command]
+pub fn query_archive_log(query: LogQuery) -> Result<LogPage<ArchiveLogEntry>, String> {
+    let log_path = get_archive_log_path().ok_or("Cannot get log path")?;
+    query_log(&ARCHIVE_LOG_INDEX, &log_path, || {
+        let _guard = ARCHIVE_LOG_LOCK.lock();
+        fold_archive_log(read_records(&log_path))
+    }, &query)
+}
+
+/// Результат `prune_missing_log_entries`
+#[derive(Debug, Clone, Serialize)]
+pub struct PrunedLogEntries {
+    /// Удалено записей из лога загрузок
+    pub downloads: usize,
+    /// Удалено записей из лога архивов
+    pub archive: usize,
+}
+
+/// Удаляет из логов загрузок и архивов записи о несуществующих файлах
 ///
-/// # Arguments
-/// * `event_type` - тип события (selector_broken, cdp_timeout, storage_error, send_error)
-/// * `details` - JSON-строка с деталями
+/// Записи лога архивов без пути к файлу остаются.
 #[tauri::command]
-pub fn write_diagnostic(event_type: String, details: String) -> Result<(), String> {
+pub async fn prune_missing_log_entries() -> Result<PrunedLogEntries, String> {
+    // Проверка каждого файла может занять время — вне потока команд
+    tauri::async_runtime::spawn_blocking(|| {
+        let downloads = remove_download_entries(|e| !Path::new(&e.file_path).exists())?.len();
+
+        let archive = {
+            let _guard = ARCHIVE_LOG_LOCK.lock().map_err(|_| "Archive log lock poisoned")?;
+            let log_path = get_archive_log_path().ok_or("Cannot get log path")?;
+
+            let entries = fold_archive_log(read_records(&log_path));
+            let original_len = entries.len();
+            let kept: Vec<ArchiveLogEntry> = entries.into_iter()
+                .filter(|e| e.file_path.is_empty() || Path::new(&e.file_path).exists())
+                .collect();
+
+            if kept.len() != original_len {
+                rewrite_records(&log_path, &kept)?;
+            }
+            original_len - kept.len()
+        };
+
+        Ok(PrunedLogEntries { downloads, archive })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// ============================================================================
+// Лог диагностики (технические события для отладки)
+// ============================================================================
+
+/// Последние MAX_DIAGNOSTICS_ENTRIES записей диагностики
+fn fold_diagnostics(mut entries: Vec<DiagnosticEntry>) -> Vec<DiagnosticEntry> {
+    if entries.len() > MAX_DIAGNOSTICS_ENTRIES {
+        entries = entries.split_off(entries.len() - MAX_DIAGNOSTICS_ENTRIES);
+    }
+    entries
+}
+
+/// Читает лог диагностики (последние MAX_DIAGNOSTICS_ENTRIES записей)
+pub fn read_diagnostics() -> Result<Vec<DiagnosticEntry>, String> {
     let _guard = DIAGNOSTICS_LOG_LOCK.lock()
         .map_err(|_| "Diagnostics log lock poisoned")?;
-    
     let log_path = get_diagnostics_log_path().ok_or("Cannot get diagnostics log path")?;
-    
-    // Создаём директорию если нет
-    if let Some(parent) = log_path.parent() {
-        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-    }
-    
-    // Читаем существующий лог
-    let mut entries: Vec<DiagnosticEntry> = if log_path.exists() {
-        let content = fs::read_to_string(&log_path).unwrap_or_default();
-        serde_json::from_str(&content).unwrap_or_default()
-    } else {
-        Vec::new()
+
+    Ok(fold_diagnostics(read_records(&log_path)))
+}
+
+/// Записывает событие в лог диагностики
+///
+/// Потокобезопасная запись в конец лога; при превышении размера
+/// лог ротируется и в фоне сжимается до MAX_DIAGNOSTICS_ENTRIES записей.
+///
+/// # Arguments
+/// * `event_type` - тип события (`startup_error`, `selector_broken`, `cdp_timeout`…)
+/// * `details` - детали события (`tab` — номер Claude таба, если событие о табе)
+#[tauri::command]
+pub fn write_diagnostic(event_type: DiagnosticEvent, details: Value) -> Result<(), String> {
+    // Строка с JSON (старые вызовы) сохраняется как объект
+    let details = match details {
+        Value::String(s) => serde_json::from_str(&s).unwrap_or(Value::String(s)),
+        other => other,
     };
-    
-    // Добавляем запись
-    entries.push(DiagnosticEntry {
+    let entry = DiagnosticEntry {
         timestamp: Local::now().format("%Y-%m-%dT%H:%M:%S").to_string(),
         event_type,
         details,
-    });
-    
-    // Ротация: убираем старые записи
-    if entries.len() > MAX_DIAGNOSTICS_ENTRIES {
-        entries = entries.split_off(entries.len() - MAX_DIAGNOSTICS_ENTRIES);
+    };
+
+    let rotated = {
+        let _guard = DIAGNOSTICS_LOG_LOCK.lock()
+            .map_err(|_| "Diagnostics log lock poisoned")?;
+        let log_path = get_diagnostics_log_path().ok_or("Cannot get diagnostics log path")?;
+        append_record(&log_path, &entry)?
+    };
+
+    if rotated {
+        schedule_compaction(compact_diagnostics_log);
     }
-    
-    // Сохраняем
-    let json = serde_json::to_string_pretty(&entries).map_err(|e| e.to_string())?;
-    fs::write(&log_path, json).map_err(|e| e.to_string())?;
-    
     Ok(())
 }
 
-/// Экспортирует лог диагностики в папку загрузок пользователя
+/// Фильтры запроса к логу диагностики
+#[derive(Deserialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct DiagnosticsQuery {
+    /// Типы событий (пусто — все)
+    pub types: Vec<DiagnosticEvent>,
+    /// Начало диапазона: "YYYY-MM-DD" или "YYYY-MM-DD HH:MM:SS" (включительно)
+    pub from: Option<String>,
+    /// Конец диапазона (включительно; дата без времени — весь день)
+    pub to: Option<String>,
+    pub tab: Option<u8>,
+    pub offset: usize,
+    /// Размер страницы (по умолчанию DEFAULT_LOG_PAGE_SIZE, не больше MAX_LOG_PAGE_SIZE)
+    pub limit: Option<usize>,
+}
+
+/// Ответ `query_diagnostics`
+#[derive(Serialize, Clone, Debug)]
+pub struct DiagnosticsPage {
+    /// Сколько событий подходит под все фильтры
+    pub total: usize,
+    pub offset: usize,
+    /// События по типам — с фильтрами времени и таба, без фильтра типов
+    pub counts: BTreeMap<String, usize>,
+    /// События страницы, сначала новые
+    pub items: Vec<DiagnosticEntry>,
+}
+
+/// Фильтрует события диагностики (записи — в порядке лога)
+pub fn filter_diagnostics(entries: Vec<DiagnosticEntry>, query: &DiagnosticsQuery) -> DiagnosticsPage {
+    // В логе время через `T`, в запросе — через пробел
+    let in_range = |entry: &DiagnosticEntry| {
+        let ts = entry.timestamp.replacen('T', " ", 1);
+        query.from.as_deref().is_none_or(|from| ts.as_str() >= from)
+            && query.to.as_deref().is_none_or(|to| ts.get(..to.len()).unwrap_or(&ts) <= to)
+    };
+
+    let mut counts = BTreeMap::new();
+    let mut matched = Vec::new();
+    for entry in entries.into_iter().rev() {
+        if !in_range(&entry) || query.tab.is_some_and(|tab| entry.tab() != Some(tab)) {
+            continue;
+        }
+        *counts.entry(entry.event_type.as_str().to_string()).or_default() += 1;
+        if query.types.is_empty() || query.types.contains(&entry.event_type) {
+            matched.push(entry);
+        }
+    }
+
+    let limit = query.limit.unwrap_or(DEFAULT_LOG_PAGE_SIZE).min(MAX_LOG_PAGE_SIZE);
+    DiagnosticsPage {
+        total: matched.len(),
+        offset: query.offset,
+        counts,
+        items: matched.into_iter().skip(query.offset).take(limit).collect(),
+    }
+}
+
+/// Запрос к логу диагностики: типы, время, таб, страница и счётчики по типам
+#[tauri::command]
+pub fn query_diagnostics(query: DiagnosticsQuery) -> Result<DiagnosticsPage, String> {
+    Ok(filter_diagnostics(read_diagnostics()?, &query))
+}
+
+// ============================================================================
+// Лог бэкенда (utils/logger.rs)
+// ============================================================================
+
+/// Читает лог бэкенда с фильтрами
 ///
-/// Копирует diagnostics.json в Downloads с timestamp в имени.
+/// # Arguments
+/// * `query` - уровень (минимальная важность), модуль, текст, время, число строк
 ///
 /// # Returns
-/// * `Ok(path)` - путь к экспортированному файлу
-/// * `Err(String)` - ошибка экспорта
+/// Последние подходящие строки, от старых к новым
 #[tauri::command]
-pub fn export_diagnostics() -> Result<String, String> {
-    let log_path = get_diagnostics_log_path().ok_or("Cannot get diagnostics log path")?;
-    
-    if !log_path.exists() {
-        return Err("Лог диагностики пуст".to_string());
+pub fn read_backend_log(query: BackendLogQuery) -> Result<Vec<BackendLogLine>, String> {
+    logger::read_lines(&query)
+}
+
+/// Последние строки лога бэкенда (по умолчанию DEFAULT_LOG_TAIL_LINES)
+#[tauri::command]
+pub fn tail_backend_log(lines: Option<usize>) -> Result<Vec<BackendLogLine>, String> {
+    logger::read_lines(&BackendLogQuery { limit: lines, ..Default::default() })
+}
+
+/// Текущие уровни логирования
+#[tauri::command]
+pub fn get_log_settings() -> LogSettings {
+    logger::current_settings()
+}
+
+/// Меняет уровень логирования без перезапуска
+///
+/// # Arguments
+/// * `level` - уровень (`off`, `error`, `warn`, `info`, `debug`, `trace`);
+///   `None` вместе с `target` убирает правило модуля
+/// * `target` - модуль (`webview`, `commands::scraper`) или `None` для общего уровня
+///
+/// # Returns
+/// Сохранённые настройки
+#[tauri::command]
+pub fn set_log_level(level: Option<String>, target: Option<String>) -> Result<LogSettings, String> {
+    let mut settings = logger::current_settings();
+    let level = level.map(|l| l.trim().to_lowercase());
+
+    match (target.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()), level) {
+        (Some(target), Some(level)) => { settings.targets.insert(target, level); }
+        (Some(target), None) => { settings.targets.remove(&target); }
+        (None, Some(level)) => settings.level = level,
+        (None, None) => return Err("Log level is required".to_string()),
+    }
+
+    let settings = logger::apply_settings(settings)?;
+    log::info!("Log levels changed: {} {:?}", settings.level, settings.targets);
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn archive(timestamp: &str, filename: &str, url: &str) -> ArchiveLogEntry {
+        ArchiveLogEntry {
+            timestamp: timestamp.to_string(),
+            tab: 1,
+            filename: filename.to_string(),
+            claude_url: url.to_string(),
+            file_path: String::new(),
+            project_name: String::new(),
+            download_count: 1,
+        }
+    }
+
+    fn download(file_path: &str, timestamp: &str) -> DownloadEntry {
+        DownloadEntry {
+            timestamp: timestamp.to_string(),
+            filename: file_path.to_string(),
+            file_path: file_path.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn diagnostic(timestamp: &str, event_type: DiagnosticEvent, details: Value) -> DiagnosticEntry {
+        DiagnosticEntry { timestamp: timestamp.to_string(), event_type, details }
+    }
+
+    #[test]
+    fn test_filter_diagnostics() {
+        let entries = vec![
+            diagnostic("2026-03-01T10:00:00", DiagnosticEvent::CdpTimeout, serde_json::json!({ "tab": 1 })),
+            diagnostic("2026-03-02T09:00:00", DiagnosticEvent::SendError, serde_json::json!({ "tab": 2 })),
+            diagnostic("2026-03-02T12:00:00", DiagnosticEvent::CdpTimeout, serde_json::json!({ "tab": 2 })),
+            diagnostic("2026-03-02T18:00:00", DiagnosticEvent::SelectorsUpdated, serde_json::json!({ "version": "1.2" })),
+        ];
+
+        let query = DiagnosticsQuery {
+            types: vec![DiagnosticEvent::CdpTimeout],
+            from: Some("2026-03-02".to_string()),
+            ..Default::default()
+        };
+        let page = filter_diagnostics(entries.clone(), &query);
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].timestamp, "2026-03-02T12:00:00");
+        // Счётчики — без фильтра типов
+        assert_eq!(page.counts, BTreeMap::from([
+            ("cdp_timeout".to_string(), 1),
+            ("selectors_updated".to_string(), 1),
+            ("send_error".to_string(), 1),
+        ]));
+
+        let query = DiagnosticsQuery { tab: Some(2), to: Some("2026-03-02 10:00:00".to_string()), ..Default::default() };
+        let page = filter_diagnostics(entries, &query);
+        assert_eq!(page.items.iter().map(|e| e.event_type.clone()).collect::<Vec<_>>(), vec![DiagnosticEvent::SendError]);
+    }
+
+    #[test]
+    fn test_diagnostic_entry_reads_legacy_format() {
+        let entry: DiagnosticEntry = serde_json::from_str(
+            r#"{"timestamp":"2026-03-01T10:00:00","event_type":"startup_error","details":"{\"tab\":3,\"error\":\"boom\"}"}"#
+        ).unwrap();
+        assert_eq!(entry.event_type, DiagnosticEvent::StartupError);
+        assert_eq!(entry.tab(), Some(3));
+        assert_eq!(entry.details["error"], "boom");
+
+        let entry: DiagnosticEntry = serde_json::from_str(
+            r#"{"timestamp":"2026-03-01T10:00:00","event_type":"removed_type","details":"tab 1: a.md"}"#
+        ).unwrap();
+        assert_eq!(entry.event_type, DiagnosticEvent::Custom("removed_type".to_string()));
+        assert_eq!(entry.details, "tab 1: a.md");
+
+        // Имя неизвестного типа сохраняется при записи и доступно фильтру
+        assert!(serde_json::to_string(&entry).unwrap().contains(r#""event_type":"removed_type""#));
+        let query: DiagnosticsQuery = serde_json::from_str(r#"{"types":["removed_type"]}"#).unwrap();
+        let page = filter_diagnostics(vec![entry, diagnostic("2026-03-01T11:00:00", DiagnosticEvent::Crash, Value::Null)], &query);
+        assert_eq!(page.total, 1);
+        assert_eq!(page.counts["removed_type"], 1);
+        assert_eq!(DiagnosticEvent::from_name("crash"), DiagnosticEvent::Crash);
+    }
+
+    #[test]
+    fn test_fold_archive_log_merges_repeats() {
+        let folded = fold_archive_log(vec![
+            archive("2026-03-01 10:00:00", "plan.zip", "https://claude.ai/chat/a"),
+            archive("2026-03-01 11:00:00", "notes.md", "https://claude.ai/chat/a"),
+            archive("2026-03-02 09:00:00", "plan (1).zip", "https://claude.ai/chat/a"),
+        ]);
+        assert_eq!(folded.len(), 2);
+        assert_eq!(folded[0].filename, "plan.zip");
+        assert_eq!(folded[0].download_count, 2);
+        assert_eq!(folded[0].timestamp, "2026-03-02 09:00:00");
+    }
+
+    #[test]
+    fn test_fold_downloads_log_keeps_last_per_path() {
+        let folded = fold_downloads_log(vec![
+            download("C:/a.md", "1"),
+            download("C:/b.md", "2"),
+            download("C:/a.md", "3"),
+        ]);
+        let order: Vec<(&str, &str)> = folded.iter()
+            .map(|e| (e.file_path.as_str(), e.timestamp.as_str()))
+            .collect();
+        assert_eq!(order, vec![("C:/b.md", "2"), ("C:/a.md", "3")]);
     }
-    
-    // Целевая папка — Downloads пользователя
-    let downloads_dir = dirs::download_dir()
-        .ok_or("Cannot find Downloads directory")?;
-    
-    let filename = format!("apm-diagnostics-{}.json", Local::now().format("%Y%m%d-%H%M%S"));
-    let target_path = downloads_dir.join(&filename);
-    
-    fs::copy(&log_path, &target_path).map_err(|e| e.to_string())?;
-    
-    Ok(target_path.to_string_lossy().to_string())
 }
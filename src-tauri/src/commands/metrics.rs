@@ -0,0 +1,46 @@
+//! Команды метрик времени этапов автоматизации
+//!
+//! Замеры пишут CDP eval, навигация табов, прикрепление файлов, pipeline
+//! отправки и скрапер (`utils/metrics.rs`). Здесь — агрегаты по этапам и
+//! выгрузка таймлайна текущей сессии.
+
+use crate::state::METRICS;
+use crate::utils::metrics::{self, StageMetrics};
+
+/// Перцентили времени по этапам текущей сессии
+///
+/// # Arguments
+/// * `stage` - только этапы с этим началом имени (например `send.`), опционально
+#[tauri::command]
+pub fn get_stage_metrics(stage: Option<String>) -> Result<StageMetrics, String> {
+    let metrics = METRICS.lock().map_err(|e| e.to_string())?;
+    Ok(metrics.stats(stage.as_deref()))
+}
+
+/// Выгружает таймлайн текущей сессии в папку загрузок
+///
+/// # Returns
+/// Путь к `apm-timeline-<сессия>.json`
+#[tauri::command]
+pub async fn export_metrics_timeline() -> Result<String, String> {
+    let timeline = METRICS.lock().map_err(|e| e.to_string())?.timeline();
+
+    let downloads_dir = dirs::download_dir()
+        .ok_or("Cannot find Downloads directory")?;
+    let target_path = downloads_dir.join(format!("apm-timeline-{}.json", timeline.session_id));
+
+    let json = serde_json::to_string_pretty(&timeline).map_err(|e| e.to_string())?;
+    std::fs::write(&target_path, json).map_err(|e| e.to_string())?;
+
+    log::info!("Metrics timeline exported: {} spans", timeline.spans.len());
+    Ok(target_path.to_string_lossy().to_string())
+}
+
+/// Начинает новую сессию замеров
+///
+/// # Returns
+/// Идентификатор новой сессии
+#[tauri::command]
+pub fn reset_stage_metrics() -> String {
+    metrics::reset()
+}
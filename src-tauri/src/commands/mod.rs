@@ -4,21 +4,43 @@
 //! - `app` - управление приложением (сброс данных, папка данных)
 //! - `toolbar` - навигация и тулбар
 //! - `downloads` - управление загрузками
-//! - `logs` - работа с логами
+//! - `logs` - работа с логами (в т.ч. лог бэкенда)
+//! - `diagnostics` - пакет диагностики для поддержки
+//! - `metrics` - время этапов автоматизации (перцентили, таймлайн)
 //! - `claude` - взаимодействие с Claude (табы, навигация, eval)
 //! - `attachments` - аттачменты (чтение, запись, прикрепление)
 //! - `storage` - хранение вкладок (файловая система)
 //! - `scraper` - автосбор данных из Google (SERP Scraper)
+//! - `send` - единый pipeline отправки сообщения в Claude
+//! - `selectors` - проверка селекторов Claude.ai на живой странице
+//! - `plugins` - пользовательские плагины для Claude табов
+//! - `autocontinue` - настройки, лимиты и статистика Auto-Continue
+//! - `usage` - локальная история usage Claude
+//! - `budget` - бюджет usage, останавливающий отправки у лимита
+//! - `projects` - реестр проектов Claude
+//! - `knowledge` - файлы в knowledge проекта Claude
+//! - `knowledge_watch` - синхронизация локальной папки с knowledge
 
 pub mod app;
 pub mod toolbar;
 pub mod downloads;
 pub mod logs;
+pub mod diagnostics;
+pub mod metrics;
 pub mod claude;
 pub mod attachments;
 pub mod storage;
 pub mod scraper;
 pub mod auth;
+pub mod send;
+pub mod selectors;
+pub mod plugins;
+pub mod autocontinue;
+pub mod usage;
+pub mod budget;
+pub mod projects;
+pub mod knowledge;
+pub mod knowledge_watch;
 
 // Реэкспорт команд для удобной регистрации в main.rs
 
@@ -44,6 +66,16 @@
 pub use downloads::{
     get_downloads_path,
     pick_downloads_folder,
+    get_downloads_settings,
+    set_archive_extraction,
+    set_download_rules,
+    preview_download_route,
+    set_download_duplicates,
+    find_duplicate_downloads,
+    list_active_downloads,
+    list_failed_downloads,
+    retry_download,
+    clear_failed_downloads,
     open_file,
     delete_download,
     delete_all_downloads,
@@ -55,11 +87,24 @@
     clear_archive_log,
     add_archive_log_entry,
     get_downloads_log,
+    query_downloads_log,
+    query_archive_log,
+    prune_missing_log_entries,
     write_archive_log, // internal fn (not #[tauri::command]), used by other Rust modules
     write_diagnostic,
-    export_diagnostics,
+    query_diagnostics,
+    read_backend_log,
+    tail_backend_log,
+    get_log_settings,
+    set_log_level,
 };
 
+// Diagnostics commands
+pub use diagnostics::{export_diagnostics, list_pending_crashes, resolve_pending_crashes};
+
+// Metrics commands
+pub use metrics::{get_stage_metrics, export_metrics_timeline, reset_stage_metrics};
+
 // Claude commands
 pub use claude::{
     toggle_claude,
@@ -74,6 +119,13 @@
     reset_claude_state,
     set_panel_ratio,
     get_panel_ratio,
+    detach_claude,
+    attach_claude,
+    is_claude_detached,
+    set_claude_layout,
+    get_claude_layout,
+    update_selectors,
+    get_selectors_info,
     eval_in_claude,
     eval_in_claude_with_result,
     inject_generation_monitor,
@@ -111,3 +163,71 @@
     get_edit_mode_password_status,
     verify_edit_mode_password,
 };
+
+// Send commands
+pub use send::send_to_claude;
+
+// Selectors commands
+pub use selectors::check_selectors;
+
+// Plugins commands
+pub use plugins::{
+    list_plugins,
+    reload_plugins,
+    set_plugin_enabled,
+};
+
+// Auto-Continue commands
+pub use autocontinue::{
+    get_auto_continue_config,
+    migrate_auto_continue_config,
+    set_auto_continue_config,
+    set_auto_continue_enabled,
+    register_auto_continue,
+    get_auto_continue_stats,
+    reset_auto_continue_stats,
+};
+
+// Usage commands
+pub use usage::{
+    record_usage_snapshot,
+    get_usage_history,
+    get_usage_daily,
+    get_usage_summary,
+};
+
+// Usage budget commands
+pub use budget::{
+    get_usage_budget,
+    set_usage_budget,
+    check_usage_budget,
+};
+
+// Project registry commands
+pub use projects::{
+    register_project,
+    list_projects,
+    get_project,
+    update_project,
+    add_project_chat,
+    finish_project,
+    delete_project,
+};
+
+// Project knowledge commands
+pub use knowledge::{
+    list_project_knowledge,
+    delete_project_knowledge,
+    replace_project_knowledge,
+    upload_project_knowledge,
+    reconcile_knowledge_manifest,
+    get_knowledge_manifest,
+};
+
+// Knowledge folder sync commands
+pub use knowledge_watch::{
+    add_knowledge_watch,
+    remove_knowledge_watch,
+    list_knowledge_watches,
+    set_knowledge_watch_enabled,
+};
@@ -0,0 +1,30 @@
+//! Команды пользовательских плагинов Claude табов
+//!
+//! Плагины лежат в `{app_data}/plugins/`, логика загрузки и инжекции —
+//! в `webview::plugins`.
+
+use crate::webview::plugins::{self, PluginInfo};
+
+/// Возвращает список плагинов (включая плагины с ошибкой загрузки)
+#[tauri::command]
+pub fn list_plugins() -> Vec<PluginInfo> {
+    plugins::list_plugins()
+}
+
+/// Перечитывает папку плагинов с диска
+#[tauri::command]
+pub fn reload_plugins() -> Vec<PluginInfo> {
+    plugins::reload_plugins()
+}
+
+/// Включает или выключает плагин
+///
+/// Применяется при следующей загрузке страницы в Claude табах.
+///
+/// # Arguments
+/// * `id` - идентификатор плагина
+/// * `enabled` - новое состояние
+#[tauri::command]
+pub fn set_plugin_enabled(id: String, enabled: bool) -> Result<PluginInfo, String> {
+    plugins::set_plugin_enabled(&id, enabled)
+}
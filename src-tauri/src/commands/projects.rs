@@ -0,0 +1,300 @@
+//! Реестр проектов Claude
+//!
+//! Проекты, созданные или продолженные из приложения, сохраняются
+//! в `projects.json`: UUID, имя, Claude таб, вкладка промптов,
+//! keyword/GEO, время создания и завершения, URL чатов проекта.
+//!
+//! Реестр используется логом архивов: `add_archive_log_entry`
+//! берёт отсюда настоящее имя проекта по URL страницы или чата.
+
+use std::fs;
+use chrono::Local;
+
+use crate::downloads::paths::get_projects_path;
+use crate::state::PROJECTS_LOCK;
+use crate::types::ProjectRecord;
+use crate::utils::dimensions::limits::MAX_PROJECT_ENTRIES;
+
+/// Достаёт UUID проекта из URL вида `.../project/{uuid}`
+pub fn extract_project_uuid(url: &str) -> Option<&str> {
+    extract_path_id(url, "/project/")
+}
+
+/// Достаёт ID чата из URL вида `.../chat/{id}`
+pub fn extract_chat_id(url: &str) -> Option<&str> {
+    extract_path_id(url, "/chat/")
+}
+
+/// UUID проекта/чата/документа Claude: hex-символы и дефисы
+pub fn is_valid_uuid(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_hexdigit() || c == '-')
+}
+
+fn extract_path_id<'a>(url: &'a str, marker: &str) -> Option<&'a str> {
+    let start = url.find(marker)? + marker.len();
+    let rest = &url[start..];
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let id = &rest[..end];
+    is_valid_uuid(id).then_some(id)
+}
+
+/// Индекс проекта по UUID (без учёта регистра — в URL он может быть в верхнем)
+fn project_index(projects: &[ProjectRecord], uuid: &str) -> Option<usize> {
+    projects.iter().position(|p| p.uuid.eq_ignore_ascii_case(uuid))
+}
+
+/// Находит проект по URL страницы Claude
+///
+/// `/project/{uuid}` сопоставляется по UUID, `/chat/{id}` — по сохранённым
+/// URL чатов проекта.
+pub fn find_project_for_url<'a>(projects: &'a [ProjectRecord], url: &str) -> Option<&'a ProjectRecord> {
+    if let Some(uuid) = extract_project_uuid(url) {
+        return project_index(projects, uuid).map(|index| &projects[index]);
+    }
+    let chat_id = extract_chat_id(url)?;
+    projects.iter().rev().find(|p| p.chat_urls.iter().any(|u| extract_chat_id(u) == Some(chat_id)))
+}
+
+/// Читает реестр (без блокировки — вызывающий держит PROJECTS_LOCK)
+fn read_projects() -> Vec<ProjectRecord> {
+    get_projects_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Сохраняет реестр, оставляя последние MAX_PROJECT_ENTRIES проектов
+fn write_projects(mut projects: Vec<ProjectRecord>) -> Result<(), String> {
+    let path = get_projects_path().ok_or("Cannot get projects path")?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    if projects.len() > MAX_PROJECT_ENTRIES {
+        projects = projects.split_off(projects.len() - MAX_PROJECT_ENTRIES);
+    }
+
+    let json = serde_json::to_string_pretty(&projects).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Изменяет проект под блокировкой реестра
+fn modify_project<F>(uuid: &str, change: F) -> Result<ProjectRecord, String>
+where
+    F: FnOnce(&mut ProjectRecord),
+{
+    let _guard = PROJECTS_LOCK.lock().map_err(|_| "Projects lock poisoned")?;
+    let mut projects = read_projects();
+    let index = project_index(&projects, uuid)
+        .ok_or_else(|| format!("Project not found: {}", uuid))?;
+    let project = &mut projects[index];
+    change(project);
+    let updated = project.clone();
+    write_projects(projects)?;
+    Ok(updated)
+}
+
+/// Имя проекта для страницы Claude (для лога архивов)
+pub fn project_name_for_url(url: &str) -> Option<String> {
+    let _guard = PROJECTS_LOCK.lock().ok()?;
+    find_project_for_url(&read_projects(), url).map(|p| p.name.clone())
+}
+
+fn now() -> String {
+    Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Регистрирует проект (при создании или продолжении)
+///
+/// Повторная регистрация того же UUID обновляет имя и таб,
+/// снимает отметку завершения и сохраняет историю чатов.
+///
+/// # Arguments
+/// * `uuid` - UUID проекта в Claude
+/// * `name` - имя проекта
+/// * `claude_tab` - Claude таб (1-3)
+/// * `prompt_tab` - ID вкладки промптов
+/// * `keyword` / `geo` - ключевое слово и GEO запуска
+#[tauri::command]
+pub fn register_project(
+    uuid: String,
+    name: String,
+    claude_tab: u8,
+    prompt_tab: Option<String>,
+    keyword: Option<String>,
+    geo: Option<String>,
+) -> Result<ProjectRecord, String> {
+    if !is_valid_uuid(&uuid) {
+        return Err(format!("Invalid project uuid: {}", uuid));
+    }
+    if !(1..=3).contains(&claude_tab) {
+        return Err(format!("Invalid tab: {}", claude_tab));
+    }
+
+    let _guard = PROJECTS_LOCK.lock().map_err(|_| "Projects lock poisoned")?;
+    let mut projects = read_projects();
+
+    let record = match project_index(&projects, &uuid) {
+        Some(index) => {
+            let mut existing = projects.remove(index);
+            existing.name = name;
+            existing.claude_tab = claude_tab;
+            existing.prompt_tab = prompt_tab.or(existing.prompt_tab);
+            existing.keyword = keyword.or(existing.keyword);
+            existing.geo = geo.or(existing.geo);
+            existing.finished_at = None;
+            existing
+        }
+        None => ProjectRecord {
+            uuid,
+            name,
+            claude_tab,
+            prompt_tab,
+            keyword,
+            geo,
+            created_at: now(),
+            finished_at: None,
+            chat_urls: Vec::new(),
+        },
+    };
+
+    // Последний использованный проект — в конце реестра
+    projects.push(record.clone());
+    write_projects(projects)?;
+
+    Ok(record)
+}
+
+/// Список проектов (новые в конце)
+///
+/// # Arguments
+/// * `active_only` - только незавершённые
+#[tauri::command]
+pub fn list_projects(active_only: Option<bool>) -> Result<Vec<ProjectRecord>, String> {
+    let _guard = PROJECTS_LOCK.lock().map_err(|_| "Projects lock poisoned")?;
+    let projects = read_projects();
+    Ok(if active_only.unwrap_or(false) {
+        projects.into_iter().filter(|p| p.finished_at.is_none()).collect()
+    } else {
+        projects
+    })
+}
+
+/// Получает проект по UUID
+#[tauri::command]
+pub fn get_project(uuid: String) -> Result<Option<ProjectRecord>, String> {
+    let _guard = PROJECTS_LOCK.lock().map_err(|_| "Projects lock poisoned")?;
+    let mut projects = read_projects();
+    Ok(project_index(&projects, &uuid).map(|index| projects.swap_remove(index)))
+}
+
+/// Обновляет имя, keyword или GEO проекта (переданные поля)
+#[tauri::command]
+pub fn update_project(
+    uuid: String,
+    name: Option<String>,
+    keyword: Option<String>,
+    geo: Option<String>,
+) -> Result<ProjectRecord, String> {
+    modify_project(&uuid, |project| {
+        if let Some(name) = name {
+            project.name = name;
+        }
+        if keyword.is_some() {
+            project.keyword = keyword;
+        }
+        if geo.is_some() {
+            project.geo = geo;
+        }
+    })
+}
+
+/// Добавляет URL чата в проект (повторы игнорируются)
+#[tauri::command]
+pub fn add_project_chat(uuid: String, url: String) -> Result<ProjectRecord, String> {
+    let chat_id = extract_chat_id(&url)
+        .ok_or_else(|| format!("Not a chat URL: {}", url))?
+        .to_string();
+    let clean_url = url.split(['?', '#']).next().unwrap_or(&url).to_string();
+
+    modify_project(&uuid, |project| {
+        if !project.chat_urls.iter().any(|u| extract_chat_id(u) == Some(chat_id.as_str())) {
+            project.chat_urls.push(clean_url);
+        }
+    })
+}
+
+/// Отмечает проект завершённым
+#[tauri::command]
+pub fn finish_project(uuid: String) -> Result<ProjectRecord, String> {
+    modify_project(&uuid, |project| {
+        if project.finished_at.is_none() {
+            project.finished_at = Some(now());
+        }
+    })
+}
+
+/// Удаляет проект из реестра (сам проект в Claude не удаляется)
+#[tauri::command]
+pub fn delete_project(uuid: String) -> Result<(), String> {
+    let _guard = PROJECTS_LOCK.lock().map_err(|_| "Projects lock poisoned")?;
+    let mut projects = read_projects();
+    let index = project_index(&projects, &uuid)
+        .ok_or_else(|| format!("Project not found: {}", uuid))?;
+    projects.remove(index);
+    write_projects(projects)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(uuid: &str, name: &str, chats: &[&str]) -> ProjectRecord {
+        ProjectRecord {
+            uuid: uuid.to_string(),
+            name: name.to_string(),
+            claude_tab: 1,
+            prompt_tab: None,
+            keyword: None,
+            geo: None,
+            created_at: "2026-03-02 10:00:00".to_string(),
+            finished_at: None,
+            chat_urls: chats.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_extract_ids_from_urls() {
+        assert_eq!(extract_project_uuid("https://claude.ai/project/0a1b-2c3d"), Some("0a1b-2c3d"));
+        assert_eq!(extract_project_uuid("https://claude.ai/project/0a1b?x=1"), Some("0a1b"));
+        assert_eq!(extract_chat_id("https://claude.ai/chat/ff00-11#generating"), Some("ff00-11"));
+        assert_eq!(extract_project_uuid("https://claude.ai/new"), None);
+        assert_eq!(extract_chat_id("https://claude.ai/chat/"), None);
+    }
+
+    #[test]
+    fn test_find_project_by_project_or_chat_url() {
+        let projects = vec![
+            project("aaaa-1111", "Alpha", &["https://claude.ai/chat/c0ffee-01"]),
+            project("bbbb-2222", "Beta", &[]),
+        ];
+        let by_project = find_project_for_url(&projects, "https://claude.ai/project/BBBB-2222");
+        assert_eq!(by_project.map(|p| p.name.as_str()), Some("Beta"));
+
+        let by_chat = find_project_for_url(&projects, "https://claude.ai/chat/c0ffee-01?foo=bar");
+        assert_eq!(by_chat.map(|p| p.name.as_str()), Some("Alpha"));
+
+        assert!(find_project_for_url(&projects, "https://claude.ai/chat/dead-beef").is_none());
+    }
+
+    #[test]
+    fn test_project_index_ignores_uuid_case() {
+        let projects = vec![project("aaaa-1111", "Alpha", &[]), project("BBBB-2222", "Beta", &[])];
+        assert_eq!(project_index(&projects, "AAAA-1111"), Some(0));
+        assert_eq!(project_index(&projects, "bbbb-2222"), Some(1));
+        assert_eq!(project_index(&projects, "cccc-3333"), None);
+    }
+}
@@ -12,6 +12,9 @@
 use tauri::{AppHandle, Emitter, Manager, WebviewBuilder, WebviewUrl, LogicalPosition, LogicalSize};
 use serde::{Deserialize, Serialize};
 
+use crate::utils::metrics::{self, stage};
+use crate::utils::crash::{track_webview, untrack_webview};
+
 // ─── Константы ───────────────────────────────────────────────────────────
 
 const SCRAPER_LABEL: &str = "scraper";
@@ -78,6 +81,7 @@ pub async fn create_scraper_webview(app: AppHandle) -> Result<(), String> {
         LogicalPosition::new(-9999.0, -9999.0),
         LogicalSize::new(1280.0, 900.0),
     ).map_err(|e| format!("Failed to create scraper webview: {}", e))?;
+    track_webview(SCRAPER_LABEL);
 
     Ok(())
 }
@@ -87,6 +91,7 @@ pub async fn create_scraper_webview(app: AppHandle) -> Result<(), String> {
 pub async fn destroy_scraper_webview(app: AppHandle) -> Result<(), String> {
     if let Some(webview) = app.get_webview(SCRAPER_LABEL) {
         webview.close().map_err(|e| e.to_string())?;
+        untrack_webview(SCRAPER_LABEL);
     }
     Ok(())
 }
@@ -163,18 +168,18 @@ pub async fn scrape_google_serp(
 
         // Google SERP
         if let Err(e) = navigate_and_wait(&app, &google_url, 10).await {
-            eprintln!("[scraper] SERP failed for '{}': {}", query, e);
+            log::warn!("SERP failed for '{}': {}", query, e);
             continue;
         }
 
         let serp_json = match cdp_eval(&app, SERP_EXTRACT_JS, 15).await {
             Ok(json) => json,
-            Err(e) => { eprintln!("[scraper] SERP extract failed: {}", e); continue; }
+            Err(e) => { log::warn!("SERP extract failed: {}", e); continue; }
         };
 
         let serp_data: serde_json::Value = match serde_json::from_str(&serp_json) {
             Ok(v) => v,
-            Err(e) => { eprintln!("[scraper] SERP parse failed: {}", e); continue; }
+            Err(e) => { log::warn!("SERP parse failed: {}", e); continue; }
         };
 
         let results: Vec<SerpResult> = serp_data.get("results")
@@ -210,7 +215,7 @@ pub async fn scrape_google_serp(
                     }
                 }
                 Err(e) => {
-                    eprintln!("[scraper] Fetch failed {}: {}", url, e);
+                    log::warn!("Fetch failed {}: {}", url, e);
                 }
             }
         }
@@ -254,6 +259,7 @@ async fn navigate_and_wait(app: &AppHandle, url: &str, timeout_secs: u64) -> Res
     let webview = app.get_webview(SCRAPER_LABEL)
         .ok_or("Scraper webview not found")?;
 
+    let span = metrics::Span::start(stage::SCRAPER_NAVIGATION, None);
     PAGE_LOADED.store(false, Ordering::SeqCst);
 
     let url_parsed = url.parse()
@@ -269,6 +275,8 @@ async fn navigate_and_wait(app: &AppHandle, url: &str, timeout_secs: u64) -> Res
         tokio::time::sleep(std::time::Duration::from_millis(50)).await;
     }
 
+    span.end(true);
+
     // Пауза для рендеринга контента
     tokio::time::sleep(std::time::Duration::from_millis(500)).await;
     Ok(())
@@ -276,9 +284,11 @@ async fn navigate_and_wait(app: &AppHandle, url: &str, timeout_secs: u64) -> Res
 
 /// Навигация на URL → возвращает текстовый контент страницы
 async fn fetch_page_html(app: &AppHandle, url: &str) -> Result<String, String> {
-    navigate_and_wait(app, url, 15).await?;
-    // Берём только текст — никакой разметки, скриптов, стилей
-    cdp_eval(app, "document.body.innerText", 10).await
+    metrics::timed(stage::SCRAPER_FETCH, None, async {
+        navigate_and_wait(app, url, 15).await?;
+        // Берём только текст — никакой разметки, скриптов, стилей
+        cdp_eval(app, "document.body.innerText", 10).await
+    }).await
 }
 
 /// CDP eval в scraper webview
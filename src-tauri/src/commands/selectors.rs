@@ -0,0 +1,237 @@
+//! Проверка селекторов Claude.ai на живой странице
+//!
+//! Команда `check_selectors` прогоняет каждую запись активного
+//! selectors.json через `querySelectorAll` в указанном табе и возвращает
+//! отчёт: сколько элементов нашла каждая альтернатива и какие группы
+//! не нашли ничего. Сломанные группы пишутся в диагностику как
+//! `selector_broken` вместе с версией набора селекторов.
+//!
+//! Часть групп зависит от состояния страницы (например, `generation.stopButton`
+//! виден только во время генерации) — они перечислены в `STATE_DEPENDENT_GROUPS`.
+//! Если такая группа ничего не нашла, она попадает в `inactive`, а не в `broken`,
+//! и в диагностику не пишется.
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::commands::claude::eval_in_claude_with_result;
+use crate::commands::logs::write_diagnostic;
+use crate::types::DiagnosticEvent;
+use crate::webview::selectors::{active_selectors_json, selectors_info};
+
+/// Таймаут выполнения скрипта проверки (секунды)
+const CHECK_TIMEOUT_SECS: u64 = 10;
+
+/// Группы, которые находятся только в определённом состоянии страницы
+/// (идёт генерация, открыт артефакт, чат внутри проекта)
+const STATE_DEPENDENT_GROUPS: &[&str] = &[
+    "generation.stopButton",
+    "generation.streamingIndicator",
+    "generation.thinkingIndicator",
+    "ui.artifactControls",
+    "project.projectLinkInHeader",
+    "project.projectLinkGeneric",
+];
+
+/// Результат проверки одной альтернативы селектора
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectorMatch {
+    pub selector: String,
+    /// Сколько элементов найдено
+    pub count: u32,
+    /// Ошибка querySelectorAll (невалидный CSS)
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Результат проверки группы (`section.key`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectorGroupReport {
+    /// Путь группы, например `input.sendButton`
+    pub group: String,
+    pub alternatives: Vec<SelectorMatch>,
+    /// Хотя бы одна альтернатива нашла элемент
+    pub matched: bool,
+    /// Группа видна только в определённом состоянии страницы
+    #[serde(default)]
+    pub state_dependent: bool,
+}
+
+/// Отчёт `check_selectors`
+#[derive(Debug, Clone, Serialize)]
+pub struct SelectorCheckReport {
+    pub tab: u8,
+    /// `_meta.version` проверенного набора
+    pub version: String,
+    pub url: String,
+    pub groups: Vec<SelectorGroupReport>,
+    /// Группы без единого совпадения
+    pub broken: Vec<String>,
+    /// Группы из `STATE_DEPENDENT_GROUPS` без совпадений (страница не в нужном состоянии)
+    pub inactive: Vec<String>,
+}
+
+/// Помечает группы, зависящие от состояния, и делит несовпавшие на `broken` / `inactive`
+fn classify_groups(groups: &mut [SelectorGroupReport]) -> (Vec<String>, Vec<String>) {
+    let mut broken = Vec::new();
+    let mut inactive = Vec::new();
+    for group in groups.iter_mut() {
+        group.state_dependent = STATE_DEPENDENT_GROUPS.contains(&group.group.as_str());
+        if group.matched {
+            continue;
+        }
+        if group.state_dependent {
+            inactive.push(group.group.clone());
+        } else {
+            broken.push(group.group.clone());
+        }
+    }
+    (broken, inactive)
+}
+
+/// Формирует JS, проверяющий все селекторы набора
+///
+/// Ключи `*AriaPattern` — не CSS, а подстрока `aria-label`:
+/// для них считаются кнопки с подходящим `aria-label`.
+fn build_check_script(selectors_json: &str) -> String {
+    format!(r#"
+        (function() {{
+            var s = {selectors};
+            var groups = [];
+            Object.keys(s).forEach(function(section) {{
+                if (section === '_meta') return;
+                var entries = s[section] || {{}};
+                Object.keys(entries).forEach(function(key) {{
+                    if (key === '_comment') return;
+                    var value = entries[key];
+                    var list = Array.isArray(value) ? value : [value];
+                    var alternatives = list.map(function(sel) {{
+                        try {{
+                            if (/AriaPattern$/.test(key)) {{
+                                var pattern = String(sel).toLowerCase();
+                                var count = Array.prototype.filter.call(
+                                    document.querySelectorAll('button[aria-label]'),
+                                    function(b) {{ return b.getAttribute('aria-label').toLowerCase().indexOf(pattern) !== -1; }}
+                                ).length;
+                                return {{ selector: sel, count: count }};
+                            }}
+                            return {{ selector: sel, count: document.querySelectorAll(sel).length }};
+                        }} catch (e) {{
+                            return {{ selector: String(sel), count: 0, error: String(e && e.message || e) }};
+                        }}
+                    }});
+                    groups.push({{
+                        group: section + '.' + key,
+                        alternatives: alternatives,
+                        matched: alternatives.some(function(a) {{ return a.count > 0; }})
+                    }});
+                }});
+            }});
+            return {{ url: location.href, groups: groups }};
+        }})()
+    "#, selectors = selectors_json)
+}
+
+/// Собирает отчёт из результата скрипта проверки (`{url, groups}` или `{error}`)
+fn parse_check_result(tab: u8, version: String, raw: &str) -> Result<SelectorCheckReport, String> {
+    let value: serde_json::Value = serde_json::from_str(raw)
+        .map_err(|e| format!("Invalid check result '{}': {}", raw, e))?;
+    if let Some(err) = value.get("error") {
+        return Err(format!("Script exception: {}", err));
+    }
+
+    let url = value.get("url").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+    let mut groups: Vec<SelectorGroupReport> = serde_json::from_value(
+        value.get("groups").cloned().unwrap_or_default()
+    ).map_err(|e| e.to_string())?;
+
+    let (broken, inactive) = classify_groups(&mut groups);
+    Ok(SelectorCheckReport { tab, version, url, groups, broken, inactive })
+}
+
+/// Проверяет все селекторы selectors.json на живой странице таба
+///
+/// # Arguments
+/// * `tab` - номер таба (1-3)
+///
+/// # Returns
+/// Отчёт по группам: совпавшие альтернативы, количество элементов,
+/// список групп без рабочего селектора
+#[tauri::command]
+pub async fn check_selectors(app: AppHandle, tab: u8) -> Result<SelectorCheckReport, String> {
+    run_selector_check(&app, tab, true).await
+}
+
+/// Проверка селекторов в табе
+///
+/// # Arguments
+/// * `record` - записать сломанные группы в диагностику (`selector_broken`)
+pub async fn run_selector_check(app: &AppHandle, tab: u8, record: bool) -> Result<SelectorCheckReport, String> {
+    if !(1..=3).contains(&tab) {
+        return Err(format!("Invalid tab: {}", tab));
+    }
+
+    let version = selectors_info().version;
+    let script = build_check_script(&active_selectors_json());
+    let raw = eval_in_claude_with_result(app.clone(), tab, script, Some(CHECK_TIMEOUT_SECS)).await?;
+
+    let report = parse_check_result(tab, version, &raw)?;
+
+    if record && !report.broken.is_empty() {
+        let _ = write_diagnostic(
+            DiagnosticEvent::SelectorBroken,
+            serde_json::json!({
+                "tab": tab,
+                "version": report.version,
+                "url": report.url,
+                "broken": report.broken,
+            }),
+        );
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(name: &str, matched: bool) -> SelectorGroupReport {
+        SelectorGroupReport { group: name.to_string(), alternatives: Vec::new(), matched, state_dependent: false }
+    }
+
+    #[test]
+    fn test_parse_check_result_reports_broken_and_inactive() {
+        let raw = r#"{"url":"https://claude.ai/chat/c0ffee","groups":[
+            {"group":"input.sendButton","alternatives":[{"selector":"button[aria-label=\"Send\"]","count":1}],"matched":true},
+            {"group":"input.fileInput","alternatives":[{"selector":"input[","count":0,"error":"invalid selector"}],"matched":false},
+            {"group":"generation.stopButton","alternatives":[{"selector":"button.stop","count":0}],"matched":false}
+        ]}"#;
+        let report = parse_check_result(2, "1.4.0".to_string(), raw).unwrap();
+
+        assert_eq!((report.tab, report.url.as_str()), (2, "https://claude.ai/chat/c0ffee"));
+        assert_eq!(report.broken, vec!["input.fileInput"]);
+        assert_eq!(report.inactive, vec!["generation.stopButton"]);
+        assert_eq!(report.groups[1].alternatives[0].error.as_deref(), Some("invalid selector"));
+
+        let failed = parse_check_result(2, String::new(), r#"{"error":"ReferenceError"}"#).unwrap_err();
+        assert!(failed.starts_with("Script exception"));
+        assert!(parse_check_result(2, String::new(), "undefined").is_err());
+    }
+
+    #[test]
+    fn test_state_dependent_groups_are_not_broken() {
+        let mut groups = vec![
+            group("input.sendButton", true),
+            group("input.fileInput", false),
+            group("generation.stopButton", false),
+            group("generation.streamingIndicator", true),
+        ];
+        let (broken, inactive) = classify_groups(&mut groups);
+
+        assert_eq!(broken, vec!["input.fileInput"]);
+        assert_eq!(inactive, vec!["generation.stopButton"]);
+        assert!(groups[2].state_dependent && groups[3].state_dependent);
+        assert!(!groups[1].state_dependent);
+    }
+}
@@ -0,0 +1,378 @@
+//! Единый pipeline отправки сообщения в Claude
+//!
+//! Одна команда `send_to_claude` выполняет все этапы отправки:
+//! budget check → attach → verify uploads → insert → click send → confirm generation.
+//!
+//! Каждый этап пишется в лог диагностики, а результат возвращается
+//! структурой `SendResult` с указанием упавшего этапа и причины.
+
+use std::future::Future;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::commands::attachments::attach_files_batch;
+use crate::commands::budget::enforce_usage_budget;
+use crate::commands::claude::eval_in_claude_with_result;
+use crate::commands::logs::write_diagnostic;
+use crate::types::DiagnosticEvent;
+use crate::utils::metrics;
+use crate::state::{UPLOAD_COUNTERS, GENERATING_STATE};
+use crate::webview::scripts::CLAUDE_EDITOR_JS;
+
+// ─── Константы ───────────────────────────────────────────────────────────
+
+/// Таймаут ожидания загрузки файлов по умолчанию (секунды)
+const DEFAULT_UPLOAD_TIMEOUT_SECS: u64 = 30;
+
+/// Таймаут ожидания старта генерации по умолчанию (секунды)
+const DEFAULT_CONFIRM_TIMEOUT_SECS: u64 = 15;
+
+/// Сколько ждать активации кнопки Send внутри скрипта (миллисекунды)
+const SEND_BUTTON_WAIT_MS: u64 = 15_000;
+
+/// Интервал опроса счётчиков и состояния генерации
+const POLL_INTERVAL_MS: u64 = 400;
+
+
+// ─── Типы ────────────────────────────────────────────────────────────────
+
+/// Этап pipeline отправки
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SendStage {
+    BudgetCheck,
+    Attach,
+    VerifyUploads,
+    Insert,
+    ClickSend,
+    ConfirmGeneration,
+}
+
+impl SendStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SendStage::BudgetCheck => "budget_check",
+            SendStage::Attach => "attach",
+            SendStage::VerifyUploads => "verify_uploads",
+            SendStage::Insert => "insert",
+            SendStage::ClickSend => "click_send",
+            SendStage::ConfirmGeneration => "confirm_generation",
+        }
+    }
+}
+
+/// Отчёт об одном этапе
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageReport {
+    pub stage: SendStage,
+    pub ok: bool,
+    pub duration_ms: u64,
+    /// Детали успеха или причина ошибки
+    pub detail: String,
+}
+
+/// Результат отправки
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SendResult {
+    pub tab: u8,
+    pub success: bool,
+    /// Этап, на котором pipeline остановился (None при успехе)
+    pub failed_stage: Option<SendStage>,
+    /// Причина ошибки (None при успехе)
+    pub error: Option<String>,
+    /// Все выполненные этапы по порядку
+    pub stages: Vec<StageReport>,
+}
+
+/// Накопитель отчётов по этапам
+struct SendReport {
+    tab: u8,
+    started: Instant,
+    stages: Vec<StageReport>,
+}
+
+impl SendReport {
+    fn new(tab: u8) -> Self {
+        Self { tab, started: Instant::now(), stages: Vec::new() }
+    }
+
+    /// Выполняет этап, замеряет время и пишет его в диагностику.
+    /// Возвращает true если этап прошёл успешно.
+    async fn run<F>(&mut self, stage: SendStage, fut: F) -> bool
+    where
+        F: Future<Output = Result<String, String>>,
+    {
+        let started = Instant::now();
+        let outcome = fut.await;
+        let duration_ms = started.elapsed().as_millis() as u64;
+        let ok = outcome.is_ok();
+        metrics::record(format!("{}.{}", metrics::stage::SEND, stage.as_str()), Some(self.tab), started, ok);
+        let detail = match outcome {
+            Ok(detail) => detail,
+            Err(error) => error,
+        };
+
+        let _ = write_diagnostic(
+            if ok { DiagnosticEvent::SendStage } else { DiagnosticEvent::SendError },
+            serde_json::json!({
+                "tab": self.tab,
+                "stage": stage.as_str(),
+                "ok": ok,
+                "duration_ms": duration_ms,
+                "detail": detail,
+            }),
+        );
+
+        self.stages.push(StageReport { stage, ok, duration_ms, detail });
+        ok
+    }
+
+    fn finish(self) -> SendResult {
+        let failed = self.stages.iter().find(|s| !s.ok);
+        metrics::record(metrics::stage::SEND, Some(self.tab), self.started, failed.is_none());
+        SendResult {
+            tab: self.tab,
+            success: failed.is_none(),
+            failed_stage: failed.map(|s| s.stage),
+            error: failed.map(|s| s.detail.clone()),
+            stages: self.stages,
+        }
+    }
+}
+
+
+// ─── Команды ─────────────────────────────────────────────────────────────
+
+/// Отправляет сообщение в Claude одной командой
+///
+/// Этапы: проверка бюджета usage → прикрепление файлов → ожидание их загрузки →
+/// вставка текста → клик по Send → подтверждение старта генерации. Pipeline останавливается
+/// на первом упавшем этапе.
+///
+/// # Arguments
+/// * `tab` - номер таба (1-3)
+/// * `text` - текст сообщения
+/// * `paths` - файлы для прикрепления (опционально)
+/// * `upload_timeout_secs` - таймаут загрузки файлов (по умолчанию 30)
+/// * `confirm_timeout_secs` - таймаут старта генерации (по умолчанию 15)
+///
+/// # Returns
+/// `SendResult` — успех или этап, на котором отправка упала, и причина
+#[tauri::command]
+pub async fn send_to_claude(
+    app: AppHandle,
+    tab: u8,
+    text: String,
+    paths: Option<Vec<String>>,
+    upload_timeout_secs: Option<u64>,
+    confirm_timeout_secs: Option<u64>,
+) -> Result<SendResult, String> {
+    if !(1..=3).contains(&tab) {
+        return Err("Invalid tab".to_string());
+    }
+
+    let label = format!("claude_{}", tab);
+    if app.get_webview(&label).is_none() {
+        return Err(format!("Webview {} not found", label));
+    }
+
+    let paths = paths.unwrap_or_default();
+    let upload_timeout = upload_timeout_secs.unwrap_or(DEFAULT_UPLOAD_TIMEOUT_SECS);
+    let confirm_timeout = confirm_timeout_secs.unwrap_or(DEFAULT_CONFIRM_TIMEOUT_SECS);
+
+    let mut report = SendReport::new(tab);
+
+    if !report.run(SendStage::BudgetCheck, enforce_usage_budget(&app, tab)).await {
+        return Ok(report.finish());
+    }
+
+    if !paths.is_empty() {
+        if !report.run(SendStage::Attach, stage_attach(&app, tab, &paths)).await {
+            return Ok(report.finish());
+        }
+        if !report.run(SendStage::VerifyUploads, stage_verify_uploads(tab, paths.len() as u32, upload_timeout)).await {
+            return Ok(report.finish());
+        }
+    }
+
+    if !report.run(SendStage::Insert, stage_insert(&app, tab, &text)).await {
+        return Ok(report.finish());
+    }
+
+    if !report.run(SendStage::ClickSend, stage_click_send(&app, tab)).await {
+        return Ok(report.finish());
+    }
+
+    report.run(SendStage::ConfirmGeneration, stage_confirm_generation(&app, tab, confirm_timeout)).await;
+
+    Ok(report.finish())
+}
+
+
+// ─── Этапы ───────────────────────────────────────────────────────────────
+
+/// Сбрасывает счётчик загрузок и прикрепляет файлы
+async fn stage_attach(app: &AppHandle, tab: u8, paths: &[String]) -> Result<String, String> {
+    let missing: Vec<&str> = paths.iter()
+        .filter(|p| !std::path::Path::new(p).exists())
+        .map(|p| p.as_str())
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("Files not found: {}", missing.join(", ")));
+    }
+
+    UPLOAD_COUNTERS[(tab - 1) as usize].store(0, Ordering::SeqCst);
+    attach_files_batch(app.clone(), tab, paths.to_vec()).await?;
+
+    Ok(format!("{} file(s) attached", paths.len()))
+}
+
+/// Ждёт пока счётчик загрузок достигнет ожидаемого количества
+async fn stage_verify_uploads(tab: u8, expected: u32, timeout_secs: u64) -> Result<String, String> {
+    let start = Instant::now();
+    loop {
+        let count = UPLOAD_COUNTERS[(tab - 1) as usize].load(Ordering::SeqCst);
+        if count >= expected {
+            return Ok(format!("{}/{} uploads confirmed", count, expected));
+        }
+        if start.elapsed().as_secs() >= timeout_secs {
+            return Err(format!(
+                "Upload timeout after {}s: {}/{} files uploaded",
+                timeout_secs, count, expected
+            ));
+        }
+        tokio::time::sleep(Duration::from_millis(POLL_INTERVAL_MS)).await;
+    }
+}
+
+/// Вставляет текст в ProseMirror и проверяет что он появился в редакторе
+async fn stage_insert(app: &AppHandle, tab: u8, text: &str) -> Result<String, String> {
+    let text_literal = serde_json::to_string(text).map_err(|e| e.to_string())?;
+
+    let script = format!(r#"
+        (function() {{
+            {editor}
+            const text = {text};
+            const pm = findEditor();
+            if (!pm) return {{ ok: false, reason: 'editor not found' }};
+
+            insertIntoEditor(pm, text);
+
+            const length = (pm.textContent || '').trim().length;
+            if (text.trim().length > 0 && length === 0) {{
+                return {{ ok: false, reason: 'editor is empty after insert' }};
+            }}
+            return {{ ok: true, length: length }};
+        }})()
+    "#, editor = CLAUDE_EDITOR_JS, text = text_literal);
+
+    let result = eval_json(app, tab, script, 10).await?;
+    script_outcome(&result)?;
+
+    let length = result.get("length").and_then(|v| v.as_u64()).unwrap_or(0);
+    Ok(format!("{} chars in editor", length))
+}
+
+/// Ждёт активации кнопки Send и кликает по ней
+async fn stage_click_send(app: &AppHandle, tab: u8) -> Result<String, String> {
+    let script = format!(r#"
+        (async function() {{
+            {editor}
+            const pm = findEditor();
+            if (!pm) return {{ ok: false, reason: 'editor not found' }};
+
+            const start = Date.now();
+            let btn = null;
+            while (Date.now() - start < {wait_ms}) {{
+                btn = findSendButton(pm);
+                if (btn && !btn.disabled) {{
+                    btn.click();
+                    return {{ ok: true, waited_ms: Date.now() - start }};
+                }}
+                await new Promise(r => setTimeout(r, 200));
+            }}
+
+            return {{ ok: false, reason: btn ? 'send button stayed disabled' : 'send button not found' }};
+        }})()
+    "#, editor = CLAUDE_EDITOR_JS, wait_ms = SEND_BUTTON_WAIT_MS);
+
+    let timeout = SEND_BUTTON_WAIT_MS / 1000 + 5;
+    let result = eval_json(app, tab, script, timeout).await?;
+    script_outcome(&result)?;
+
+    let waited = result.get("waited_ms").and_then(|v| v.as_u64()).unwrap_or(0);
+    Ok(format!("send clicked after {}ms", waited))
+}
+
+/// Ждёт признаков старта генерации (stop-кнопка, streaming, флаг монитора)
+async fn stage_confirm_generation(app: &AppHandle, tab: u8, timeout_secs: u64) -> Result<String, String> {
+    let script = r#"
+        (function() {
+            const SEL = window._s;
+            const gen = SEL?.generation || {};
+            const stops = Array.isArray(gen.stopButton) ? gen.stopButton : (gen.stopButton ? [gen.stopButton] : []);
+            let stop = false;
+            for (const sel of stops) {
+                try { if (document.querySelector(sel)) { stop = true; break; } } catch(e) {}
+            }
+            let streaming = false;
+            try { streaming = !!(gen.streamingIndicator && document.querySelector(gen.streamingIndicator)); } catch(e) {}
+            const pm = document.querySelector(SEL?.input?.proseMirror || '.ProseMirror');
+            return {
+                ok: true,
+                generating: !!window._apmGen || stop || streaming,
+                editor_empty: !pm || !(pm.textContent || '').trim()
+            };
+        })()
+    "#;
+
+    let start = Instant::now();
+    let mut editor_empty = false;
+    loop {
+        if GENERATING_STATE[(tab - 1) as usize].load(Ordering::SeqCst) {
+            return Ok("generation started".to_string());
+        }
+
+        if let Ok(result) = eval_json(app, tab, script.to_string(), 5).await {
+            if result.get("generating").and_then(|v| v.as_bool()) == Some(true) {
+                return Ok(format!("generation started after {}ms", start.elapsed().as_millis()));
+            }
+            editor_empty = result.get("editor_empty").and_then(|v| v.as_bool()).unwrap_or(false);
+        }
+
+        if start.elapsed().as_secs() >= timeout_secs {
+            return Err(if editor_empty {
+                format!("Message left the editor but generation did not start within {}s", timeout_secs)
+            } else {
+                format!("Message was not sent: editor still has content after {}s", timeout_secs)
+            });
+        }
+        tokio::time::sleep(Duration::from_millis(POLL_INTERVAL_MS)).await;
+    }
+}
+
+
+// ─── Вспомогательные ─────────────────────────────────────────────────────
+
+/// CDP eval в Claude табе с разбором результата как JSON
+async fn eval_json(app: &AppHandle, tab: u8, script: String, timeout_secs: u64) -> Result<serde_json::Value, String> {
+    let raw = eval_in_claude_with_result(app.clone(), tab, script, Some(timeout_secs)).await?;
+    serde_json::from_str(&raw).map_err(|e| format!("Invalid eval result '{}': {}", raw, e))
+}
+
+/// Проверяет `{ ok, reason }` из скрипта этапа (или exceptionDetails из CDP)
+fn script_outcome(result: &serde_json::Value) -> Result<(), String> {
+    match result.get("ok").and_then(|v| v.as_bool()) {
+        Some(true) => Ok(()),
+        Some(false) => Err(result.get("reason")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown error")
+            .to_string()),
+        None => Err(match result.get("error") {
+            Some(err) => format!("Script exception: {}", err),
+            None => format!("Unexpected script result: {}", result),
+        }),
+    }
+}
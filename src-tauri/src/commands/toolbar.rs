@@ -8,13 +8,20 @@
 use tauri::{AppHandle, Emitter, Manager};
 use tauri::LogicalPosition;
 
-use crate::state::{ACTIVE_TAB, PANEL_RATIO};
-use crate::utils::dimensions::sizes;
+use crate::state::ACTIVE_TAB;
+use crate::webview::layout::downloads_position;
+use crate::webview::manager::tab_tile_area;
 
-/// Навигация назад в активном Claude webview
+/// Таб toolbar (toolbar передаёт свой таб; без него — активный)
+fn toolbar_tab(tab: Option<u8>) -> u8 {
+    tab.filter(|t| (1..=3).contains(t))
+        .unwrap_or_else(|| ACTIVE_TAB.load(Ordering::SeqCst))
+}
+
+/// Навигация назад в Claude webview таба
 #[tauri::command]
-pub fn toolbar_back(app: AppHandle) -> Result<(), String> {
-    let tab = ACTIVE_TAB.load(Ordering::SeqCst);
+pub fn toolbar_back(app: AppHandle, tab: Option<u8>) -> Result<(), String> {
+    let tab = toolbar_tab(tab);
     let label = format!("claude_{}", tab);
     
     if let Some(webview) = app.get_webview(&label) {
@@ -23,10 +30,10 @@ pub fn toolbar_back(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
-/// Навигация вперёд в активном Claude webview
+/// Навигация вперёд в Claude webview таба
 #[tauri::command]
-pub fn toolbar_forward(app: AppHandle) -> Result<(), String> {
-    let tab = ACTIVE_TAB.load(Ordering::SeqCst);
+pub fn toolbar_forward(app: AppHandle, tab: Option<u8>) -> Result<(), String> {
+    let tab = toolbar_tab(tab);
     let label = format!("claude_{}", tab);
     
     if let Some(webview) = app.get_webview(&label) {
@@ -35,10 +42,10 @@ pub fn toolbar_forward(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
-/// Перезагрузка активного Claude webview
+/// Перезагрузка Claude webview таба
 #[tauri::command]
-pub fn toolbar_reload(app: AppHandle) -> Result<(), String> {
-    let tab = ACTIVE_TAB.load(Ordering::SeqCst);
+pub fn toolbar_reload(app: AppHandle, tab: Option<u8>) -> Result<(), String> {
+    let tab = toolbar_tab(tab);
     let label = format!("claude_{}", tab);
     
     if let Some(webview) = app.get_webview(&label) {
@@ -47,38 +54,24 @@ pub fn toolbar_reload(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
-/// Пересоздание активного Claude webview (для зависших табов)
+/// Пересоздание Claude webview таба (для зависших табов)
 #[tauri::command]
-pub async fn toolbar_recreate(app: AppHandle) -> Result<(), String> {
-    let tab = ACTIVE_TAB.load(Ordering::SeqCst);
+pub async fn toolbar_recreate(app: AppHandle, tab: Option<u8>) -> Result<(), String> {
+    let tab = toolbar_tab(tab);
     crate::commands::claude::recreate_claude_tab(app, tab).await
 }
 
 /// Показывает popup загрузок
 ///
-/// Позиционирует popup над тулбаром в центре области Claude.
+/// Позиционирует popup над тулбаром в центре плитки таба, чей toolbar
+/// его открыл (в главном окне или в отдельном окне Claude).
 #[tauri::command]
-pub fn show_downloads(app: AppHandle) -> Result<(), String> {
-    let window = app.get_window("main").ok_or("Main window not found")?;
-    let scale = window.scale_factor().unwrap_or(1.0);
-    let size = window.inner_size().map_err(|e| e.to_string())?;
-    let width = size.width as f64 / scale;
-    let height = size.height as f64 / scale;
-    
-    let ratio = PANEL_RATIO.load(Ordering::SeqCst) as f64 / 100.0;
-    let ui_width = width * ratio;
-    let claude_x = ui_width;
-    let claude_width = width - ui_width;
+pub fn show_downloads(app: AppHandle, tab: Option<u8>) -> Result<(), String> {
+    // Плитка таба — в главном окне или в отдельном (detached режим)
+    let area = tab_tile_area(&app, toolbar_tab(tab))?;
     
     if let Some(downloads) = app.get_webview("downloads") {
-        // Центрируем по горизонтали в области Claude
-        let downloads_x = claude_x + (claude_width - sizes::DOWNLOADS_WIDTH) / 2.0;
-        // Позиционируем над toolbar
-        let downloads_y = height 
-            - sizes::TOOLBAR_HEIGHT 
-            - sizes::TOOLBAR_BOTTOM_OFFSET 
-            - sizes::DOWNLOADS_MARGIN 
-            - sizes::DOWNLOADS_HEIGHT;
+        let (downloads_x, downloads_y) = downloads_position(&area);
         
         downloads.set_position(LogicalPosition::new(downloads_x, downloads_y))
             .map_err(|e| e.to_string())?;
@@ -0,0 +1,331 @@
+//! Локальная история usage Claude
+//!
+//! Claude Counter в каждом табе запрашивает `/api/organizations/{orgId}/usage`
+//! и присылает снимок в `record_usage_snapshot`. Снимки дописываются
+//! в `usage_log.jsonl` (append-only, `utils/jsonl.rs`; при ротации лог
+//! сжимается до MAX_USAGE_LOG_ENTRIES последних снимков) и доступны через
+//! запросы: история, сводка по дням, пики в текущих окнах и время до сброса.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, FixedOffset, Local};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use crate::commands::logs::{compact_log, schedule_compaction};
+use crate::downloads::paths::get_usage_log_path;
+use crate::state::USAGE_LOG_LOCK;
+use crate::types::{UsageSnapshot, UsageWindow};
+use crate::utils::dimensions::limits::MAX_USAGE_LOG_ENTRIES;
+use crate::utils::jsonl::{append_record, read_records};
+
+/// Повтор того же значения из того же таба не пишется чаще этого интервала
+const DEDUP_INTERVAL_SECS: i64 = 300;
+
+/// Длительность окон лимита
+const SESSION_WINDOW_HOURS: i64 = 5;
+const WEEKLY_WINDOW_HOURS: i64 = 24 * 7;
+
+/// Последний записанный снимок по табам (для дедупликации)
+static LAST_RECORDED: Lazy<Mutex<HashMap<u8, UsageSnapshot>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Сводка usage за день
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct UsageDay {
+    /// Дата (YYYY-MM-DD, локальное время)
+    pub date: String,
+    pub snapshots: u32,
+    pub session_peak: Option<f64>,
+    pub weekly_peak: Option<f64>,
+    /// Последнее значение weekly за день
+    pub weekly_last: Option<f64>,
+}
+
+/// Текущая сводка usage
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageSummary {
+    pub latest: Option<UsageSnapshot>,
+    /// Пик session в текущем 5-часовом окне
+    pub session_peak: Option<f64>,
+    /// Пик weekly в текущем 7-дневном окне
+    pub weekly_peak: Option<f64>,
+    /// Секунд до сброса session (None — неизвестно или уже сброшено)
+    pub session_resets_in_secs: Option<i64>,
+    /// Секунд до сброса weekly
+    pub weekly_resets_in_secs: Option<i64>,
+}
+
+fn parse_time(value: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(value).ok()
+}
+
+/// Совпадают ли значения снимков (без учёта времени)
+fn same_values(a: &UsageSnapshot, b: &UsageSnapshot) -> bool {
+    a.five_hour == b.five_hour && a.seven_day == b.seven_day
+}
+
+/// Дописывает снимок в лог
+///
+/// # Returns
+/// `false` если снимок пропущен как повтор предыдущего из того же таба
+pub fn append_usage_snapshot(snapshot: &UsageSnapshot) -> Result<bool, String> {
+    if snapshot.five_hour.is_none() && snapshot.seven_day.is_none() {
+        return Ok(false);
+    }
+
+    if let Ok(mut last) = LAST_RECORDED.lock() {
+        if let Some(prev) = last.get(&snapshot.tab) {
+            let elapsed = match (parse_time(&prev.timestamp), parse_time(&snapshot.timestamp)) {
+                (Some(prev), Some(now)) => (now - prev).num_seconds(),
+                _ => i64::MAX,
+            };
+            if same_values(prev, snapshot) && elapsed < DEDUP_INTERVAL_SECS {
+                return Ok(false);
+            }
+        }
+        last.insert(snapshot.tab, snapshot.clone());
+    }
+
+    let rotated = {
+        let _guard = USAGE_LOG_LOCK.lock().map_err(|_| "Usage log lock poisoned")?;
+        let path = get_usage_log_path().ok_or("Cannot get usage log path")?;
+        append_record(&path, snapshot)?
+    };
+
+    if rotated {
+        schedule_compaction(compact_usage_log);
+    }
+    Ok(true)
+}
+
+/// Последние MAX_USAGE_LOG_ENTRIES снимков
+fn fold_usage_log(mut snapshots: Vec<UsageSnapshot>) -> Vec<UsageSnapshot> {
+    if snapshots.len() > MAX_USAGE_LOG_ENTRIES {
+        snapshots = snapshots.split_off(snapshots.len() - MAX_USAGE_LOG_ENTRIES);
+    }
+    snapshots
+}
+
+/// Сжимает лог usage (без `force` — только после ротации)
+pub fn compact_usage_log(force: bool) -> Result<(), String> {
+    compact_log(&USAGE_LOG_LOCK, get_usage_log_path(), fold_usage_log, force)
+}
+
+/// Читает лог снимков (битые строки пропускаются)
+pub fn read_usage_log() -> Vec<UsageSnapshot> {
+    let _guard = USAGE_LOG_LOCK.lock();
+    get_usage_log_path()
+        .map(|path| fold_usage_log(read_records(&path)))
+        .unwrap_or_default()
+}
+
+fn filter_tab(snapshots: Vec<UsageSnapshot>, tab: Option<u8>) -> Vec<UsageSnapshot> {
+    match tab {
+        Some(tab) => snapshots.into_iter().filter(|s| s.tab == tab).collect(),
+        None => snapshots,
+    }
+}
+
+fn max_opt(acc: Option<f64>, value: Option<f64>) -> Option<f64> {
+    match (acc, value) {
+        (Some(a), Some(v)) => Some(a.max(v)),
+        (a, v) => a.or(v),
+    }
+}
+
+/// Группирует снимки по локальной дате
+pub fn daily_summary(snapshots: &[UsageSnapshot]) -> Vec<UsageDay> {
+    let mut days: BTreeMap<String, UsageDay> = BTreeMap::new();
+
+    for snapshot in snapshots {
+        let Some(time) = parse_time(&snapshot.timestamp) else { continue };
+        let date = time.with_timezone(&Local).format("%Y-%m-%d").to_string();
+        let day = days.entry(date.clone()).or_insert(UsageDay {
+            date,
+            snapshots: 0,
+            session_peak: None,
+            weekly_peak: None,
+            weekly_last: None,
+        });
+        let session = snapshot.five_hour.as_ref().map(|w| w.utilization);
+        let weekly = snapshot.seven_day.as_ref().map(|w| w.utilization);
+        day.snapshots += 1;
+        day.session_peak = max_opt(day.session_peak, session);
+        day.weekly_peak = max_opt(day.weekly_peak, weekly);
+        if weekly.is_some() {
+            day.weekly_last = weekly;
+        }
+    }
+
+    days.into_values().collect()
+}
+
+/// Пик utilization в текущем окне, определяемом `resets_at` последнего снимка
+fn window_peak(
+    snapshots: &[UsageSnapshot],
+    resets_at: Option<DateTime<FixedOffset>>,
+    window_hours: i64,
+    select: fn(&UsageSnapshot) -> Option<&UsageWindow>,
+) -> Option<f64> {
+    let start = resets_at.map(|r| r - Duration::hours(window_hours));
+    snapshots.iter()
+        .filter(|s| match (start, parse_time(&s.timestamp)) {
+            (Some(start), Some(time)) => time >= start,
+            (None, _) => true,
+            _ => false,
+        })
+        .filter_map(|s| select(s).map(|w| w.utilization))
+        .fold(None, |acc, v| max_opt(acc, Some(v)))
+}
+
+/// Строит сводку: последний снимок, пики в текущих окнах, время до сброса
+pub fn summarize(snapshots: &[UsageSnapshot], now: DateTime<FixedOffset>) -> UsageSummary {
+    let latest = snapshots.iter()
+        .filter(|s| parse_time(&s.timestamp).is_some())
+        .max_by_key(|s| parse_time(&s.timestamp))
+        .cloned();
+
+    let session_reset = latest.as_ref()
+        .and_then(|s| s.five_hour.as_ref())
+        .and_then(|w| w.resets_at.as_deref())
+        .and_then(parse_time);
+    let weekly_reset = latest.as_ref()
+        .and_then(|s| s.seven_day.as_ref())
+        .and_then(|w| w.resets_at.as_deref())
+        .and_then(parse_time);
+
+    let resets_in = |reset: Option<DateTime<FixedOffset>>| reset
+        .map(|r| (r - now).num_seconds())
+        .filter(|secs| *secs > 0);
+
+    UsageSummary {
+        session_peak: window_peak(snapshots, session_reset, SESSION_WINDOW_HOURS, |s| s.five_hour.as_ref()),
+        weekly_peak: window_peak(snapshots, weekly_reset, WEEKLY_WINDOW_HOURS, |s| s.seven_day.as_ref()),
+        session_resets_in_secs: resets_in(session_reset),
+        weekly_resets_in_secs: resets_in(weekly_reset),
+        latest,
+    }
+}
+
+/// Записывает снимок usage (вызывается из Claude Counter)
+///
+/// # Arguments
+/// * `tab` - номер таба
+/// * `five_hour` / `seven_day` - окна из `/usage` (utilization 0-100, resets_at)
+#[tauri::command]
+pub fn record_usage_snapshot(
+    tab: u8,
+    five_hour: Option<UsageWindow>,
+    seven_day: Option<UsageWindow>,
+) -> Result<bool, String> {
+    append_usage_snapshot(&UsageSnapshot {
+        timestamp: Local::now().to_rfc3339(),
+        tab,
+        five_hour,
+        seven_day,
+    })
+}
+
+/// История снимков usage
+///
+/// # Arguments
+/// * `tab` - фильтр по табу
+/// * `since_hours` - только снимки за последние N часов
+#[tauri::command]
+pub fn get_usage_history(tab: Option<u8>, since_hours: Option<u32>) -> Vec<UsageSnapshot> {
+    let snapshots = filter_tab(read_usage_log(), tab);
+    match since_hours {
+        Some(hours) => {
+            let since = Local::now().fixed_offset() - Duration::hours(hours as i64);
+            snapshots.into_iter()
+                .filter(|s| parse_time(&s.timestamp).is_some_and(|t| t >= since))
+                .collect()
+        }
+        None => snapshots,
+    }
+}
+
+/// Сводка usage по дням (последние `days` дней, по умолчанию 14)
+#[tauri::command]
+pub fn get_usage_daily(tab: Option<u8>, days: Option<u32>) -> Vec<UsageDay> {
+    let summary = daily_summary(&filter_tab(read_usage_log(), tab));
+    let keep = days.unwrap_or(14) as usize;
+    let skip = summary.len().saturating_sub(keep);
+    summary.into_iter().skip(skip).collect()
+}
+
+/// Текущая сводка: последний снимок, пики в окнах, время до сброса
+#[tauri::command]
+pub fn get_usage_summary(tab: Option<u8>) -> UsageSummary {
+    summarize(&filter_tab(read_usage_log(), tab), Local::now().fixed_offset())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(timestamp: &str, session: f64, weekly: f64) -> UsageSnapshot {
+        UsageSnapshot {
+            timestamp: timestamp.to_string(),
+            tab: 1,
+            five_hour: Some(UsageWindow {
+                utilization: session,
+                resets_at: Some("2026-03-02T15:00:00+00:00".to_string()),
+            }),
+            seven_day: Some(UsageWindow {
+                utilization: weekly,
+                resets_at: Some("2026-03-05T00:00:00+00:00".to_string()),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_summary_peaks_within_current_window() {
+        let snapshots = vec![
+            snapshot("2026-03-02T08:00:00+00:00", 95.0, 40.0),
+            snapshot("2026-03-02T11:00:00+00:00", 30.0, 45.0),
+            snapshot("2026-03-02T13:00:00+00:00", 60.0, 50.0),
+        ];
+        let now = parse_time("2026-03-02T14:00:00+00:00").unwrap();
+        let summary = summarize(&snapshots, now);
+
+        // 08:00 — до начала текущего session-окна (10:00)
+        assert_eq!(summary.session_peak, Some(60.0));
+        assert_eq!(summary.weekly_peak, Some(50.0));
+        assert_eq!(summary.session_resets_in_secs, Some(3600));
+        assert_eq!(summary.latest.unwrap().timestamp, "2026-03-02T13:00:00+00:00");
+    }
+
+    #[test]
+    fn test_summary_of_empty_log() {
+        let now = parse_time("2026-03-02T14:00:00+00:00").unwrap();
+        let summary = summarize(&[], now);
+        assert!(summary.latest.is_none());
+        assert_eq!(summary.session_peak, None);
+        assert_eq!(summary.weekly_resets_in_secs, None);
+    }
+
+    #[test]
+    fn test_daily_summary_counts_snapshots() {
+        let snapshots = vec![
+            snapshot("2026-03-02T12:00:00+00:00", 20.0, 40.0),
+            snapshot("2026-03-02T12:30:00+00:00", 70.0, 45.0),
+        ];
+        let days = daily_summary(&snapshots);
+        assert_eq!(days.len(), 1);
+        assert_eq!(days[0].snapshots, 2);
+        assert_eq!(days[0].session_peak, Some(70.0));
+        assert_eq!(days[0].weekly_last, Some(45.0));
+    }
+
+    #[test]
+    fn test_fold_keeps_newest_snapshots() {
+        let snapshots: Vec<UsageSnapshot> = (0..MAX_USAGE_LOG_ENTRIES + 2)
+            .map(|i| snapshot(&format!("2026-03-02T12:00:{:02}+00:00", i % 60), i as f64, 0.0))
+            .collect();
+        let folded = fold_usage_log(snapshots);
+        assert_eq!(folded.len(), MAX_USAGE_LOG_ENTRIES);
+        assert_eq!(folded[0].five_hour.as_ref().unwrap().utilization, 2.0);
+    }
+}
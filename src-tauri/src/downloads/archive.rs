@@ -0,0 +1,246 @@
+//! Распаковка скачанных архивов Claude
+//!
+//! Несколько файлов Claude отдаёт одним архивом, который сохраняется
+//! как `claude_files.zip` (`claude_files (17).zip` и т.д.). При включённой
+//! настройке `extract_archives` архив распаковывается в папку проекта
+//! или чата рядом с ним, а сам архив удаляется.
+
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use crate::downloads::paths::get_unique_filepath;
+use crate::types::ArchiveExtractFolder;
+use crate::utils::dimensions::limits::{MAX_ARCHIVE_ENTRIES, MAX_EXTRACTED_SIZE};
+
+/// Максимальная длина имени папки для распаковки
+const MAX_FOLDER_NAME_LEN: usize = 80;
+
+/// Многофайловая загрузка Claude (`claude_files.zip`, `claude_files (3).zip`)
+pub fn is_claude_archive(filename: &str) -> bool {
+    let lower = filename.to_lowercase();
+    lower.starts_with("claude_files") && lower.ends_with(".zip")
+}
+
+/// Имя папки без недопустимых в Windows символов
+///
+/// # Returns
+/// `None`, если после очистки имя пустое
+pub fn sanitize_folder_name(name: &str) -> Option<String> {
+    let cleaned: String = name.chars()
+        .map(|c| if c.is_control() || "<>:\"/\\|?*".contains(c) { '_' } else { c })
+        .take(MAX_FOLDER_NAME_LEN)
+        .collect();
+    let cleaned = cleaned.trim().trim_end_matches('.').trim();
+    (!cleaned.is_empty()).then(|| cleaned.to_string())
+}
+
+/// Имя папки для распаковки архива
+///
+/// `project` — имя проекта, затем чат; `chat` — чат (заголовок или ID), затем проект.
+/// Без проекта и чата — имя архива без расширения.
+pub fn archive_folder_name(
+    into: ArchiveExtractFolder,
+    project: Option<&str>,
+    chat: Option<&str>,
+    archive_stem: &str,
+) -> String {
+    let candidates = match into {
+        ArchiveExtractFolder::Project => [project, chat],
+        ArchiveExtractFolder::Chat => [chat, project],
+    };
+    candidates.into_iter()
+        .flatten()
+        .chain(std::iter::once(archive_stem))
+        .find_map(sanitize_folder_name)
+        .unwrap_or_else(|| "claude_files".to_string())
+}
+
+/// Распаковывает архив в папку
+///
+/// Структура папок внутри архива сохраняется. Записи, выходящие за пределы
+/// папки (`../`), пропускаются; существующие файлы не перезаписываются —
+/// новым даются имена с (1), (2) и т.д. При ошибке уже распакованные файлы
+/// и созданные папки удаляются.
+///
+/// # Returns
+/// Пути распакованных файлов
+pub fn extract_archive(archive_path: &Path, dest: &Path) -> Result<Vec<PathBuf>, String> {
+    extract_archive_limited(archive_path, dest, MAX_EXTRACTED_SIZE)
+}
+
+/// Распаковка с ограничением суммарного размера `max_size`
+fn extract_archive_limited(archive_path: &Path, dest: &Path, max_size: u64) -> Result<Vec<PathBuf>, String> {
+    let file = File::open(archive_path).map_err(|e| e.to_string())?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+
+    if archive.len() > MAX_ARCHIVE_ENTRIES {
+        return Err(format!("Too many files in archive: {}", archive.len()));
+    }
+
+    let mut declared = 0u64;
+    for index in 0..archive.len() {
+        declared += archive.by_index(index).map_err(|e| e.to_string())?.size();
+    }
+    if declared > max_size {
+        return Err(format!("Archive too large: {} bytes", declared));
+    }
+
+    let mut output = ExtractedOutput::default();
+    match extract_entries(&mut archive, dest, max_size, &mut output) {
+        Ok(()) => Ok(output.files),
+        Err(e) => {
+            output.remove();
+            Err(e)
+        }
+    }
+}
+
+/// Созданное при распаковке (для отката при ошибке)
+#[derive(Default)]
+struct ExtractedOutput {
+    files: Vec<PathBuf>,
+    /// Созданные папки, от внешней к вложенной
+    dirs: Vec<PathBuf>,
+}
+
+impl ExtractedOutput {
+    /// Создаёт папку, запоминая ещё не существовавшие уровни
+    fn create_dir(&mut self, dir: &Path) -> Result<(), String> {
+        let mut missing: Vec<PathBuf> = dir.ancestors()
+            .take_while(|d| !d.exists())
+            .map(Path::to_path_buf)
+            .collect();
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        missing.reverse();
+        self.dirs.extend(missing);
+        Ok(())
+    }
+
+    /// Удаляет распакованные файлы и созданные папки (если они пусты)
+    fn remove(&self) {
+        for file in &self.files {
+            let _ = fs::remove_file(file);
+        }
+        for dir in self.dirs.iter().rev() {
+            let _ = fs::remove_dir(dir);
+        }
+    }
+}
+
+/// Распаковывает записи архива; суммарный размер — не больше `max_size`
+fn extract_entries(
+    archive: &mut zip::ZipArchive<File>,
+    dest: &Path,
+    max_size: u64,
+    output: &mut ExtractedOutput,
+) -> Result<(), String> {
+    let mut remaining = max_size;
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(|e| e.to_string())?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(relative) = entry.enclosed_name() else { continue };
+        let Some(name) = relative.file_name().map(|n| n.to_string_lossy().to_string()) else { continue };
+
+        let dir = match relative.parent() {
+            Some(parent) => dest.join(parent),
+            None => dest.to_path_buf(),
+        };
+        output.create_dir(&dir)?;
+
+        let target = get_unique_filepath(&dir, &name);
+        let mut out = File::create(&target).map_err(|e| e.to_string())?;
+        output.files.push(target);
+
+        // Заголовки могут занижать размер — читаем на байт больше остатка
+        let written = io::copy(&mut (&mut entry).take(remaining.saturating_add(1)), &mut out).map_err(|e| e.to_string())?;
+        if written > remaining {
+            return Err("Archive too large".to_string());
+        }
+        remaining -= written;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    #[test]
+    fn test_is_claude_archive() {
+        assert!(is_claude_archive("claude_files.zip"));
+        assert!(is_claude_archive("claude_files (17).zip"));
+        assert!(!is_claude_archive("report.zip"));
+        assert!(!is_claude_archive("claude_files.md"));
+    }
+
+    #[test]
+    fn test_folder_name_fallbacks() {
+        use ArchiveExtractFolder::*;
+        assert_eq!(archive_folder_name(Project, Some("SEO: plan?"), Some("c0ffee"), "claude_files"), "SEO_ plan_");
+        assert_eq!(archive_folder_name(Chat, Some("Alpha"), Some("c0ffee"), "claude_files"), "c0ffee");
+        assert_eq!(archive_folder_name(Project, Some(" .. "), None, "claude_files (2)"), "claude_files (2)");
+    }
+
+    #[test]
+    fn test_extract_keeps_structure_and_skips_escaping_entries() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+
+        let archive_path = dir.join("claude_files.zip");
+        write_archive(&archive_path, &[("a.md", "alpha"), ("docs/b.md", "beta"), ("../evil.md", "x")]);
+
+        let dest = dir.join("Project");
+        let files = extract_archive(&archive_path, &dest).unwrap();
+        assert_eq!(files, vec![dest.join("a.md"), dest.join("docs").join("b.md")]);
+        assert_eq!(fs::read_to_string(dest.join("docs").join("b.md")).unwrap(), "beta");
+        assert!(!dir.join("evil.md").exists());
+
+        // Повторная распаковка не перезаписывает файлы
+        let again = extract_archive(&archive_path, &dest).unwrap();
+        assert_eq!(again[0], dest.join("a (1).md"));
+    }
+
+    fn write_archive(path: &Path, entries: &[(&str, &str)]) {
+        let mut writer = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, content) in entries {
+            writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn test_extract_size_limit_is_inclusive() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("claude_files.zip");
+        write_archive(&archive_path, &[("a.md", "12345"), ("b.md", "67890")]);
+
+        let dest = dir.path().join("exact");
+        assert_eq!(extract_archive_limited(&archive_path, &dest, 10).unwrap().len(), 2);
+        assert!(extract_archive_limited(&archive_path, &dir.path().join("over"), 9).is_err());
+    }
+
+    #[test]
+    fn test_failed_extract_removes_partial_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("claude_files.zip");
+        write_archive(&archive_path, &[("a.md", "alpha"), ("new/c.md", "gamma"), ("docs/b.md", "beta")]);
+
+        // `docs` — файл, поэтому третья запись не распакуется
+        let dest = dir.path().join("Project");
+        fs::create_dir_all(&dest).unwrap();
+        fs::write(dest.join("docs"), "user file").unwrap();
+
+        assert!(extract_archive(&archive_path, &dest).is_err());
+        assert!(!dest.join("a.md").exists());
+        assert!(!dest.join("new").exists());
+        assert_eq!(fs::read_to_string(dest.join("docs")).unwrap(), "user file");
+    }
+}
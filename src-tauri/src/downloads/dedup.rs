@@ -0,0 +1,185 @@
+//! Дедупликация загрузок по содержимому
+//!
+//! Повторное скачивание того же артефакта создаёт `file (1).md`, `file (2).md`…
+//! Каждая завершённая загрузка хэшируется (SHA-256) и сравнивается с файлами
+//! той же папки: при совпадении дубль либо удаляется, а запись в логе
+//! указывает на существующий файл (`drop`), либо остаётся с пометкой (`keep`).
+//!
+//! `scan_duplicates` ищет группы одинаковых файлов во всей папке загрузок.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// Максимальная глубина обхода подпапок при сканировании
+const MAX_SCAN_DEPTH: usize = 8;
+
+/// Группа одинаковых файлов
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DuplicateGroup {
+    pub hash: String,
+    /// Размер одного файла в байтах
+    pub size: u64,
+    /// Пути файлов (старший по времени изменения — первый)
+    pub files: Vec<String>,
+    /// Сколько байт занимают лишние копии
+    pub wasted: u64,
+}
+
+/// SHA-256 содержимого файла (потоково, без чтения в память целиком)
+pub fn hash_file(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| e.to_string())?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher).map_err(|e| e.to_string())?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Ищет в папке файла более старый файл с тем же содержимым
+///
+/// Сначала сравнивается размер, хэш считается только для совпавших по размеру.
+/// Оригиналом считается только файл старше нового (по времени изменения,
+/// при равенстве — по пути): две одинаковые загрузки, завершившиеся
+/// одновременно, не удалят друг друга — дублем окажется только более новая.
+///
+/// # Returns
+/// Путь существующего файла (самого старого из одинаковых)
+pub fn find_identical(path: &Path, hash: &str) -> Option<PathBuf> {
+    let dir = path.parent()?;
+    let metadata = fs::metadata(path).ok()?;
+    let size = metadata.len();
+    let own = (metadata.modified().unwrap_or(std::time::UNIX_EPOCH), path.to_path_buf());
+
+    let mut matches: Vec<(std::time::SystemTime, PathBuf)> = fs::read_dir(dir).ok()?
+        .flatten()
+        .filter(|entry| entry.path() != path)
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            (metadata.is_file() && metadata.len() == size)
+                .then(|| (metadata.modified().unwrap_or(std::time::UNIX_EPOCH), entry.path()))
+        })
+        .filter(|candidate| *candidate < own)
+        .filter(|(_, candidate)| hash_file(candidate).ok().as_deref() == Some(hash))
+        .collect();
+
+    matches.sort();
+    matches.into_iter().next().map(|(_, path)| path)
+}
+
+/// Собирает файлы папки рекурсивно (до MAX_SCAN_DEPTH)
+fn collect_files(dir: &Path, depth: usize, files: &mut Vec<(PathBuf, u64, std::time::SystemTime)>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else { continue };
+        if metadata.is_dir() {
+            if depth < MAX_SCAN_DEPTH {
+                collect_files(&entry.path(), depth + 1, files);
+            }
+        } else if metadata.is_file() && metadata.len() > 0 {
+            let modified = metadata.modified().unwrap_or(std::time::UNIX_EPOCH);
+            files.push((entry.path(), metadata.len(), modified));
+        }
+    }
+}
+
+/// Находит группы одинаковых файлов в папке (с подпапками)
+///
+/// Группы отсортированы по убыванию занимаемого лишнего места.
+pub fn scan_duplicates(root: &Path) -> Vec<DuplicateGroup> {
+    let mut files = Vec::new();
+    collect_files(root, 0, &mut files);
+
+    let mut by_size: HashMap<u64, Vec<(PathBuf, std::time::SystemTime)>> = HashMap::new();
+    for (path, size, modified) in files {
+        by_size.entry(size).or_default().push((path, modified));
+    }
+
+    let mut groups = Vec::new();
+    for (size, candidates) in by_size.into_iter().filter(|(_, c)| c.len() > 1) {
+        let mut by_hash: HashMap<String, Vec<(std::time::SystemTime, PathBuf)>> = HashMap::new();
+        for (path, modified) in candidates {
+            if let Ok(hash) = hash_file(&path) {
+                by_hash.entry(hash).or_default().push((modified, path));
+            }
+        }
+        for (hash, mut same) in by_hash.into_iter().filter(|(_, s)| s.len() > 1) {
+            same.sort();
+            groups.push(DuplicateGroup {
+                hash,
+                size,
+                wasted: size * (same.len() as u64 - 1),
+                files: same.into_iter().map(|(_, p)| p.to_string_lossy().to_string()).collect(),
+            });
+        }
+    }
+
+    groups.sort_by(|a, b| b.wasted.cmp(&a.wasted).then_with(|| a.files.cmp(&b.files)));
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    /// Пишет файл с заданным временем изменения (секунды Unix)
+    fn write_at(path: &Path, content: &str, secs: u64) {
+        fs::write(path, content).unwrap();
+        File::options().write(true).open(path).unwrap()
+            .set_modified(UNIX_EPOCH + Duration::from_secs(secs)).unwrap();
+    }
+
+    #[test]
+    fn test_find_identical_in_same_folder() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        write_at(&dir.join("report.md"), "same", 100);
+        write_at(&dir.join("other.md"), "diff", 200);
+        write_at(&dir.join("report (1).md"), "same", 300);
+
+        let new_file = dir.join("report (1).md");
+        let hash = hash_file(&new_file).unwrap();
+        assert_eq!(find_identical(&new_file, &hash), Some(dir.join("report.md")));
+
+        let unique = dir.join("other.md");
+        assert_eq!(find_identical(&unique, &hash_file(&unique).unwrap()), None);
+    }
+
+    #[test]
+    fn test_simultaneous_duplicates_drop_only_newer() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let older = dir.join("plan.md");
+        let newer = dir.join("plan (1).md");
+        write_at(&older, "same", 100);
+        write_at(&newer, "same", 101);
+
+        // Обе загрузки проверяются, пока оба файла на диске
+        let hash = hash_file(&older).unwrap();
+        assert_eq!(find_identical(&older, &hash), None);
+        assert_eq!(find_identical(&newer, &hash), Some(older.clone()));
+
+        // Равное время — порядок по пути, оригинал всё равно один
+        write_at(&newer, "same", 100);
+        let originals = [&older, &newer].iter().filter(|p| find_identical(p, &hash).is_none()).count();
+        assert_eq!(originals, 1);
+    }
+
+    #[test]
+    fn test_scan_groups_across_subfolders() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        fs::create_dir_all(dir.join("Alpha")).unwrap();
+        fs::write(dir.join("a.md"), "twice").unwrap();
+        fs::write(dir.join("Alpha").join("a.md"), "twice").unwrap();
+        fs::write(dir.join("b.md"), "other").unwrap();
+
+        let groups = scan_duplicates(dir);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].files.len(), 2);
+        assert_eq!(groups[0].wasted, 5);
+    }
+}
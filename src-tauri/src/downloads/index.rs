@@ -0,0 +1,273 @@
+//! Индекс логов загрузок и архивов для запросов
+//!
+//! `query_downloads_log` / `query_archive_log` не читают и не разбирают лог
+//! заново при каждом вызове: записи держатся в памяти вместе с
+//! подготовленной строкой поиска и расширением. Индекс перестраивается,
+//! только когда у файлов лога меняется размер или время изменения —
+//! проверка файлов лога вместо проверки каждого файла из него.
+//!
+//! Удаление записей о несуществующих файлах — отдельная команда
+//! `prune_missing_log_entries`.
+
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{ArchiveLogEntry, DownloadEntry};
+use crate::utils::dimensions::limits::{DEFAULT_LOG_PAGE_SIZE, MAX_LOG_PAGE_SIZE};
+use crate::utils::jsonl::log_files;
+
+/// Порядок записей в ответе
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogSort {
+    /// Сначала новые
+    #[default]
+    Newest,
+    Oldest,
+    /// По имени файла (без учёта регистра)
+    Name,
+    /// Сначала большие (записи без размера — в конце)
+    Size,
+}
+
+/// Фильтры, сортировка и страница запроса к логу
+#[derive(Deserialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct LogQuery {
+    /// Подстрока в имени, пути, проекте, URL (без учёта регистра)
+    pub text: Option<String>,
+    /// Расширения через запятую (`md`, `.md,zip`)
+    pub ext: Option<String>,
+    pub tab: Option<u8>,
+    /// Имя проекта (без учёта регистра)
+    pub project: Option<String>,
+    /// Начало диапазона: "YYYY-MM-DD" или "YYYY-MM-DD HH:MM:SS" (включительно)
+    pub from: Option<String>,
+    /// Конец диапазона (включительно; дата без времени — весь день)
+    pub to: Option<String>,
+    pub sort: LogSort,
+    pub offset: usize,
+    /// Размер страницы (по умолчанию DEFAULT_LOG_PAGE_SIZE, не больше MAX_LOG_PAGE_SIZE)
+    pub limit: Option<usize>,
+}
+
+/// Страница результатов
+#[derive(Serialize, Clone, Debug)]
+pub struct LogPage<T> {
+    /// Сколько записей подходит под фильтры (без учёта страницы)
+    pub total: usize,
+    pub offset: usize,
+    pub items: Vec<T>,
+}
+
+/// Запись лога, по которой можно искать
+pub trait LogRecord: Clone {
+    fn timestamp(&self) -> &str;
+    fn filename(&self) -> &str;
+    fn tab(&self) -> Option<u8>;
+    fn project(&self) -> Option<&str>;
+    fn size(&self) -> Option<u64>;
+    /// Поля для текстового поиска
+    fn search_fields(&self) -> Vec<&str>;
+}
+
+impl LogRecord for DownloadEntry {
+    fn timestamp(&self) -> &str { &self.timestamp }
+    fn filename(&self) -> &str { &self.filename }
+    fn tab(&self) -> Option<u8> { self.tab }
+    fn project(&self) -> Option<&str> { self.project.as_deref() }
+    fn size(&self) -> Option<u64> { self.size }
+    fn search_fields(&self) -> Vec<&str> {
+        let mut fields = vec![self.filename.as_str(), self.file_path.as_str()];
+        fields.extend(self.project.as_deref());
+        fields.extend(self.archive.as_deref());
+        fields
+    }
+}
+
+impl LogRecord for ArchiveLogEntry {
+    fn timestamp(&self) -> &str { &self.timestamp }
+    fn filename(&self) -> &str { &self.filename }
+    fn tab(&self) -> Option<u8> { Some(self.tab) }
+    fn project(&self) -> Option<&str> { Some(self.project_name.as_str()).filter(|p| !p.is_empty()) }
+    fn size(&self) -> Option<u64> { None }
+    fn search_fields(&self) -> Vec<&str> {
+        vec![&self.filename, &self.file_path, &self.project_name, &self.claude_url]
+    }
+}
+
+/// Запись с подготовленными полями поиска
+#[derive(Clone, Debug)]
+pub struct IndexedRecord<T> {
+    pub entry: T,
+    /// Поля поиска в нижнем регистре через `\n`
+    haystack: String,
+    /// Расширение в нижнем регистре без точки
+    ext: String,
+}
+
+impl<T: LogRecord> IndexedRecord<T> {
+    pub fn new(entry: T) -> Self {
+        let haystack = entry.search_fields().join("\n").to_lowercase();
+        let ext = Path::new(entry.filename())
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        Self { entry, haystack, ext }
+    }
+}
+
+/// Индекс лога в памяти
+pub struct LogIndex<T> {
+    /// Размер и время изменения частей лога, по которым построен индекс
+    stamp: Option<Vec<Option<(u64, SystemTime)>>>,
+    records: Vec<IndexedRecord<T>>,
+}
+
+impl<T> Default for LogIndex<T> {
+    fn default() -> Self {
+        Self { stamp: None, records: Vec::new() }
+    }
+}
+
+/// Выполняет запрос по индексированным записям
+pub fn query_records<T: LogRecord>(records: &[IndexedRecord<T>], query: &LogQuery) -> LogPage<T> {
+    let text = query.text.as_deref()
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty());
+    let exts: Vec<String> = query.ext.as_deref()
+        .unwrap_or("")
+        .split(',')
+        .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+        .filter(|e| !e.is_empty())
+        .collect();
+    let project = query.project.as_deref()
+        .map(|p| p.trim().to_lowercase())
+        .filter(|p| !p.is_empty());
+
+    let mut matched: Vec<&IndexedRecord<T>> = records.iter()
+        .filter(|r| text.as_ref().is_none_or(|t| r.haystack.contains(t.as_str())))
+        .filter(|r| exts.is_empty() || exts.contains(&r.ext))
+        .filter(|r| query.tab.is_none() || r.entry.tab() == query.tab)
+        .filter(|r| project.as_ref().is_none_or(|p| {
+            r.entry.project().is_some_and(|name| name.to_lowercase() == *p)
+        }))
+        .filter(|r| query.from.as_deref().is_none_or(|from| r.entry.timestamp() >= from))
+        .filter(|r| query.to.as_deref().is_none_or(|to| {
+            // Сравниваем по длине границы: "2026-03-02" включает весь день
+            let ts = r.entry.timestamp();
+            ts.get(..to.len()).unwrap_or(ts) <= to
+        }))
+        .collect();
+
+    // Записи в логе идут по времени добавления — стабильная сортировка
+    // сохраняет этот порядок при равных ключах
+    match query.sort {
+        LogSort::Newest => matched.reverse(),
+        LogSort::Oldest => {}
+        LogSort::Name => matched.sort_by_cached_key(|r| r.entry.filename().to_lowercase()),
+        LogSort::Size => {
+            matched.reverse();
+            matched.sort_by_key(|r| std::cmp::Reverse(r.entry.size()));
+        }
+    }
+
+    let limit = query.limit.unwrap_or(DEFAULT_LOG_PAGE_SIZE).min(MAX_LOG_PAGE_SIZE);
+    LogPage {
+        total: matched.len(),
+        offset: query.offset,
+        items: matched.into_iter()
+            .skip(query.offset)
+            .take(limit)
+            .map(|r| r.entry.clone())
+            .collect(),
+    }
+}
+
+/// Запрос к логу через индекс
+///
+/// Индекс перестраивается (`load`), если файлы лога изменились с прошлого
+/// запроса. Отсутствующий лог — пустой результат.
+pub fn query_log<T, F>(index: &Mutex<LogIndex<T>>, log_path: &Path, load: F, query: &LogQuery) -> Result<LogPage<T>, String>
+where
+    T: LogRecord,
+    F: FnOnce() -> Vec<T>,
+{
+    let mut index = index.lock().map_err(|_| "Log index lock poisoned")?;
+
+    let stamp: Vec<Option<(u64, SystemTime)>> = log_files(log_path).iter()
+        .map(|file| fs::metadata(file).ok()
+            .map(|m| (m.len(), m.modified().unwrap_or(SystemTime::UNIX_EPOCH))))
+        .collect();
+    if index.stamp.as_ref() != Some(&stamp) {
+        index.records = load().into_iter().map(IndexedRecord::new).collect();
+        index.stamp = Some(stamp);
+    }
+
+    Ok(query_records(&index.records, query))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp: &str, filename: &str, tab: u8, project: Option<&str>, size: u64) -> IndexedRecord<DownloadEntry> {
+        IndexedRecord::new(DownloadEntry {
+            timestamp: timestamp.to_string(),
+            filename: filename.to_string(),
+            file_path: format!("C:/Downloads/{}", filename),
+            tab: Some(tab),
+            project: project.map(str::to_string),
+            size: Some(size),
+            ..Default::default()
+        })
+    }
+
+    fn records() -> Vec<IndexedRecord<DownloadEntry>> {
+        vec![
+            entry("2026-03-01 09:00:00", "plan.md", 1, Some("Alpha"), 10),
+            entry("2026-03-02 12:00:00", "Report.MD", 2, Some("alpha"), 30),
+            entry("2026-03-02 18:30:00", "claude_files.zip", 1, None, 20),
+            entry("2026-03-03 08:00:00", "notes.txt", 2, Some("Beta"), 5),
+        ]
+    }
+
+    fn names(page: &LogPage<DownloadEntry>) -> Vec<&str> {
+        page.items.iter().map(|e| e.filename.as_str()).collect()
+    }
+
+    #[test]
+    fn test_filters_combine() {
+        let records = records();
+
+        let query = LogQuery { ext: Some(".md, zip".to_string()), tab: Some(1), ..Default::default() };
+        assert_eq!(names(&query_records(&records, &query)), vec!["claude_files.zip", "plan.md"]);
+
+        let query = LogQuery { project: Some("ALPHA".to_string()), text: Some("report".to_string()), ..Default::default() };
+        assert_eq!(names(&query_records(&records, &query)), vec!["Report.MD"]);
+
+        let query = LogQuery { from: Some("2026-03-02".to_string()), to: Some("2026-03-02".to_string()), ..Default::default() };
+        assert_eq!(names(&query_records(&records, &query)), vec!["claude_files.zip", "Report.MD"]);
+    }
+
+    #[test]
+    fn test_sort_and_pagination() {
+        let records = records();
+
+        let query = LogQuery { sort: LogSort::Size, offset: 1, limit: Some(2), ..Default::default() };
+        let page = query_records(&records, &query);
+        assert_eq!(page.total, 4);
+        assert_eq!(names(&page), vec!["claude_files.zip", "plan.md"]);
+
+        let query = LogQuery { sort: LogSort::Name, ..Default::default() };
+        assert_eq!(names(&query_records(&records, &query)), vec!["claude_files.zip", "notes.txt", "plan.md", "Report.MD"]);
+
+        let query = LogQuery { offset: 10, ..Default::default() };
+        let page = query_records(&records, &query);
+        assert_eq!((page.total, page.items.len()), (4, 0));
+    }
+}
@@ -2,9 +2,18 @@
 //!
 //! Этот модуль объединяет функциональность для:
 //! - Работы с путями к логам и настройкам (`paths`)
-//! - Обработки событий загрузки (будет добавлено позже)
+//! - Распаковки скачанных архивов Claude (`archive`)
+//! - Раскладки загрузок по подпапкам по правилам (`routing`)
+//! - Дедупликации загрузок по содержимому (`dedup`)
+//! - Отслеживания активных и неудачных загрузок (`tracker`)
+//! - Запросов к логам загрузок и архивов через индекс (`index`)
 
+pub mod archive;
+pub mod dedup;
+pub mod index;
 pub mod paths;
+pub mod routing;
+pub mod tracker;
 
 // Реэкспорт часто используемых функций
 pub use paths::{
@@ -12,6 +21,8 @@
     get_archive_log_path,
     get_downloads_log_path,
     get_downloads_settings_path,
+    load_downloads_settings,
+    save_downloads_settings,
     get_custom_downloads_path,
     save_custom_downloads_path,
     get_unique_filepath,
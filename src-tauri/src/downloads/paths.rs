@@ -4,6 +4,10 @@
 //! - Логу архивов (скачанные из Claude файлы)
 //! - Логу всех загрузок
 //! - Настройкам загрузок
+//! - Логу снимков usage Claude
+//! - Списку неудачных загрузок
+//! - Логу бэкенда и его настройкам
+//! - Отчётам о падениях
 //! - Генерации уникальных имён файлов
 
 use std::fs;
@@ -30,9 +34,9 @@ pub fn get_app_data_dir() -> Option<PathBuf> {
 /// (артефакты, код, документы).
 ///
 /// # Returns
-/// Путь к `archive_log.json` или `None` если не удалось определить директорию
+/// Путь к `archive_log.jsonl` или `None` если не удалось определить директорию
 pub fn get_archive_log_path() -> Option<PathBuf> {
-    get_app_data_dir().map(|d| d.join("archive_log.json"))
+    get_app_data_dir().map(|d| d.join("archive_log.jsonl"))
 }
 
 /// Получает путь к файлу лога всех загрузок
@@ -41,9 +45,9 @@ pub fn get_archive_log_path() -> Option<PathBuf> {
 /// для отображения в менеджере загрузок.
 ///
 /// # Returns
-/// Путь к `downloads_log.json` или `None` если не удалось определить директорию
+/// Путь к `downloads_log.jsonl` или `None` если не удалось определить директорию
 pub fn get_downloads_log_path() -> Option<PathBuf> {
-    get_app_data_dir().map(|d| d.join("downloads_log.json"))
+    get_app_data_dir().map(|d| d.join("downloads_log.jsonl"))
 }
 
 /// Получает путь к файлу настроек загрузок
@@ -62,9 +66,89 @@ pub fn get_downloads_settings_path() -> Option<PathBuf> {
 /// сломанные селекторы, CDP-таймауты, ошибки отправки и т.д.
 ///
 /// # Returns
-/// Путь к `diagnostics.json` или `None` если не удалось определить директорию
+/// Путь к `diagnostics.jsonl` или `None` если не удалось определить директорию
 pub fn get_diagnostics_log_path() -> Option<PathBuf> {
-    get_app_data_dir().map(|d| d.join("diagnostics.json"))
+    get_app_data_dir().map(|d| d.join("diagnostics.jsonl"))
+}
+
+/// Получает путь к логу снимков usage Claude
+///
+/// Append-only JSONL: одна строка — один снимок `/usage`
+/// (session/weekly utilization), присланный Claude Counter.
+///
+/// # Returns
+/// Путь к `usage_log.jsonl` или `None` если не удалось определить директорию
+pub fn get_usage_log_path() -> Option<PathBuf> {
+    get_app_data_dir().map(|d| d.join("usage_log.jsonl"))
+}
+
+/// Получает путь к реестру проектов Claude
+///
+/// # Returns
+/// Путь к `projects.json` или `None` если не удалось определить директорию
+pub fn get_projects_path() -> Option<PathBuf> {
+    get_app_data_dir().map(|d| d.join("projects.json"))
+}
+
+/// Получает путь к списку неудачных загрузок
+///
+/// # Returns
+/// Путь к `failed_downloads.json` или `None` если не удалось определить директорию
+pub fn get_failed_downloads_path() -> Option<PathBuf> {
+    get_app_data_dir().map(|d| d.join("failed_downloads.json"))
+}
+
+/// Получает путь к логу бэкенда
+///
+/// Текстовый лог с ротацией: `logs/backend.log`, `logs/backend.1.log`…
+///
+/// # Returns
+/// Путь к `logs/backend.log` или `None` если не удалось определить директорию
+pub fn get_backend_log_path() -> Option<PathBuf> {
+    get_app_data_dir().map(|d| d.join("logs").join("backend.log"))
+}
+
+/// Получает путь к настройкам уровней логирования
+///
+/// # Returns
+/// Путь к `log_settings.json` или `None` если не удалось определить директорию
+pub fn get_log_settings_path() -> Option<PathBuf> {
+    get_app_data_dir().map(|d| d.join("log_settings.json"))
+}
+
+/// Получает путь к папке отчётов о падениях
+///
+/// `crashes/pending/` — отчёты, которые пользователь ещё не видел,
+/// `crashes/included/` — отчёты, добавленные в пакет диагностики.
+///
+/// # Returns
+/// Путь к `crashes` или `None` если не удалось определить директорию
+pub fn get_crash_reports_dir() -> Option<PathBuf> {
+    get_app_data_dir().map(|d| d.join("crashes"))
+}
+
+/// Читает настройки загрузок (по умолчанию, если файла нет)
+pub fn load_downloads_settings() -> DownloadsSettings {
+    get_downloads_settings_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Сохраняет настройки загрузок
+pub fn save_downloads_settings(settings: &DownloadsSettings) -> Result<(), String> {
+    let settings_path = get_downloads_settings_path()
+        .ok_or("Cannot get settings path")?;
+    
+    // Создаём директорию если нет
+    if let Some(parent) = settings_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    fs::write(&settings_path, json).map_err(|e| e.to_string())?;
+    
+    Ok(())
 }
 
 /// Получает кастомный путь загрузок из настроек
@@ -76,14 +160,7 @@ pub fn get_diagnostics_log_path() -> Option<PathBuf> {
 /// * `Some(path)` - кастомный путь существует и валиден
 /// * `None` - путь не задан или не существует (использовать путь по умолчанию)
 pub fn get_custom_downloads_path() -> Option<String> {
-    let settings_path = get_downloads_settings_path()?;
-    
-    if !settings_path.exists() {
-        return None;
-    }
-    
-    let content = fs::read_to_string(&settings_path).ok()?;
-    let settings: DownloadsSettings = serde_json::from_str(&content).ok()?;
+    let settings = load_downloads_settings();
     
     // Проверяем что путь существует
     if let Some(ref path) = settings.custom_path {
@@ -97,6 +174,8 @@ pub fn get_custom_downloads_path() -> Option<String> {
 
 /// Сохраняет кастомный путь загрузок в настройки
 ///
+/// Остальные настройки загрузок сохраняются как есть.
+///
 /// # Arguments
 /// * `path` - новый путь или `None` для сброса на путь по умолчанию
 ///
@@ -104,19 +183,8 @@ pub fn get_custom_downloads_path() -> Option<String> {
 /// * `Ok(())` - настройки успешно сохранены
 /// * `Err(String)` - ошибка сохранения
 pub fn save_custom_downloads_path(path: Option<String>) -> Result<(), String> {
-    let settings_path = get_downloads_settings_path()
-        .ok_or("Cannot get settings path")?;
-    
-    // Создаём директорию если нет
-    if let Some(parent) = settings_path.parent() {
-        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-    }
-    
-    let settings = DownloadsSettings { custom_path: path };
-    let json = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
-    fs::write(&settings_path, json).map_err(|e| e.to_string())?;
-    
-    Ok(())
+    let settings = DownloadsSettings { custom_path: path, ..load_downloads_settings() };
+    save_downloads_settings(&settings)
 }
 
 /// Генерирует уникальное имя файла, добавляя (1), (2) и т.д. если файл существует
@@ -194,11 +262,11 @@ fn test_get_app_data_dir() {
     #[test]
     fn test_log_paths_have_correct_filenames() {
         if let Some(path) = get_archive_log_path() {
-            assert_eq!(path.file_name().unwrap(), "archive_log.json");
+            assert_eq!(path.file_name().unwrap(), "archive_log.jsonl");
         }
         
         if let Some(path) = get_downloads_log_path() {
-            assert_eq!(path.file_name().unwrap(), "downloads_log.json");
+            assert_eq!(path.file_name().unwrap(), "downloads_log.jsonl");
         }
         
         if let Some(path) = get_downloads_settings_path() {
@@ -206,7 +274,7 @@ fn test_log_paths_have_correct_filenames() {
         }
         
         if let Some(path) = get_diagnostics_log_path() {
-            assert_eq!(path.file_name().unwrap(), "diagnostics.json");
+            assert_eq!(path.file_name().unwrap(), "diagnostics.jsonl");
         }
     }
 
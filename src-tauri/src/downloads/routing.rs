@@ -0,0 +1,227 @@
+//! Правила раскладки загрузок по подпапкам
+//!
+//! Правила хранятся в `downloads_settings.json` (`rules`) и проверяются
+//! по порядку: первое правило, шаблон имени которого подходит к файлу,
+//! задаёт путь внутри папки загрузок. Без подходящего правила файл
+//! сохраняется в корень папки, как раньше.
+//!
+//! Переменные шаблона: `{tab}`, `{project}`, `{chat}`, `{date}`, `{ext}`,
+//! `{filename}`, `{stem}`. Шаблон без `{filename}`/`{stem}` задаёт папку —
+//! имя файла добавляется в конец (`{project}/{date}` → `Alpha/2026-03-02/a.md`).
+
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+
+use crate::commands::projects::{extract_chat_id, extract_project_uuid, project_name_for_url};
+use crate::downloads::archive::sanitize_folder_name;
+use crate::state::CHAT_TITLES;
+use crate::types::DownloadRule;
+
+/// Переменные, допустимые в шаблоне
+const TEMPLATE_VARS: [&str; 7] = ["tab", "project", "chat", "date", "ext", "filename", "stem"];
+
+/// Контекст загрузки для подстановки в шаблон
+#[derive(Debug, Clone, Default)]
+pub struct DownloadContext {
+    pub tab: u8,
+    /// Имя проекта (из реестра) или его UUID
+    pub project: Option<String>,
+    /// Заголовок чата или его ID
+    pub chat: Option<String>,
+    /// Дата загрузки (YYYY-MM-DD)
+    pub date: String,
+}
+
+/// Сопоставление имени файла с шаблоном (`*` и `?`, без учёта регистра)
+///
+/// Пустой шаблон подходит к любому файлу.
+pub fn matches_pattern(pattern: &str, filename: &str) -> bool {
+    let pattern: Vec<char> = pattern.trim().to_lowercase().chars().collect();
+    let name: Vec<char> = filename.to_lowercase().chars().collect();
+    if pattern.is_empty() {
+        return true;
+    }
+
+    // Жадный glob с откатом к последней звёздочке
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Проверяет шаблон: относительный путь и только известные переменные
+pub fn validate_template(template: &str) -> Result<(), String> {
+    let template = template.trim();
+    if template.is_empty() {
+        return Err("Empty template".to_string());
+    }
+    if template.starts_with(['/', '\\']) || template.chars().nth(1) == Some(':') {
+        return Err(format!("Template must be relative: {}", template));
+    }
+
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..].find('}')
+            .ok_or_else(|| format!("Unclosed variable in template: {}", template))?;
+        let var = &rest[start + 1..start + end];
+        if !TEMPLATE_VARS.contains(&var) {
+            return Err(format!("Unknown template variable: {{{}}}", var));
+        }
+        rest = &rest[start + end + 1..];
+    }
+    Ok(())
+}
+
+/// Подставляет контекст в шаблон
+///
+/// Каждая часть пути очищается от недопустимых символов; пустые части
+/// (нет проекта или чата) и `..` отбрасываются.
+///
+/// # Returns
+/// Относительный путь файла внутри папки загрузок
+pub fn render_template(template: &str, ctx: &DownloadContext, filename: &str) -> PathBuf {
+    let path = Path::new(filename);
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let ext = path.extension().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+
+    let mut template = template.trim().to_string();
+    if !template.contains("{filename}") && !template.contains("{stem}") {
+        template.push_str("/{filename}");
+    }
+
+    let rendered = template
+        .replace("{tab}", &ctx.tab.to_string())
+        .replace("{project}", ctx.project.as_deref().unwrap_or(""))
+        .replace("{chat}", ctx.chat.as_deref().unwrap_or(""))
+        .replace("{date}", &ctx.date)
+        .replace("{ext}", &ext)
+        .replace("{stem}", &stem)
+        .replace("{filename}", filename);
+
+    let mut parts: Vec<&str> = rendered.split(['/', '\\']).collect();
+    // Имя файла не обрезается по длине, в отличие от папок
+    let name = parts.pop()
+        .map(|name| name.replace(|c: char| c.is_control() || "<>:\"|?*".contains(c), "_"))
+        .filter(|name| !name.trim().trim_matches('.').is_empty())
+        .unwrap_or_else(|| filename.to_string());
+
+    parts.into_iter()
+        .filter(|part| *part != "..")
+        .filter_map(sanitize_folder_name)
+        .collect::<PathBuf>()
+        .join(name)
+}
+
+/// Находит первое подходящее правило и строит путь
+///
+/// # Returns
+/// Индекс правила и относительный путь, либо `None` — файл идёт в корень папки
+pub fn route_download(rules: &[DownloadRule], ctx: &DownloadContext, filename: &str) -> Option<(usize, PathBuf)> {
+    rules.iter()
+        .position(|rule| matches_pattern(&rule.pattern, filename))
+        .map(|index| (index, render_template(&rules[index].template, ctx, filename)))
+}
+
+/// Контекст загрузки из таба: проект из реестра, заголовок чата, текущая дата
+///
+/// Без записи в реестре проект — UUID из URL; без заголовка чат — его ID.
+pub fn download_context(tab: u8, claude_url: &str) -> DownloadContext {
+    let project = project_name_for_url(claude_url)
+        .or_else(|| extract_project_uuid(claude_url).map(str::to_string));
+    let chat = extract_chat_id(claude_url).map(|chat_id| {
+        CHAT_TITLES.lock().ok()
+            .and_then(|titles| titles.get((tab as usize).wrapping_sub(1)).cloned())
+            .filter(|title| !title.is_empty())
+            .unwrap_or_else(|| chat_id.to_string())
+    });
+
+    DownloadContext {
+        tab,
+        project,
+        chat,
+        date: Local::now().format("%Y-%m-%d").to_string(),
+    }
+}
+
+/// Путь загрузки внутри `base` с учётом правил (без проверки уникальности)
+///
+/// # Returns
+/// Путь файла и индекс сработавшего правила
+pub fn resolve_destination(
+    base: &Path,
+    rules: &[DownloadRule],
+    ctx: &DownloadContext,
+    filename: &str,
+) -> (PathBuf, Option<usize>) {
+    match route_download(rules, ctx, filename) {
+        Some((index, relative)) => (base.join(relative), Some(index)),
+        None => (base.join(filename), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx() -> DownloadContext {
+        DownloadContext {
+            tab: 2,
+            project: Some("Alpha: SEO".to_string()),
+            chat: None,
+            date: "2026-03-02".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("*.md", "Report.MD"));
+        assert!(matches_pattern("claude_files*.zip", "claude_files (3).zip"));
+        assert!(matches_pattern("file?.txt", "file1.txt"));
+        assert!(matches_pattern("", "anything"));
+        assert!(!matches_pattern("*.md", "notes.txt"));
+        assert!(!matches_pattern("a*b*c", "abca"));
+    }
+
+    #[test]
+    fn test_render_template_drops_empty_and_unsafe_parts() {
+        assert_eq!(
+            render_template("{project}/{date}", &ctx(), "a.md"),
+            Path::new("Alpha_ SEO").join("2026-03-02").join("a.md")
+        );
+        assert_eq!(
+            render_template("../{chat}/tab{tab}/{stem}-{date}.{ext}", &ctx(), "a.md"),
+            Path::new("tab2").join("a-2026-03-02.md")
+        );
+    }
+
+    #[test]
+    fn test_route_and_validate() {
+        let rules = vec![
+            DownloadRule { pattern: "*.zip".to_string(), template: "archives".to_string() },
+            DownloadRule { pattern: "*".to_string(), template: "{ext}".to_string() },
+        ];
+        assert_eq!(route_download(&rules, &ctx(), "x.zip"), Some((0, Path::new("archives").join("x.zip"))));
+        assert_eq!(route_download(&rules, &ctx(), "x.md"), Some((1, Path::new("md").join("x.md"))));
+        assert!(route_download(&[], &ctx(), "x.md").is_none());
+
+        assert!(validate_template("{project}/{date}").is_ok());
+        assert!(validate_template("{author}").is_err());
+        assert!(validate_template("C:/downloads").is_err());
+        assert!(validate_template("{project").is_err());
+    }
+}
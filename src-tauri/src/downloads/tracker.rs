@@ -0,0 +1,270 @@
+//! Отслеживание загрузок: активные, прогресс, ошибки и повтор
+//!
+//! `DownloadEvent::Requested` регистрирует загрузку с ID, `Finished` снимает её.
+//! На Windows нативный обработчик WebView2 `DownloadStarting` дополняет запись
+//! размером и полученными байтами (событие `download-progress`) и причиной
+//! прерывания. На других платформах известны только имя и итог.
+//!
+//! Неудачные загрузки сохраняются в `failed_downloads.json` (URL, таб, причина)
+//! и повторяются командой `retry_download`.
+
+use std::fs;
+use std::sync::atomic::Ordering;
+
+use chrono::Local;
+
+use crate::downloads::paths::get_failed_downloads_path;
+use crate::state::{DOWNLOADS_IN_FLIGHT, FAILED_DOWNLOADS_LOCK, LAST_DOWNLOAD_ID};
+use crate::types::{FailedDownload, InFlightDownload};
+use crate::utils::dimensions::limits::MAX_FAILED_DOWNLOADS;
+
+/// Причина ошибки, если WebView2 её не сообщил
+pub const UNKNOWN_FAILURE: &str = "unknown";
+
+fn now() -> String {
+    Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Следующий ID загрузки
+///
+/// Миллисекунды Unix, но строго больше предыдущего — ID не повторяются
+/// и после перезапуска (важно для сохранённых неудачных загрузок).
+pub fn next_download_id() -> u64 {
+    let now = chrono::Utc::now().timestamp_millis().max(0) as u64;
+    let mut last = LAST_DOWNLOAD_ID.load(Ordering::SeqCst);
+    loop {
+        let id = now.max(last + 1);
+        match LAST_DOWNLOAD_ID.compare_exchange(last, id, Ordering::SeqCst, Ordering::SeqCst) {
+            Ok(_) => return id,
+            Err(actual) => last = actual,
+        }
+    }
+}
+
+/// Регистрирует начатую загрузку
+pub fn start_download(tab: u8, url: &str, filename: &str, file_path: &str) -> InFlightDownload {
+    let download = InFlightDownload {
+        id: next_download_id(),
+        tab,
+        url: url.to_string(),
+        filename: filename.to_string(),
+        file_path: file_path.to_string(),
+        started_at: now(),
+        total_bytes: None,
+        received_bytes: None,
+        interrupt_reason: None,
+    };
+
+    if let Ok(mut downloads) = DOWNLOADS_IN_FLIGHT.lock() {
+        downloads.insert(download.id, download.clone());
+    }
+    download
+}
+
+/// Изменяет самую раннюю активную загрузку с этим URL
+fn modify_by_url<F>(url: &str, change: F) -> Option<InFlightDownload>
+where
+    F: FnOnce(&mut InFlightDownload),
+{
+    let mut downloads = DOWNLOADS_IN_FLIGHT.lock().ok()?;
+    let download = downloads.values_mut()
+        .filter(|d| d.url == url)
+        .min_by_key(|d| d.id)?;
+    change(download);
+    Some(download.clone())
+}
+
+/// Обновляет прогресс загрузки (из WebView2 `BytesReceivedChanged`)
+pub fn update_download_progress(url: &str, received: u64, total: Option<u64>) -> Option<InFlightDownload> {
+    modify_by_url(url, |download| {
+        download.received_bytes = Some(received);
+        if total.is_some() {
+            download.total_bytes = total;
+        }
+    })
+}
+
+/// Запоминает причину прерывания (из WebView2 `StateChanged`)
+pub fn record_interrupt_reason(url: &str, reason: &str) {
+    modify_by_url(url, |download| download.interrupt_reason = Some(reason.to_string()));
+}
+
+/// Снимает загрузку с учёта (по `Finished`)
+pub fn finish_tracked_download(url: &str) -> Option<InFlightDownload> {
+    let mut downloads = DOWNLOADS_IN_FLIGHT.lock().ok()?;
+    let id = downloads.values()
+        .filter(|d| d.url == url)
+        .map(|d| d.id)
+        .min()?;
+    downloads.remove(&id)
+}
+
+/// Активные загрузки (по времени начала)
+pub fn active_downloads() -> Vec<InFlightDownload> {
+    let mut downloads: Vec<InFlightDownload> = DOWNLOADS_IN_FLIGHT.lock()
+        .map(|d| d.values().cloned().collect())
+        .unwrap_or_default();
+    downloads.sort_by_key(|d| d.id);
+    downloads
+}
+
+/// Читает список неудачных загрузок (без блокировки)
+fn read_failed() -> Vec<FailedDownload> {
+    get_failed_downloads_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Сохраняет список неудачных загрузок
+fn write_failed(entries: &[FailedDownload]) -> Result<(), String> {
+    let path = get_failed_downloads_path().ok_or("Cannot get failed downloads path")?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let json = serde_json::to_string_pretty(entries).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Добавляет ошибку в список: прежняя ошибка того же URL заменяется,
+/// хранятся последние MAX_FAILED_DOWNLOADS
+pub fn push_failed(entries: &mut Vec<FailedDownload>, entry: FailedDownload) {
+    entries.retain(|e| e.url != entry.url);
+    entries.push(entry);
+    if entries.len() > MAX_FAILED_DOWNLOADS {
+        let excess = entries.len() - MAX_FAILED_DOWNLOADS;
+        entries.drain(..excess);
+    }
+}
+
+/// Фиксирует неудачную загрузку
+///
+/// # Arguments
+/// * `tracked` - запись активной загрузки (если была зарегистрирована)
+/// * `tab` / `url` / `filename` - данные из `Finished`
+pub fn record_failed_download(
+    tracked: Option<InFlightDownload>,
+    tab: u8,
+    url: &str,
+    filename: &str,
+) -> FailedDownload {
+    let entry = match tracked {
+        Some(download) => FailedDownload {
+            id: download.id,
+            tab: download.tab,
+            url: download.url,
+            filename: download.filename,
+            reason: download.interrupt_reason.unwrap_or_else(|| UNKNOWN_FAILURE.to_string()),
+            failed_at: now(),
+            received_bytes: download.received_bytes,
+            total_bytes: download.total_bytes,
+        },
+        None => FailedDownload {
+            id: next_download_id(),
+            tab,
+            url: url.to_string(),
+            filename: filename.to_string(),
+            reason: UNKNOWN_FAILURE.to_string(),
+            failed_at: now(),
+            received_bytes: None,
+            total_bytes: None,
+        },
+    };
+
+    store_failed(&entry);
+    entry
+}
+
+/// Фиксирует загрузку, которую не удалось обработать после скачивания
+/// (например, распаковать архив), с причиной `reason`
+pub fn record_failed_processing(tab: u8, url: &str, filename: &str, reason: String) -> FailedDownload {
+    let entry = FailedDownload {
+        id: next_download_id(),
+        tab,
+        url: url.to_string(),
+        filename: filename.to_string(),
+        reason,
+        failed_at: now(),
+        received_bytes: None,
+        total_bytes: None,
+    };
+    store_failed(&entry);
+    entry
+}
+
+/// Добавляет ошибку в `failed_downloads.json`
+fn store_failed(entry: &FailedDownload) {
+    if let Ok(_guard) = FAILED_DOWNLOADS_LOCK.lock() {
+        let mut entries = read_failed();
+        push_failed(&mut entries, entry.clone());
+        if let Err(e) = write_failed(&entries) {
+            log::error!("Failed to save failed downloads: {}", e);
+        }
+    }
+}
+
+/// Список неудачных загрузок (новые в конце)
+pub fn failed_downloads() -> Vec<FailedDownload> {
+    let _guard = FAILED_DOWNLOADS_LOCK.lock();
+    read_failed()
+}
+
+/// Убирает неудачную загрузку из списка (перед повтором)
+pub fn take_failed_download(id: u64) -> Result<FailedDownload, String> {
+    let _guard = FAILED_DOWNLOADS_LOCK.lock().map_err(|_| "Failed downloads lock poisoned")?;
+    let mut entries = read_failed();
+    let index = entries.iter()
+        .position(|e| e.id == id)
+        .ok_or_else(|| format!("Failed download not found: {}", id))?;
+    let entry = entries.remove(index);
+    write_failed(&entries)?;
+    Ok(entry)
+}
+
+/// Очищает список неудачных загрузок
+pub fn clear_failed() -> Result<(), String> {
+    let _guard = FAILED_DOWNLOADS_LOCK.lock().map_err(|_| "Failed downloads lock poisoned")?;
+    write_failed(&[])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failed(id: u64, url: &str) -> FailedDownload {
+        FailedDownload {
+            id,
+            tab: 1,
+            url: url.to_string(),
+            filename: "a.md".to_string(),
+            reason: UNKNOWN_FAILURE.to_string(),
+            failed_at: "2026-03-02 10:00:00".to_string(),
+            received_bytes: None,
+            total_bytes: None,
+        }
+    }
+
+    #[test]
+    fn test_download_ids_strictly_increase() {
+        let first = next_download_id();
+        let second = next_download_id();
+        assert!(second > first);
+    }
+
+    #[test]
+    fn test_push_failed_replaces_same_url_and_caps() {
+        let mut entries = vec![failed(1, "https://claude.ai/a"), failed(2, "https://claude.ai/b")];
+        push_failed(&mut entries, failed(3, "https://claude.ai/a"));
+        assert_eq!(entries.iter().map(|e| e.id).collect::<Vec<_>>(), vec![2, 3]);
+
+        for id in 10..(10 + MAX_FAILED_DOWNLOADS as u64) {
+            push_failed(&mut entries, failed(id, &format!("https://claude.ai/{}", id)));
+        }
+        assert_eq!(entries.len(), MAX_FAILED_DOWNLOADS);
+        assert_eq!(entries[0].id, 10);
+    }
+}
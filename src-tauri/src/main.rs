@@ -7,19 +7,29 @@
 
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use std::sync::atomic::Ordering;
+
 use tauri::{
+    Manager,
     WebviewBuilder, WebviewUrl, WindowBuilder,
     LogicalPosition, LogicalSize,
 };
 
 // Импортируем библиотеку
 use ai_prompts_manager::{
+    state,
     utils, 
+    utils::window_state,
     webview, 
-    commands::{app, claude, attachments, downloads, logs, toolbar, storage, scraper, auth},
+    commands::{app, claude, attachments, downloads, logs, diagnostics, metrics, toolbar, storage, scraper, auth, send, selectors, plugins, autocontinue, usage, budget, projects, knowledge, knowledge_watch},
 };
 
 fn main() {
+    // Лог бэкенда — до всего остального, чтобы ошибки старта попали в файл
+    utils::logger::init();
+    // Отчёт о панике до abort (release собирается с panic = "abort")
+    utils::crash::install_panic_hook();
+    
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
@@ -49,6 +59,13 @@ fn main() {
             claude::reset_claude_state,
             claude::set_panel_ratio,
             claude::get_panel_ratio,
+            claude::detach_claude,
+            claude::attach_claude,
+            claude::is_claude_detached,
+            claude::set_claude_layout,
+            claude::get_claude_layout,
+            claude::update_selectors,
+            claude::get_selectors_info,
             claude::eval_in_claude,
             claude::eval_in_claude_with_result,
             claude::inject_generation_monitor,
@@ -56,6 +73,59 @@ fn main() {
             claude::set_generation_state,
             claude::insert_text_to_claude,
             
+            // Send pipeline
+            send::send_to_claude,
+            
+            // Selectors health-check
+            selectors::check_selectors,
+            
+            // User plugins
+            plugins::list_plugins,
+            plugins::reload_plugins,
+            plugins::set_plugin_enabled,
+            
+            // Auto-Continue
+            autocontinue::get_auto_continue_config,
+            autocontinue::set_auto_continue_config,
+            autocontinue::set_auto_continue_enabled,
+            autocontinue::migrate_auto_continue_config,
+            autocontinue::register_auto_continue,
+            autocontinue::get_auto_continue_stats,
+            autocontinue::reset_auto_continue_stats,
+            
+            // Usage history
+            usage::record_usage_snapshot,
+            usage::get_usage_history,
+            usage::get_usage_daily,
+            usage::get_usage_summary,
+            
+            // Usage budget
+            budget::get_usage_budget,
+            budget::set_usage_budget,
+            budget::check_usage_budget,
+            
+            // Project registry
+            projects::register_project,
+            projects::list_projects,
+            projects::get_project,
+            projects::update_project,
+            projects::add_project_chat,
+            projects::finish_project,
+            projects::delete_project,
+            
+            // Project knowledge
+            knowledge::list_project_knowledge,
+            knowledge::delete_project_knowledge,
+            knowledge::replace_project_knowledge,
+            knowledge::upload_project_knowledge,
+            knowledge::reconcile_knowledge_manifest,
+            knowledge::get_knowledge_manifest,
+            // Knowledge folder sync
+            knowledge_watch::add_knowledge_watch,
+            knowledge_watch::remove_knowledge_watch,
+            knowledge_watch::list_knowledge_watches,
+            knowledge_watch::set_knowledge_watch_enabled,
+            
             // Attachments commands
             attachments::read_file_for_attachment,
             attachments::write_temp_file,
@@ -72,6 +142,16 @@ fn main() {
             // Downloads commands
             downloads::get_downloads_path,
             downloads::pick_downloads_folder,
+            downloads::get_downloads_settings,
+            downloads::set_archive_extraction,
+            downloads::set_download_rules,
+            downloads::preview_download_route,
+            downloads::set_download_duplicates,
+            downloads::find_duplicate_downloads,
+            downloads::list_active_downloads,
+            downloads::list_failed_downloads,
+            downloads::retry_download,
+            downloads::clear_failed_downloads,
             downloads::open_file,
             downloads::delete_download,
             downloads::delete_all_downloads,
@@ -81,8 +161,21 @@ fn main() {
             logs::clear_archive_log,
             logs::add_archive_log_entry,
             logs::get_downloads_log,
+            logs::query_downloads_log,
+            logs::query_archive_log,
+            logs::prune_missing_log_entries,
             logs::write_diagnostic,
-            logs::export_diagnostics,
+            logs::query_diagnostics,
+            diagnostics::export_diagnostics,
+            diagnostics::list_pending_crashes,
+            diagnostics::resolve_pending_crashes,
+            metrics::get_stage_metrics,
+            metrics::export_metrics_timeline,
+            metrics::reset_stage_metrics,
+            logs::read_backend_log,
+            logs::tail_backend_log,
+            logs::get_log_settings,
+            logs::set_log_level,
             
             // Toolbar commands
             toolbar::toolbar_back,
@@ -98,17 +191,62 @@ fn main() {
             scraper::scrape_google_serp,
         ])
         .setup(|app| {
+            utils::crash::log_pending_crashes();
+            // Сессия метрик этапов начинается с запуска
+            once_cell::sync::Lazy::force(&state::METRICS);
+
             // Разрешаем множественные загрузки с claude.ai до создания WebView2
             webview::allow_claude_multiple_downloads();
             
-            // Создаём окно - на весь экран
-            let window = WindowBuilder::new(app, "main")
+            // Логи: перенос старых JSON-массивов в append-only JSONL
+            logs::migrate_legacy_logs();
+            
+            // Селекторы Claude.ai: override из app data, если он новее встроенных
+            webview::load_selectors();
+            
+            // Пользовательские плагины из app data
+            webview::plugins::reload_plugins();
+            
+            // Настройки Auto-Continue (пушатся в табы при загрузке страницы)
+            autocontinue::load_auto_continue_config();
+            
+            // Бюджет usage (проверяется перед каждой отправкой)
+            budget::load_usage_budget();
+            
+            // Папки, синхронизируемые в knowledge (опрашиваются в фоне)
+            knowledge_watch::load_knowledge_watches();
+            tauri::async_runtime::spawn(knowledge_watch::run_knowledge_watcher(app.handle().clone()));
+            
+            // Восстанавливаем раскладку Claude табов
+            if let Ok(mut layout) = state::CLAUDE_LAYOUT.lock() {
+                *layout = webview::load_layout_settings();
+            }
+            
+            // Восстанавливаем геометрию окна и соотношение панелей
+            // (позиция сбрасывается, если сохранённого монитора больше нет)
+            let monitors: Vec<_> = app.available_monitors()
+                .unwrap_or_default()
+                .iter()
+                .map(window_state::MonitorArea::from_monitor)
+                .collect();
+            let saved = window_state::clamp_to_monitors(&window_state::load_window_state(), &monitors);
+            state::PANEL_RATIO.store(saved.panel_ratio.clamp(35, 65), Ordering::SeqCst);
+            
+            // Создаём окно - по умолчанию на весь экран
+            let mut builder = WindowBuilder::new(app, "main")
                 .title("AI Prompts Manager")
-                .inner_size(1000.0, 600.0)
-                .min_inner_size(800.0, 500.0)
-                .center()
-                .maximized(true)
-                .build()?;
+                .inner_size(saved.width, saved.height)
+                .min_inner_size(window_state::MIN_WIDTH, window_state::MIN_HEIGHT)
+                .maximized(saved.maximized);
+            builder = match (saved.x, saved.y) {
+                (Some(x), Some(y)) => builder.position(x, y),
+                _ => builder.center(),
+            };
+            let window = builder.build()?;
+            
+            if let Ok(mut window_state) = state::WINDOW_STATE.lock() {
+                *window_state = saved;
+            }
             
             // Устанавливаем иконку из EXE ресурса (workaround для бага Tauri)
             utils::set_window_icon_from_exe(&window);
@@ -119,6 +257,7 @@ fn main() {
                 LogicalPosition::new(0.0, 0.0),
                 LogicalSize::new(1000.0, 600.0),
             )?;
+            utils::crash::track_webview("ui");
             
             // Запускаем фоновые задачи
             let app_handle = app.handle().clone();
@@ -133,10 +272,27 @@ fn main() {
             
             // Обработчик изменения размера окна
             let app_handle2 = app.handle().clone();
-            window.on_window_event(move |event| {
-                if let tauri::WindowEvent::Resized(_) = event {
+            window.on_window_event(move |event| match event {
+                tauri::WindowEvent::Resized(_) => {
                     let _ = webview::resize_webviews(&app_handle2);
+                    window_state::track_window_geometry(&app_handle2);
+                }
+                tauri::WindowEvent::Moved(_) => {
+                    window_state::track_window_geometry(&app_handle2);
+                }
+                // Сохраняем геометрию, пока окно ещё живо
+                tauri::WindowEvent::CloseRequested { .. } => {
+                    if let Err(e) = window_state::persist_window_state(&app_handle2) {
+                        log::error!("Failed to save window state: {}", e);
+                    }
+                }
+                // Главное окно закрыто — закрываем и отдельное окно Claude
+                tauri::WindowEvent::Destroyed => {
+                    if let Some(claude_window) = app_handle2.get_window(webview::CLAUDE_WINDOW_LABEL) {
+                        let _ = claude_window.destroy();
+                    }
                 }
+                _ => {}
             });
             
             Ok(())
@@ -2,17 +2,43 @@
 //!
 //! Этот модуль содержит все статические переменные для хранения состояния:
 //! - Видимость панели Claude
+//! - Режим панели Claude (в главном окне / в отдельном окне)
 //! - Активный таб
 //! - Соотношение панелей
+//! - Раскладка Claude табов
+//! - Геометрия главного окна
+//! - Настройки и счётчики Auto-Continue
+//! - Бюджет usage
+//! - Синхронизируемые папки knowledge
+//! - Заголовки чатов в табах
+//! - Загрузки в процессе
+//! - Индексы логов загрузок и архивов
+//! - Уровни логирования и файл лога бэкенда
+//! - Метки webview для отчёта о панике
+//! - Замеры времени этапов автоматизации
 //! - Мьютексы для синхронизации
 
-use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU32};
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU32, AtomicU64};
 use std::sync::Mutex;
 use once_cell::sync::Lazy;
 
+use crate::downloads::index::LogIndex;
+use crate::utils::metrics::MetricsRecorder;
+use crate::types::{
+    ArchiveLogEntry, AutoContinueConfig, DownloadEntry, InFlightDownload, KnowledgeWatch,
+    LayoutSettings, LogSettings, UsageBudget, WindowState,
+};
+
 /// Видимость панели Claude (true = показана)
 pub static CLAUDE_VISIBLE: AtomicBool = AtomicBool::new(false);
 
+/// Панель Claude вынесена в отдельное окно (true = detached режим)
+/// В detached режиме Claude табы, toolbar и downloads живут в окне `claude_window`,
+/// а UI панель занимает всё главное окно.
+pub static CLAUDE_DETACHED: AtomicBool = AtomicBool::new(false);
+
 /// Номер активного таба Claude (1-3)
 pub static ACTIVE_TAB: AtomicU8 = AtomicU8::new(1);
 
@@ -20,6 +46,60 @@
 /// По умолчанию 50% (равное разделение)
 pub static PANEL_RATIO: AtomicU32 = AtomicU32::new(50);
 
+/// Раскладка Claude табов (single / columns / grid)
+/// Загружается из `claude_layout.json` при старте, меняется через set_claude_layout
+pub static CLAUDE_LAYOUT: Lazy<Mutex<LayoutSettings>> = Lazy::new(|| Mutex::new(LayoutSettings::default()));
+
+/// Геометрия главного окна и состояние панели для сохранения между запусками
+/// Загружается из `window_state.json` при старте, сохраняется при закрытии окна
+pub static WINDOW_STATE: Lazy<Mutex<WindowState>> = Lazy::new(|| Mutex::new(WindowState::default()));
+
+/// Настройки Auto-Continue
+/// Загружаются из `autocontinue_settings.json` при старте
+pub static AUTO_CONTINUE_CONFIG: Lazy<Mutex<AutoContinueConfig>> = Lazy::new(|| Mutex::new(AutoContinueConfig::default()));
+
+/// Счётчики Auto-Continue по чатам (chat_id, количество продолжений),
+/// последний использованный чат — в конце
+/// Инкрементируется из Claude WebView через register_auto_continue,
+/// сохраняется в `autocontinue_counts.json` и загружается при старте
+pub static AUTO_CONTINUE_COUNTS: Lazy<Mutex<Vec<(String, u32)>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Бюджет usage (пороги для остановки отправок)
+/// Загружается из `usage_budget.json` при старте, меняется через set_usage_budget
+pub static USAGE_BUDGET: Lazy<Mutex<UsageBudget>> = Lazy::new(|| Mutex::new(UsageBudget::default()));
+
+/// Заголовки страниц Claude табов [tab1, tab2, tab3] (без суффикса " - Claude")
+/// Обновляются из on_document_title_changed, используются правилами загрузок
+pub static CHAT_TITLES: Lazy<Mutex<[String; 3]>> = Lazy::new(|| Mutex::new(Default::default()));
+
+/// Загрузки в процессе по ID (`downloads/tracker.rs`)
+pub static DOWNLOADS_IN_FLIGHT: Lazy<Mutex<HashMap<u64, InFlightDownload>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Последний выданный ID загрузки (миллисекунды Unix, строго возрастает)
+pub static LAST_DOWNLOAD_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Индекс лога загрузок для запросов (`downloads/index.rs`)
+pub static DOWNLOADS_LOG_INDEX: Lazy<Mutex<LogIndex<DownloadEntry>>> = Lazy::new(|| Mutex::new(LogIndex::default()));
+
+/// Индекс лога архивов для запросов
+pub static ARCHIVE_LOG_INDEX: Lazy<Mutex<LogIndex<ArchiveLogEntry>>> = Lazy::new(|| Mutex::new(LogIndex::default()));
+
+/// Уровни логирования (загружаются из `log_settings.json` при старте)
+pub static LOG_SETTINGS: Lazy<Mutex<LogSettings>> = Lazy::new(|| Mutex::new(LogSettings::default()));
+
+/// Открытый файл лога бэкенда и его размер (`utils/logger.rs`)
+pub static BACKEND_LOG_WRITER: Lazy<Mutex<Option<(File, u64)>>> = Lazy::new(|| Mutex::new(None));
+
+/// Метки созданных webview для отчёта о падении (`utils/crash.rs`)
+/// Panic hook читает их через `try_lock`, не трогая блокировки Tauri
+pub static WEBVIEW_LABELS: Lazy<Mutex<BTreeSet<String>>> = Lazy::new(|| Mutex::new(BTreeSet::new()));
+
+/// Замеры времени этапов текущей сессии (`utils/metrics.rs`)
+pub static METRICS: Lazy<Mutex<MetricsRecorder>> = Lazy::new(|| Mutex::new(MetricsRecorder::default()));
+
+/// Папки, синхронизируемые в knowledge проектов (загружаются при старте)
+pub static KNOWLEDGE_WATCHES: Lazy<Mutex<Vec<KnowledgeWatch>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
 /// Счётчики загруженных файлов по табам Claude [tab1, tab2, tab3]
 /// Инкрементируется из WebResourceRequested (Windows) или JS интерсептора (fallback)
 /// Сбрасывается перед каждой операцией прикрепления
@@ -43,6 +123,18 @@
 /// Мьютекс для защиты записи в лог архивов
 pub static ARCHIVE_LOG_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
 
+/// Мьютекс для защиты записи в лог usage
+pub static USAGE_LOG_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Мьютекс для защиты записи в реестр проектов
+pub static PROJECTS_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Мьютекс для защиты записи в манифесты knowledge проектов
+pub static KNOWLEDGE_MANIFEST_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Мьютекс для защиты записи в список неудачных загрузок
+pub static FAILED_DOWNLOADS_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
 /// Мьютекс для защиты записи в лог диагностики
 pub static DIAGNOSTICS_LOG_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
 
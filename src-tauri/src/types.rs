@@ -5,6 +5,8 @@
 //! - Настройки
 //! - Данные файлов для аттачментов
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 /// Запись в логе архивов (скачанные из Claude файлы)
@@ -31,8 +33,57 @@ pub struct ArchiveLogEntry {
 
 fn default_download_count() -> u32 { 1 }
 
-/// Запись в логе всех загрузок
+/// Проект Claude, созданный или продолженный из приложения
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ProjectRecord {
+    /// UUID проекта в Claude
+    pub uuid: String,
+    pub name: String,
+    /// Claude таб (1-3), в котором ведётся проект
+    pub claude_tab: u8,
+    /// ID вкладки промптов, запустившей проект
+    #[serde(default)]
+    pub prompt_tab: Option<String>,
+    #[serde(default)]
+    pub keyword: Option<String>,
+    #[serde(default)]
+    pub geo: Option<String>,
+    /// Временная метка создания в формате "YYYY-MM-DD HH:MM:SS"
+    pub created_at: String,
+    /// Временная метка завершения (None — проект активен)
+    #[serde(default)]
+    pub finished_at: Option<String>,
+    /// URL чатов проекта
+    #[serde(default)]
+    pub chat_urls: Vec<String>,
+}
+
+/// Локальная папка, синхронизируемая в knowledge проекта
+///
+/// Хранится в `knowledge_watches.json` (одна папка на проект).
 #[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct KnowledgeWatch {
+    /// UUID проекта в Claude
+    pub project_uuid: String,
+    /// Отслеживаемая папка (без подпапок)
+    pub folder: String,
+    /// Удалять из knowledge файлы, удалённые из папки
+    #[serde(default)]
+    pub mirror_deletions: bool,
+    /// Расширения синхронизируемых файлов (без точки, нижний регистр)
+    #[serde(default = "default_watch_extensions")]
+    pub extensions: Vec<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+pub fn default_watch_extensions() -> Vec<String> {
+    ["md", "txt", "html", "csv", "json"].iter().map(|e| e.to_string()).collect()
+}
+fn default_true() -> bool { true }
+
+/// Запись в логе всех загрузок
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct DownloadEntry {
     /// Временная метка в формате "YYYY-MM-DD HH:MM:SS"
     pub timestamp: String,
@@ -40,6 +91,110 @@ pub struct DownloadEntry {
     pub filename: String,
     /// Полный путь к файлу на диске
     pub file_path: String,
+    /// Claude таб, из которого скачан файл
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tab: Option<u8>,
+    /// Имя проекта из реестра (если скачан из проекта)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Размер файла в байтах на момент загрузки
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// Имя архива, из которого распакован файл
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive: Option<String>,
+    /// SHA-256 содержимого
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// Путь уже существовавшего файла с тем же содержимым (режим `keep`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<String>,
+}
+
+/// Загрузка в процессе (`downloads/tracker.rs`)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct InFlightDownload {
+    pub id: u64,
+    /// Claude таб, из которого начата загрузка
+    pub tab: u8,
+    /// URL загрузки
+    pub url: String,
+    pub filename: String,
+    /// Путь, куда сохраняется файл
+    pub file_path: String,
+    /// Временная метка начала в формате "YYYY-MM-DD HH:MM:SS"
+    pub started_at: String,
+    /// Размер файла (если известен — WebView2)
+    #[serde(default)]
+    pub total_bytes: Option<u64>,
+    /// Получено байт (если известно — WebView2)
+    #[serde(default)]
+    pub received_bytes: Option<u64>,
+    /// Причина прерывания от WebView2 (`network_failed`, `file_no_space`…)
+    #[serde(default)]
+    pub interrupt_reason: Option<String>,
+}
+
+/// Неудачная загрузка (`failed_downloads.json`)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FailedDownload {
+    pub id: u64,
+    pub tab: u8,
+    /// URL загрузки (для `retry_download`)
+    pub url: String,
+    pub filename: String,
+    /// Причина ошибки
+    pub reason: String,
+    /// Временная метка ошибки в формате "YYYY-MM-DD HH:MM:SS"
+    pub failed_at: String,
+    /// Получено байт до ошибки
+    #[serde(default)]
+    pub received_bytes: Option<u64>,
+    #[serde(default)]
+    pub total_bytes: Option<u64>,
+}
+
+/// Уровни логирования бэкенда (`log_settings.json`)
+///
+/// Уровни: `off`, `error`, `warn`, `info`, `debug`, `trace`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LogSettings {
+    /// Уровень по умолчанию
+    #[serde(default = "default_log_level")]
+    pub level: String,
+    /// Уровни по модулям (`webview`, `commands::knowledge`); срабатывает самый длинный префикс
+    #[serde(default)]
+    pub targets: BTreeMap<String, String>,
+}
+
+fn default_log_level() -> String { "info".to_string() }
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self { level: default_log_level(), targets: BTreeMap::new() }
+    }
+}
+
+/// Что делать с загрузкой, содержимое которой уже есть в папке
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadDuplicates {
+    /// Оставить файл, пометив в логе `duplicate_of`
+    #[default]
+    Keep,
+    /// Удалить новый файл, запись в логе указывает на существующий
+    Drop,
+}
+
+/// Куда распаковывать скачанные архивы `claude_files.zip`
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveExtractFolder {
+    /// Папка с именем проекта
+    #[default]
+    Project,
+    /// Папка с заголовком (или ID) чата
+    Chat,
 }
 
 /// Настройки загрузок
@@ -48,6 +203,137 @@ pub struct DownloadsSettings {
     /// Кастомный путь для сохранения загрузок (None = путь по умолчанию)
     #[serde(default)]
     pub custom_path: Option<String>,
+    /// Распаковывать `claude_files.zip` после скачивания
+    #[serde(default)]
+    pub extract_archives: bool,
+    #[serde(default)]
+    pub extract_into: ArchiveExtractFolder,
+    /// Правила раскладки по подпапкам (первое подходящее)
+    #[serde(default)]
+    pub rules: Vec<DownloadRule>,
+    /// Дубли по содержимому в той же папке
+    #[serde(default)]
+    pub duplicates: DownloadDuplicates,
+}
+
+/// Правило раскладки загрузок (`downloads/routing.rs`)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DownloadRule {
+    /// Шаблон имени файла (`*.md`, `claude_files*.zip`; пустой — любой файл)
+    #[serde(default)]
+    pub pattern: String,
+    /// Путь внутри папки загрузок (`{project}/{date}/{filename}`)
+    pub template: String,
+}
+
+/// Состояние главного окна (сохраняется между запусками)
+///
+/// Размер и позиция — в логических пикселях, последняя "нормальная"
+/// геометрия (до maximize). Позиция `None` — окно центрируется.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WindowState {
+    pub width: f64,
+    pub height: f64,
+    #[serde(default)]
+    pub x: Option<f64>,
+    #[serde(default)]
+    pub y: Option<f64>,
+    #[serde(default)]
+    pub maximized: bool,
+    /// Имя монитора, на котором было окно
+    #[serde(default)]
+    pub monitor: Option<String>,
+    /// Соотношение панелей (35-65)
+    #[serde(default = "default_panel_ratio")]
+    pub panel_ratio: u32,
+    /// Была ли открыта панель Claude
+    #[serde(default)]
+    pub claude_visible: bool,
+}
+
+fn default_panel_ratio() -> u32 { 50 }
+
+impl Default for WindowState {
+    fn default() -> Self {
+        Self {
+            width: 1000.0,
+            height: 600.0,
+            x: None,
+            y: None,
+            maximized: true,
+            monitor: None,
+            panel_ratio: default_panel_ratio(),
+            claude_visible: false,
+        }
+    }
+}
+
+/// Настройки Auto-Continue (сохраняются между запусками)
+///
+/// Пушится в Claude табы при каждой загрузке страницы (`window._acConfig`).
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AutoContinueConfig {
+    /// Включён ли Auto-Continue по табам [tab1, tab2, tab3]
+    #[serde(default)]
+    pub enabled: [bool; 3],
+    /// Максимум продолжений в одном чате (0 = без ограничений)
+    #[serde(default = "default_max_per_chat")]
+    pub max_per_chat: u32,
+    /// Минимальная задержка перед нажатием Continue (мс), фактическая — до x2
+    #[serde(default = "default_continue_delay_ms")]
+    pub delay_ms: u64,
+    /// Дополнительные фразы лимита (к встроенным в claude_autocontinue.js)
+    #[serde(default)]
+    pub extra_phrases: Vec<String>,
+}
+
+fn default_max_per_chat() -> u32 { 20 }
+fn default_continue_delay_ms() -> u64 { 1500 }
+
+impl Default for AutoContinueConfig {
+    fn default() -> Self {
+        Self {
+            enabled: [false; 3],
+            max_per_chat: default_max_per_chat(),
+            delay_ms: default_continue_delay_ms(),
+            extra_phrases: Vec::new(),
+        }
+    }
+}
+
+/// Режим раскладки Claude табов в панели
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClaudeLayoutMode {
+    /// Один активный таб на всю панель
+    #[default]
+    Single,
+    /// Видимые табы колонками слева направо
+    Columns,
+    /// Видимые табы сеткой
+    Grid,
+}
+
+/// Настройки раскладки Claude табов (сохраняются между запусками)
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LayoutSettings {
+    /// Режим раскладки
+    #[serde(default)]
+    pub mode: ClaudeLayoutMode,
+    /// Табы, показываемые одновременно в режимах columns/grid
+    #[serde(default = "default_visible_tabs")]
+    pub visible_tabs: Vec<u8>,
+}
+
+fn default_visible_tabs() -> Vec<u8> { vec![1, 2, 3] }
+
+impl Default for LayoutSettings {
+    fn default() -> Self {
+        Self {
+            mode: ClaudeLayoutMode::default(),
+            visible_tabs: default_visible_tabs(),
+        }
+    }
 }
 
 /// Данные файла для аттачмента к сообщению Claude
@@ -61,14 +347,227 @@ pub struct FileData {
     pub data: String,
 }
 
+/// Окно лимита Claude (`five_hour` / `seven_day` из `/usage`)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct UsageWindow {
+    /// Использование в процентах (0-100)
+    pub utilization: f64,
+    /// Время сброса окна (ISO 8601)
+    #[serde(default)]
+    pub resets_at: Option<String>,
+}
+
+/// Снимок usage Claude (строка в `usage_log.jsonl`)
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct UsageSnapshot {
+    /// Временная метка в формате ISO 8601 (RFC 3339)
+    pub timestamp: String,
+    /// Таб, из которого получен снимок
+    pub tab: u8,
+    /// 5-часовое окно (session)
+    #[serde(default)]
+    pub five_hour: Option<UsageWindow>,
+    /// 7-дневное окно (weekly)
+    #[serde(default)]
+    pub seven_day: Option<UsageWindow>,
+}
+
+/// Что делать с отправкой, если бюджет usage превышен
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageBudgetMode {
+    /// Сразу отказать с ошибкой `usage_budget_exceeded`
+    #[default]
+    Refuse,
+    /// Ждать снижения usage (до `defer_max_secs`), затем отказать
+    Defer,
+}
+
+/// Бюджет usage: пороги, выше которых новые отправки не запускаются
+///
+/// Проверяется перед каждой отправкой по свежему `/usage` таба.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct UsageBudget {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Порог 5-часового окна в процентах (None — не проверять)
+    #[serde(default = "default_session_max_pct")]
+    pub session_max_pct: Option<f64>,
+    /// Порог 7-дневного окна в процентах (None — не проверять)
+    #[serde(default = "default_weekly_max_pct")]
+    pub weekly_max_pct: Option<f64>,
+    #[serde(default)]
+    pub mode: UsageBudgetMode,
+    /// Максимальное ожидание в режиме defer (секунды)
+    #[serde(default = "default_defer_max_secs")]
+    pub defer_max_secs: u64,
+}
+
+fn default_session_max_pct() -> Option<f64> { Some(85.0) }
+fn default_weekly_max_pct() -> Option<f64> { Some(95.0) }
+fn default_defer_max_secs() -> u64 { 600 }
+
+impl Default for UsageBudget {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            session_max_pct: default_session_max_pct(),
+            weekly_max_pct: default_weekly_max_pct(),
+            mode: UsageBudgetMode::default(),
+            defer_max_secs: default_defer_max_secs(),
+        }
+    }
+}
+
+/// Тип события диагностики
+///
+/// Неизвестные типы (из старых логов или от фронтенда) читаются как
+/// `Custom` с исходным именем — по нему их можно фильтровать.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DiagnosticEvent {
+    /// Не создан Claude webview или toolbar при старте
+    StartupError,
+    /// Не пересоздан Claude webview при сбросе
+    ResetError,
+    /// Группа селекторов не нашла элементов (`check_selectors`)
+    SelectorBroken,
+    /// Применён новый selectors.json
+    SelectorsUpdated,
+    /// CDP `Runtime.evaluate` не ответил вовремя
+    CdpTimeout,
+    /// Запрос к API Claude вернул ошибку
+    ClaudeApiError,
+    /// Этап отправки выполнен
+    SendStage,
+    /// Этап отправки завершился ошибкой
+    SendError,
+    DownloadFailed,
+    ArchiveExtractError,
+    PluginError,
+    KnowledgeSyncError,
+    AutoContinueLimit,
+    UsageBudgetError,
+    UsageBudgetExceeded,
+    /// localStorage заполнен больше чем на 80%
+    Storage,
+    /// Падение бэкенда в прошлом запуске (отчёт добавлен в диагностику)
+    Crash,
+    /// Тип, которого нет в списке выше (имя сохраняется как есть)
+    Custom(String),
+}
+
+impl DiagnosticEvent {
+    /// Имя типа в логе (`startup_error`)
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::StartupError => "startup_error",
+            Self::ResetError => "reset_error",
+            Self::SelectorBroken => "selector_broken",
+            Self::SelectorsUpdated => "selectors_updated",
+            Self::CdpTimeout => "cdp_timeout",
+            Self::ClaudeApiError => "claude_api_error",
+            Self::SendStage => "send_stage",
+            Self::SendError => "send_error",
+            Self::DownloadFailed => "download_failed",
+            Self::ArchiveExtractError => "archive_extract_error",
+            Self::PluginError => "plugin_error",
+            Self::KnowledgeSyncError => "knowledge_sync_error",
+            Self::AutoContinueLimit => "auto_continue_limit",
+            Self::UsageBudgetError => "usage_budget_error",
+            Self::UsageBudgetExceeded => "usage_budget_exceeded",
+            Self::Storage => "storage",
+            Self::Crash => "crash",
+            Self::Custom(name) => name,
+        }
+    }
+
+    /// Тип по имени из лога; неизвестное имя — `Custom`
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "startup_error" => Self::StartupError,
+            "reset_error" => Self::ResetError,
+            "selector_broken" => Self::SelectorBroken,
+            "selectors_updated" => Self::SelectorsUpdated,
+            "cdp_timeout" => Self::CdpTimeout,
+            "claude_api_error" => Self::ClaudeApiError,
+            "send_stage" => Self::SendStage,
+            "send_error" => Self::SendError,
+            "download_failed" => Self::DownloadFailed,
+            "archive_extract_error" => Self::ArchiveExtractError,
+            "plugin_error" => Self::PluginError,
+            "knowledge_sync_error" => Self::KnowledgeSyncError,
+            "auto_continue_limit" => Self::AutoContinueLimit,
+            "usage_budget_error" => Self::UsageBudgetError,
+            "usage_budget_exceeded" => Self::UsageBudgetExceeded,
+            "storage" => Self::Storage,
+            "crash" => Self::Crash,
+            other => Self::Custom(other.to_string()),
+        }
+    }
+}
+
+impl Serialize for DiagnosticEvent {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for DiagnosticEvent {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(Self::from_name(&name))
+    }
+}
+
+/// Отчёт о панике бэкенда (`crashes/*.json`)
+///
+/// Пишется panic hook'ом до `abort` (release собирается с `panic = "abort"`).
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CrashReport {
+    /// Имя файла без расширения (`crash-20260302-101500-123`)
+    pub id: String,
+    pub timestamp: String,
+    pub app_version: String,
+    pub message: String,
+    /// `file:line:column`
+    pub location: Option<String>,
+    pub thread: Option<String>,
+    pub backtrace: String,
+    pub active_tab: u8,
+    pub claude_visible: bool,
+    pub claude_detached: bool,
+    /// Метки webview на момент паники (пусто, если их не удалось получить)
+    #[serde(default)]
+    pub webviews: Vec<String>,
+}
+
 /// Запись в логе диагностики (технические события для отладки)
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct DiagnosticEntry {
     /// Временная метка в формате ISO 8601
     pub timestamp: String,
-    /// Тип события (selector_broken, cdp_timeout, storage_error, send_error и т.д.)
-    pub event_type: String,
-    /// JSON-строка с деталями события
-    #[serde(default)]
-    pub details: String,
+    pub event_type: DiagnosticEvent,
+    /// Детали события (старые записи хранили JSON строкой — разбираются при чтении)
+    #[serde(default, deserialize_with = "deserialize_details")]
+    pub details: serde_json::Value,
+}
+
+impl DiagnosticEntry {
+    /// Claude таб из деталей события (`details.tab`)
+    pub fn tab(&self) -> Option<u8> {
+        self.details.get("tab")
+            .and_then(|t| t.as_u64())
+            .and_then(|t| u8::try_from(t).ok())
+    }
+}
+
+/// Детали диагностики: JSON-строка старого формата разбирается в значение
+fn deserialize_details<'de, D>(deserializer: D) -> Result<serde_json::Value, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(s) => serde_json::from_str(&s).unwrap_or(serde_json::Value::String(s)),
+        other => other,
+    })
 }
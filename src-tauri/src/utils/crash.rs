@@ -0,0 +1,183 @@
+//! Отчёты о панике бэкенда
+//!
+//! Release собирается с `panic = "abort"`: паника завершает процесс сразу,
+//! без stderr в релизной сборке под Windows. Panic hook успевает до `abort`
+//! записать в `crashes/pending/` отчёт: сообщение, место, backtrace и
+//! состояние приложения (активный таб, видимость и режим панели, метки webview).
+//!
+//! Hook не берёт блокировок Tauri (их мог держать упавший поток): метки
+//! webview берутся из `WEBVIEW_LABELS`, которые обновляются при создании и
+//! закрытии webview, и читаются через `try_lock`.
+//!
+//! При следующем запуске фронтенд спрашивает `list_pending_crashes` и
+//! предлагает добавить отчёт в пакет диагностики (`resolve_pending_crashes`).
+
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+
+use chrono::Local;
+use crate::downloads::paths::get_crash_reports_dir;
+use crate::state::{ACTIVE_TAB, CLAUDE_DETACHED, CLAUDE_VISIBLE, WEBVIEW_LABELS};
+use crate::types::CrashReport;
+
+/// Папка отчётов, которые пользователь ещё не видел
+pub fn pending_dir() -> Option<PathBuf> {
+    get_crash_reports_dir().map(|d| d.join("pending"))
+}
+
+/// Папка отчётов, добавленных в диагностику
+pub fn included_dir() -> Option<PathBuf> {
+    get_crash_reports_dir().map(|d| d.join("included"))
+}
+
+/// Текст паники из payload (`panic!("...")` — `&str` или `String`)
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload.downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Box<dyn Any>".to_string())
+}
+
+/// Сохраняет отчёт в папку (`<id>.json`)
+pub fn save_report(dir: &Path, report: &CrashReport) -> Result<PathBuf, String> {
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let path = dir.join(format!("{}.json", report.id));
+    let json = serde_json::to_string_pretty(report).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// Отчёты из папки, от старых к новым (нечитаемые файлы пропускаются)
+pub fn list_reports(dir: &Path) -> Vec<CrashReport> {
+    let Ok(entries) = fs::read_dir(dir) else { return Vec::new() };
+    let mut reports: Vec<CrashReport> = entries.flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|p| fs::read_to_string(p).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect();
+    reports.sort_by(|a, b| a.id.cmp(&b.id));
+    reports
+}
+
+/// Удаляет старые отчёты, оставляя последние `keep`
+pub fn prune_reports(dir: &Path, keep: usize) {
+    let reports = list_reports(dir);
+    let excess = reports.len().saturating_sub(keep);
+    for report in &reports[..excess] {
+        let _ = fs::remove_file(dir.join(format!("{}.json", report.id)));
+    }
+}
+
+/// Запоминает метку созданного webview (для отчёта о падении)
+pub fn track_webview(label: &str) {
+    if let Ok(mut labels) = WEBVIEW_LABELS.lock() {
+        labels.insert(label.to_string());
+    }
+}
+
+/// Забывает метку закрытого webview
+pub fn untrack_webview(label: &str) {
+    if let Ok(mut labels) = WEBVIEW_LABELS.lock() {
+        labels.remove(label);
+    }
+}
+
+/// Метки webview без ожидания: если блокировку держит упавший поток — пусто
+fn webview_labels() -> Vec<String> {
+    WEBVIEW_LABELS.try_lock()
+        .map(|labels| labels.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Пишет в лог бэкенда отчёты о падениях прошлого запуска (при старте)
+pub fn log_pending_crashes() {
+    let Some(dir) = pending_dir() else { return };
+    for report in list_reports(&dir) {
+        log::error!(
+            "Previous run crashed at {}: {} ({})",
+            report.timestamp, report.message, report.location.as_deref().unwrap_or("unknown location"),
+        );
+    }
+}
+
+/// Ставит panic hook (в начале `main`, после логгера)
+///
+/// Стандартный hook вызывается после записи отчёта (stderr в debug).
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let now = Local::now();
+        let report = CrashReport {
+            id: format!("crash-{}", now.format("%Y%m%d-%H%M%S-%3f")),
+            timestamp: now.format("%Y-%m-%d %H:%M:%S").to_string(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            message: panic_message(info.payload()),
+            location: info.location().map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+            thread: std::thread::current().name().map(str::to_string),
+            backtrace: Backtrace::force_capture().to_string(),
+            active_tab: ACTIVE_TAB.load(Ordering::SeqCst),
+            claude_visible: CLAUDE_VISIBLE.load(Ordering::SeqCst),
+            claude_detached: CLAUDE_DETACHED.load(Ordering::SeqCst),
+            webviews: webview_labels(),
+        };
+
+        if let Some(dir) = pending_dir() {
+            let _ = save_report(&dir, &report);
+        }
+
+        default_hook(info);
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(id: &str) -> CrashReport {
+        CrashReport {
+            id: id.to_string(),
+            timestamp: "2026-03-02 10:15:00".to_string(),
+            app_version: "4.4.23".to_string(),
+            message: "boom".to_string(),
+            location: Some("src/main.rs:1:1".to_string()),
+            thread: Some("main".to_string()),
+            backtrace: String::new(),
+            active_tab: 2,
+            claude_visible: true,
+            claude_detached: false,
+            webviews: vec!["claude_1".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_panic_message() {
+        let payload: Box<dyn Any + Send> = Box::new("static message");
+        assert_eq!(panic_message(payload.as_ref()), "static message");
+        let payload: Box<dyn Any + Send> = Box::new(format!("index {}", 5));
+        assert_eq!(panic_message(payload.as_ref()), "index 5");
+        let payload: Box<dyn Any + Send> = Box::new(42u32);
+        assert_eq!(panic_message(payload.as_ref()), "Box<dyn Any>");
+    }
+
+    #[test]
+    fn test_save_list_and_prune() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("crashes");
+
+        for id in ["crash-20260302-101500-002", "crash-20260301-090000-000", "crash-20260302-101500-001"] {
+            save_report(&dir, &report(id)).unwrap();
+        }
+        fs::write(dir.join("broken.json"), "{").unwrap();
+
+        let ids: Vec<String> = list_reports(&dir).into_iter().map(|r| r.id).collect();
+        assert_eq!(ids, vec!["crash-20260301-090000-000", "crash-20260302-101500-001", "crash-20260302-101500-002"]);
+
+        prune_reports(&dir, 1);
+        let ids: Vec<String> = list_reports(&dir).into_iter().map(|r| r.id).collect();
+        assert_eq!(ids, vec!["crash-20260302-101500-002"]);
+    }
+}
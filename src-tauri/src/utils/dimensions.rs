@@ -22,6 +22,17 @@
 /// Возвращает ошибку если окно "main" не найдено или не удалось получить размер
 pub fn get_dimensions(app: &AppHandle) -> Result<(f64, f64, f64), String> {
     let window = app.get_window("main").ok_or("Window not found")?;
+    get_window_dimensions(&window)
+}
+
+/// Получает размеры произвольного окна в логических пикселях
+///
+/// Используется для отдельного окна Claude (detached режим),
+/// у которого свой размер и свой DPI монитора.
+///
+/// # Returns
+/// Кортеж `(width, height, scale)` — как у `get_dimensions`
+pub fn get_window_dimensions(window: &tauri::Window) -> Result<(f64, f64, f64), String> {
     let size = window.inner_size().map_err(|e| e.to_string())?;
     let scale = window.scale_factor().unwrap_or(1.0);
     
@@ -75,4 +86,49 @@ pub mod limits {
     
     /// Максимальное количество записей в логе диагностики
     pub const MAX_DIAGNOSTICS_ENTRIES: usize = 500;
+    
+    /// Максимальное количество снимков в логе usage
+    pub const MAX_USAGE_LOG_ENTRIES: usize = 5000;
+    
+    /// Максимальное количество чатов в счётчиках Auto-Continue
+    pub const MAX_AUTO_CONTINUE_CHATS: usize = 500;
+    
+    /// Максимальное количество проектов в реестре
+    pub const MAX_PROJECT_ENTRIES: usize = 500;
+    
+    /// Максимальное количество файлов в распаковываемом архиве
+    pub const MAX_ARCHIVE_ENTRIES: usize = 1000;
+    
+    /// Максимальный суммарный размер распакованных файлов (500 MB)
+    pub const MAX_EXTRACTED_SIZE: u64 = 500 * 1024 * 1024;
+    
+    /// Максимальное количество записей в списке неудачных загрузок
+    pub const MAX_FAILED_DOWNLOADS: usize = 100;
+    
+    /// Размер JSONL лога, после которого он ротируется и сжимается (2 MB)
+    pub const MAX_LOG_FILE_SIZE: u64 = 2 * 1024 * 1024;
+    
+    /// Размер файла лога бэкенда, после которого он ротируется (1 MB)
+    pub const MAX_BACKEND_LOG_SIZE: u64 = 1024 * 1024;
+    
+    /// Количество файлов лога бэкенда (текущий + ротированные)
+    pub const BACKEND_LOG_FILES: usize = 3;
+    
+    /// Строк лога бэкенда в ответе по умолчанию
+    pub const DEFAULT_LOG_TAIL_LINES: usize = 200;
+    
+    /// Строк лога бэкенда в пакете диагностики
+    pub const DIAGNOSTICS_BUNDLE_LOG_LINES: usize = 2000;
+    
+    /// Макс. отчётов о падениях, добавленных в диагностику (старые удаляются)
+    pub const MAX_CRASH_REPORTS: usize = 10;
+    
+    /// Замеров времени этапов в памяти (старые вытесняются)
+    pub const MAX_METRIC_SPANS: usize = 5000;
+    
+    /// Размер страницы запроса к логам по умолчанию
+    pub const DEFAULT_LOG_PAGE_SIZE: usize = 50;
+    
+    /// Максимальный размер страницы запроса к логам
+    pub const MAX_LOG_PAGE_SIZE: usize = 500;
 }
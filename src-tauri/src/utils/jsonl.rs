@@ -0,0 +1,196 @@
+//! Append-only JSONL логи с ротацией по размеру
+//!
+//! Каждая запись — одна строка в конце файла: запись не перечитывает
+//! и не переписывает лог, а сбой посреди записи портит только последнюю
+//! строку (битые строки пропускаются при чтении).
+//!
+//! Когда файл больше MAX_LOG_FILE_SIZE, он переименовывается в
+//! `<name>.1.jsonl`, и новые записи идут в пустой файл. Сжатие (compaction)
+//! сворачивает обе части в актуальные записи и атомарно — через временный
+//! файл и rename — заменяет ими лог. Читатели видят обе части.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::utils::dimensions::limits::MAX_LOG_FILE_SIZE;
+
+/// Путь ротированной части лога (`diagnostics.jsonl` → `diagnostics.1.jsonl`)
+pub fn rotated_path(path: &Path) -> PathBuf {
+    path.with_extension("1.jsonl")
+}
+
+/// Файлы лога в порядке чтения: ротированная часть, затем активная
+pub fn log_files(path: &Path) -> [PathBuf; 2] {
+    [rotated_path(path), path.to_path_buf()]
+}
+
+/// Оканчивается ли файл переводом строки (пустой — да)
+fn ends_with_newline(file: &mut File) -> bool {
+    let mut last = [0u8; 1];
+    match file.seek(SeekFrom::End(-1)) {
+        Ok(_) => file.read_exact(&mut last).is_ok() && last[0] == b'\n',
+        Err(_) => true,
+    }
+}
+
+/// Дописывает запись в конец лога
+///
+/// Если предыдущая запись оборвана сбоем, новая начинается с новой строки.
+///
+/// # Returns
+/// `true`, если лог превысил MAX_LOG_FILE_SIZE и его пора сжать
+pub fn append_record<T: Serialize>(path: &Path, record: &T) -> Result<bool, String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let line = serde_json::to_string(record).map_err(|e| e.to_string())?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| e.to_string())?;
+
+    let prefix = if ends_with_newline(&mut file) { "" } else { "\n" };
+    file.write_all(format!("{}{}\n", prefix, line).as_bytes()).map_err(|e| e.to_string())?;
+
+    let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    if size <= MAX_LOG_FILE_SIZE {
+        return Ok(false);
+    }
+
+    // Ротированная часть ещё не сжата — не затираем её, просто просим сжатие
+    let rotated = rotated_path(path);
+    if !rotated.exists() {
+        drop(file);
+        fs::rename(path, &rotated).map_err(|e| e.to_string())?;
+    }
+    Ok(true)
+}
+
+/// Читает записи лога (обе части, битые строки пропускаются)
+pub fn read_records<T: DeserializeOwned>(path: &Path) -> Vec<T> {
+    log_files(path).iter()
+        .filter_map(|file| fs::read_to_string(file).ok())
+        .flat_map(|content| content.lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect::<Vec<T>>())
+        .collect()
+}
+
+/// Атомарно заменяет лог записями и удаляет ротированную часть
+pub fn rewrite_records<T: Serialize>(path: &Path, records: &[T]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let mut content = String::new();
+    for record in records {
+        content.push_str(&serde_json::to_string(record).map_err(|e| e.to_string())?);
+        content.push('\n');
+    }
+
+    let tmp = path.with_extension("jsonl.tmp");
+    fs::write(&tmp, content).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, path).map_err(|e| e.to_string())?;
+
+    let rotated = rotated_path(path);
+    if rotated.exists() {
+        fs::remove_file(&rotated).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Удаляет лог (обе части)
+pub fn remove_log(path: &Path) -> Result<(), String> {
+    for file in log_files(path) {
+        if file.exists() {
+            fs::remove_file(&file).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Переносит старый лог-массив JSON (`<name>.json`) в JSONL
+///
+/// Записи старого лога ставятся перед уже записанными в JSONL,
+/// старый файл удаляется. Нечитаемый файл остаётся как `<name>.json.bak`.
+///
+/// # Returns
+/// Количество перенесённых записей
+pub fn migrate_json_array<T: DeserializeOwned + Serialize>(path: &Path) -> Result<usize, String> {
+    let legacy = path.with_extension("json");
+    if !legacy.exists() {
+        return Ok(0);
+    }
+
+    let content = fs::read_to_string(&legacy).map_err(|e| e.to_string())?;
+    let Ok(mut records) = serde_json::from_str::<Vec<T>>(&content) else {
+        fs::rename(&legacy, legacy.with_extension("json.bak")).map_err(|e| e.to_string())?;
+        return Ok(0);
+    };
+
+    let migrated = records.len();
+    records.extend(read_records::<T>(path));
+    rewrite_records(path, &records)?;
+    fs::remove_file(&legacy).map_err(|e| e.to_string())?;
+
+    Ok(migrated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_log() -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test_log.jsonl");
+        (dir, path)
+    }
+
+    #[test]
+    fn test_append_recovers_from_torn_line() {
+        let (_dir, path) = temp_log();
+        append_record(&path, &1u32).unwrap();
+        // Сбой посреди записи: строка без перевода строки
+        fs::write(&path, "1\n{\"broken\":").unwrap();
+        append_record(&path, &2u32).unwrap();
+
+        assert_eq!(read_records::<u32>(&path), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_rotation_and_rewrite() {
+        let (_dir, path) = temp_log();
+        let big = "x".repeat(MAX_LOG_FILE_SIZE as usize);
+        assert!(!append_record(&path, &"small").unwrap());
+        assert!(append_record(&path, &big).unwrap());
+        assert!(rotated_path(&path).exists());
+
+        append_record(&path, &"after").unwrap();
+        let records: Vec<String> = read_records(&path);
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[2], "after");
+
+        rewrite_records(&path, &["after"]).unwrap();
+        assert!(!rotated_path(&path).exists());
+        assert_eq!(read_records::<String>(&path), vec!["after"]);
+    }
+
+    #[test]
+    fn test_migrate_json_array() {
+        let (_dir, path) = temp_log();
+        fs::write(path.with_extension("json"), "[1, 2]").unwrap();
+        append_record(&path, &3u32).unwrap();
+
+        assert_eq!(migrate_json_array::<u32>(&path).unwrap(), 2);
+        assert_eq!(read_records::<u32>(&path), vec![1, 2, 3]);
+        assert!(!path.with_extension("json").exists());
+        assert_eq!(migrate_json_array::<u32>(&path).unwrap(), 0);
+    }
+}
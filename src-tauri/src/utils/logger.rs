@@ -0,0 +1,378 @@
+//! Лог бэкенда: уровни, модули и файлы с ротацией
+//!
+//! Реализация `log::Log`: `log::error!` / `warn!` / `info!` из любого модуля
+//! пишутся в `logs/backend.log` в app data. Релизная сборка под Windows
+//! (`windows_subsystem = "windows"`) не показывает stderr, поэтому файл —
+//! единственное место, где видны ошибки бэкенда. В debug строки
+//! дублируются в stderr.
+//!
+//! Формат строки: `2026-03-02 10:00:00.123 WARN  [webview::manager] текст`.
+//! Target — путь модуля без имени крейта (`main` для точки входа).
+//!
+//! Уровень задаётся глобально и по модулям (`log_settings.json`, самый
+//! длинный совпавший префикс). Сторонние крейты (tauri, wry) без своего
+//! правила пишут не подробнее `warn`.
+//!
+//! Когда файл больше MAX_BACKEND_LOG_SIZE, он сдвигается в `backend.1.log`
+//! (старые части — дальше, до BACKEND_LOG_FILES файлов).
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde::{Deserialize, Serialize};
+
+use crate::downloads::paths::{get_backend_log_path, get_log_settings_path};
+use crate::state::{BACKEND_LOG_WRITER, LOG_SETTINGS};
+use crate::types::LogSettings;
+use crate::utils::dimensions::limits::{BACKEND_LOG_FILES, DEFAULT_LOG_TAIL_LINES, MAX_BACKEND_LOG_SIZE};
+
+/// Имя крейта в target записей
+const CRATE_TARGET: &str = "ai_prompts_manager";
+
+/// Строка лога бэкенда
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct BackendLogLine {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+/// Фильтры чтения лога
+#[derive(Deserialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct BackendLogQuery {
+    /// Минимальная важность (`warn` — предупреждения и ошибки)
+    pub level: Option<String>,
+    /// Префикс модуля (`webview`, `commands::scraper`)
+    pub target: Option<String>,
+    /// Подстрока в тексте (без учёта регистра)
+    pub text: Option<String>,
+    /// Не раньше этого времени ("YYYY-MM-DD" или "YYYY-MM-DD HH:MM:SS")
+    pub since: Option<String>,
+    /// Сколько последних строк вернуть (по умолчанию DEFAULT_LOG_TAIL_LINES)
+    pub limit: Option<usize>,
+}
+
+/// Разбирает уровень (`off`, `error` … `trace`, без учёта регистра)
+pub fn parse_level(level: &str) -> Option<LevelFilter> {
+    level.trim().parse().ok()
+}
+
+/// Target записи без имени крейта: `ai_prompts_manager::webview::manager` → `webview::manager`
+pub fn short_target(target: &str) -> &str {
+    match target.strip_prefix(CRATE_TARGET) {
+        Some("") => "main",
+        Some(rest) => rest.strip_prefix("::").unwrap_or(target),
+        None => target,
+    }
+}
+
+/// Совпадает ли target с префиксом модуля (по границе `::`)
+fn target_matches(target: &str, prefix: &str) -> bool {
+    target == prefix
+        || target.strip_prefix(prefix).is_some_and(|rest| rest.starts_with("::"))
+}
+
+/// Уровень для target (короткого): правило с самым длинным префиксом
+/// или общий уровень; сторонние крейты — не подробнее `warn`
+pub fn level_for(settings: &LogSettings, target: &str) -> LevelFilter {
+    let rule = settings.targets.iter()
+        .filter(|(prefix, _)| target_matches(target, prefix))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .and_then(|(_, level)| parse_level(level));
+    if let Some(level) = rule {
+        return level;
+    }
+
+    let default = parse_level(&settings.level).unwrap_or(LevelFilter::Info);
+    if is_own_target(target) {
+        default
+    } else {
+        default.min(LevelFilter::Warn)
+    }
+}
+
+/// Target нашего крейта (после `short_target`)
+fn is_own_target(target: &str) -> bool {
+    target == "main"
+        || ["commands", "downloads", "webview", "utils", "state", "types"]
+            .iter()
+            .any(|module| target_matches(target, module))
+}
+
+/// Самый подробный уровень из настроек — фильтр для `log::set_max_level`
+pub fn max_level(settings: &LogSettings) -> LevelFilter {
+    settings.targets.values()
+        .filter_map(|level| parse_level(level))
+        .chain(std::iter::once(parse_level(&settings.level).unwrap_or(LevelFilter::Info)))
+        .max()
+        .unwrap_or(LevelFilter::Info)
+}
+
+/// Форматирует строку лога (переводы строк в тексте экранируются)
+pub fn format_line(timestamp: &str, level: Level, target: &str, message: &str) -> String {
+    let message = message.replace('\\', "\\\\").replace('\r', "\\r").replace('\n', "\\n");
+    format!("{} {:<5} [{}] {}", timestamp, level, target, message)
+}
+
+/// Разбирает строку лога; чужие строки — `None`
+pub fn parse_line(line: &str) -> Option<BackendLogLine> {
+    // "YYYY-MM-DD HH:MM:SS.mmm" — 23 символа
+    let timestamp = line.get(..23)?;
+    let rest = line.get(24..)?;
+    let (level, rest) = rest.split_once(' ')?;
+    level.parse::<Level>().ok()?;
+    let rest = rest.trim_start().strip_prefix('[')?;
+    let (target, message) = rest.split_once("] ")
+        .or_else(|| rest.strip_suffix(']').map(|t| (t, "")))?;
+
+    let mut unescaped = String::with_capacity(message.len());
+    let mut chars = message.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    Some(BackendLogLine {
+        timestamp: timestamp.to_string(),
+        level: level.to_string(),
+        target: target.to_string(),
+        message: unescaped,
+    })
+}
+
+/// Часть лога с номером (`backend.log` → `backend.2.log`; 0 — сам лог)
+fn part_path(path: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        path.to_path_buf()
+    } else {
+        path.with_extension(format!("{}.log", index))
+    }
+}
+
+/// Файлы лога от старых к новым
+pub fn backend_log_files(path: &Path) -> Vec<PathBuf> {
+    (0..BACKEND_LOG_FILES).rev().map(|i| part_path(path, i)).collect()
+}
+
+/// Сдвигает части лога: `backend.log` → `backend.1.log` → `backend.2.log`…
+fn rotate(path: &Path) {
+    let _ = fs::remove_file(part_path(path, BACKEND_LOG_FILES - 1));
+    for index in (0..BACKEND_LOG_FILES - 1).rev() {
+        let _ = fs::rename(part_path(path, index), part_path(path, index + 1));
+    }
+}
+
+fn open_log(path: &Path) -> Option<(File, u64)> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).ok()?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(path).ok()?;
+    let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    Some((file, size))
+}
+
+/// Дописывает строку в файл лога, ротируя его по размеру
+fn write_to_file(line: &str) {
+    let Some(path) = get_backend_log_path() else { return };
+    let Ok(mut writer) = BACKEND_LOG_WRITER.lock() else { return };
+
+    if writer.as_ref().is_some_and(|(_, size)| *size > MAX_BACKEND_LOG_SIZE) {
+        *writer = None;
+        rotate(&path);
+    }
+    if writer.is_none() {
+        *writer = open_log(&path);
+    }
+
+    if let Some((file, size)) = writer.as_mut() {
+        if file.write_all(format!("{}\n", line).as_bytes()).is_ok() {
+            *size += line.len() as u64 + 1;
+        } else {
+            // Файл удалён или недоступен — переоткроем при следующей записи
+            *writer = None;
+        }
+    }
+}
+
+/// Логгер бэкенда (регистрируется в `init`)
+struct FileLogger;
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        LOG_SETTINGS.lock()
+            .map(|settings| metadata.level() <= level_for(&settings, short_target(metadata.target())))
+            .unwrap_or(true)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S%.3f").to_string();
+        let line = format_line(&timestamp, record.level(), short_target(record.target()), &record.args().to_string());
+        if cfg!(debug_assertions) {
+            eprintln!("{}", line);
+        }
+        write_to_file(&line);
+    }
+
+    fn flush(&self) {
+        if let Ok(mut writer) = BACKEND_LOG_WRITER.lock() {
+            if let Some((file, _)) = writer.as_mut() {
+                let _ = file.flush();
+            }
+        }
+    }
+}
+
+static LOGGER: FileLogger = FileLogger;
+
+/// Читает настройки уровней (по умолчанию, если файла нет)
+fn load_settings() -> LogSettings {
+    get_log_settings_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Регистрирует логгер (в начале `main`, до любых записей)
+pub fn init() {
+    let settings = load_settings();
+    let max = max_level(&settings);
+    if let Ok(mut current) = LOG_SETTINGS.lock() {
+        *current = settings;
+    }
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(max);
+    }
+}
+
+/// Применяет и сохраняет настройки уровней
+pub fn apply_settings(settings: LogSettings) -> Result<LogSettings, String> {
+    if let Some(bad) = std::iter::once(&settings.level)
+        .chain(settings.targets.values())
+        .find(|level| parse_level(level).is_none())
+    {
+        return Err(format!("Unknown log level: {}", bad));
+    }
+
+    let path = get_log_settings_path().ok_or("Cannot get log settings path")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| e.to_string())?;
+
+    log::set_max_level(max_level(&settings));
+    *LOG_SETTINGS.lock().map_err(|_| "Log settings lock poisoned")? = settings.clone();
+    Ok(settings)
+}
+
+/// Закрывает файл лога (перед удалением app data); следующая запись откроет новый
+pub fn close() {
+    if let Ok(mut writer) = BACKEND_LOG_WRITER.lock() {
+        *writer = None;
+    }
+}
+
+/// Текущие настройки уровней
+pub fn current_settings() -> LogSettings {
+    LOG_SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// Подходит ли строка под фильтры
+fn line_matches(line: &BackendLogLine, query: &BackendLogQuery, min_level: Option<Level>, text: Option<&str>) -> bool {
+    min_level.is_none_or(|min| line.level.parse::<Level>().is_ok_and(|level| level <= min))
+        && query.target.as_deref().is_none_or(|prefix| target_matches(&line.target, prefix))
+        && query.since.as_deref().is_none_or(|since| line.timestamp.as_str() >= since)
+        && text.is_none_or(|text| line.message.to_lowercase().contains(text))
+}
+
+/// Читает лог бэкенда (все части) с фильтрами; строки — от старых к новым
+pub fn read_lines(query: &BackendLogQuery) -> Result<Vec<BackendLogLine>, String> {
+    let min_level = match query.level.as_deref() {
+        Some(level) => Some(level.trim().parse::<Level>()
+            .map_err(|_| format!("Unknown log level: {}", level))?),
+        None => None,
+    };
+    let text = query.text.as_deref()
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty());
+
+    let path = get_backend_log_path().ok_or("Cannot get backend log path")?;
+    LOGGER.flush();
+
+    let mut lines: Vec<BackendLogLine> = backend_log_files(&path).iter()
+        .filter_map(|file| fs::read_to_string(file).ok())
+        .flat_map(|content| content.lines().filter_map(parse_line).collect::<Vec<_>>())
+        .filter(|line| line_matches(line, query, min_level, text.as_deref()))
+        .collect();
+
+    let limit = query.limit.unwrap_or(DEFAULT_LOG_TAIL_LINES);
+    if lines.len() > limit {
+        lines.drain(..lines.len() - limit);
+    }
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_format_and_parse_roundtrip() {
+        let line = format_line("2026-03-02 10:00:00.123", Level::Warn, "webview::manager", "first\nsecond \\ [x]");
+        assert_eq!(line.lines().count(), 1);
+
+        let parsed = parse_line(&line).unwrap();
+        assert_eq!(parsed.timestamp, "2026-03-02 10:00:00.123");
+        assert_eq!(parsed.level, "WARN");
+        assert_eq!(parsed.target, "webview::manager");
+        assert_eq!(parsed.message, "first\nsecond \\ [x]");
+
+        assert!(parse_line("thread 'main' panicked at src/main.rs").is_none());
+    }
+
+    #[test]
+    fn test_short_target() {
+        assert_eq!(short_target("ai_prompts_manager::webview::manager"), "webview::manager");
+        assert_eq!(short_target("ai_prompts_manager"), "main");
+        assert_eq!(short_target("tauri::manager"), "tauri::manager");
+    }
+
+    #[test]
+    fn test_level_resolution() {
+        let settings = LogSettings {
+            level: "info".to_string(),
+            targets: BTreeMap::from([
+                ("webview".to_string(), "debug".to_string()),
+                ("webview::manager".to_string(), "error".to_string()),
+                ("wry".to_string(), "info".to_string()),
+            ]),
+        };
+
+        assert_eq!(level_for(&settings, "webview::manager"), LevelFilter::Error);
+        assert_eq!(level_for(&settings, "webview::selectors"), LevelFilter::Debug);
+        // Префикс совпадает только по границе модуля
+        assert_eq!(level_for(&settings, "webviews"), LevelFilter::Warn);
+        assert_eq!(level_for(&settings, "commands::scraper"), LevelFilter::Info);
+        assert_eq!(level_for(&settings, "tauri::manager"), LevelFilter::Warn);
+        assert_eq!(level_for(&settings, "wry"), LevelFilter::Info);
+        assert_eq!(max_level(&settings), LevelFilter::Debug);
+    }
+}
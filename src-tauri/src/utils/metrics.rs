@@ -0,0 +1,327 @@
+//! Метрики времени этапов автоматизации
+//!
+//! Лёгкий рекордер в памяти: каждый замер (span) — этап, таб, смещение от
+//! начала сессии, длительность и успех. Хранятся последние `MAX_METRIC_SPANS`
+//! замеров; перцентили по этапам считаются из них по запросу (`get_stage_metrics`).
+//!
+//! Сессия — запуск приложения или `reset_stage_metrics`. Таймлайн сессии
+//! выгружается командой `export_metrics_timeline`.
+//!
+//! Этапы отправки пишутся как `send.<этап>` (`send.verify_uploads` — ожидание
+//! загрузки файлов, `send.confirm_generation` — ожидание ответа Claude).
+
+use std::collections::{BTreeMap, VecDeque};
+use std::future::Future;
+use std::time::Instant;
+
+use chrono::Local;
+use serde::Serialize;
+
+use crate::state::METRICS;
+use crate::utils::dimensions::limits::MAX_METRIC_SPANS;
+
+/// Имена этапов
+pub mod stage {
+    /// CDP `Runtime.evaluate` в табе Claude
+    pub const CDP_EVAL: &str = "cdp_eval";
+    /// Загрузка страницы в табе Claude (от начала навигации до `Finished`)
+    pub const NAVIGATION: &str = "navigation";
+    /// Прикрепление файлов (`attach_files_batch`): от чтения файлов до
+    /// подтверждения загрузки всех файлов перехватчиком `upload-file` (Windows)
+    pub const UPLOAD: &str = "upload";
+    /// Отправка целиком (`send_to_claude`); этапы — `send.<этап>`
+    pub const SEND: &str = "send";
+    /// Навигация скрапера и ожидание загрузки страницы
+    pub const SCRAPER_NAVIGATION: &str = "scraper_navigation";
+    /// Получение текста страницы скрапером (навигация + CDP eval)
+    pub const SCRAPER_FETCH: &str = "scraper_fetch";
+}
+
+/// Один замер
+#[derive(Serialize, Clone, Debug)]
+pub struct MetricSpan {
+    pub stage: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tab: Option<u8>,
+    /// Начало замера от начала сессии (мс)
+    pub start_ms: u64,
+    pub duration_ms: u64,
+    pub ok: bool,
+}
+
+/// Агрегаты этапа (перцентили — nearest-rank по всем замерам этапа)
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct StageStats {
+    pub stage: String,
+    pub count: usize,
+    pub errors: usize,
+    pub min_ms: u64,
+    pub p50_ms: u64,
+    pub p90_ms: u64,
+    pub p99_ms: u64,
+    pub max_ms: u64,
+    pub mean_ms: u64,
+}
+
+/// Ответ `get_stage_metrics`
+#[derive(Serialize, Clone, Debug)]
+pub struct StageMetrics {
+    pub session_id: String,
+    pub started_at: String,
+    /// Замеров в памяти
+    pub spans: usize,
+    /// Замеров, вытесненных из памяти (не входят в агрегаты)
+    pub dropped: u64,
+    pub stages: Vec<StageStats>,
+}
+
+/// Таймлайн сессии (`export_metrics_timeline`)
+#[derive(Serialize, Clone, Debug)]
+pub struct MetricsTimeline {
+    pub session_id: String,
+    pub started_at: String,
+    pub dropped: u64,
+    pub spans: Vec<MetricSpan>,
+}
+
+/// Замеры текущей сессии
+pub struct MetricsRecorder {
+    session_id: String,
+    started_at: String,
+    started: Instant,
+    spans: VecDeque<MetricSpan>,
+    dropped: u64,
+    /// Начало текущей навигации по табам Claude [tab1, tab2, tab3]
+    navigation: [Option<Instant>; 3],
+}
+
+impl Default for MetricsRecorder {
+    fn default() -> Self {
+        let now = Local::now();
+        Self {
+            session_id: now.format("%Y%m%d-%H%M%S").to_string(),
+            started_at: now.format("%Y-%m-%d %H:%M:%S").to_string(),
+            started: Instant::now(),
+            spans: VecDeque::new(),
+            dropped: 0,
+            navigation: [None; 3],
+        }
+    }
+}
+
+impl MetricsRecorder {
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Добавляет замер; старые вытесняются после `capacity`
+    fn push(&mut self, span: MetricSpan, capacity: usize) {
+        while self.spans.len() >= capacity.max(1) {
+            self.spans.pop_front();
+            self.dropped += 1;
+        }
+        self.spans.push_back(span);
+    }
+
+    fn record(&mut self, stage: String, tab: Option<u8>, started: Instant, ok: bool) {
+        let span = MetricSpan {
+            stage,
+            tab,
+            start_ms: started.saturating_duration_since(self.started).as_millis() as u64,
+            duration_ms: started.elapsed().as_millis() as u64,
+            ok,
+        };
+        self.push(span, MAX_METRIC_SPANS);
+    }
+
+    /// Агрегаты по этапам (`prefix` — только этапы с этим началом, например `send.`)
+    pub fn stats(&self, prefix: Option<&str>) -> StageMetrics {
+        let spans = self.spans.iter()
+            .filter(|s| prefix.is_none_or(|p| s.stage.starts_with(p)));
+        StageMetrics {
+            session_id: self.session_id.clone(),
+            started_at: self.started_at.clone(),
+            spans: self.spans.len(),
+            dropped: self.dropped,
+            stages: aggregate(spans),
+        }
+    }
+
+    pub fn timeline(&self) -> MetricsTimeline {
+        MetricsTimeline {
+            session_id: self.session_id.clone(),
+            started_at: self.started_at.clone(),
+            dropped: self.dropped,
+            spans: self.spans.iter().cloned().collect(),
+        }
+    }
+}
+
+/// Перцентиль по отсортированным значениям (nearest-rank, `p` в процентах)
+pub fn percentile(sorted: &[u64], p: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Агрегаты замеров по этапам (по имени этапа)
+pub fn aggregate<'a>(spans: impl Iterator<Item = &'a MetricSpan>) -> Vec<StageStats> {
+    let mut by_stage: BTreeMap<&str, (Vec<u64>, usize)> = BTreeMap::new();
+    for span in spans {
+        let entry = by_stage.entry(span.stage.as_str()).or_default();
+        entry.0.push(span.duration_ms);
+        if !span.ok {
+            entry.1 += 1;
+        }
+    }
+
+    by_stage.into_iter()
+        .map(|(stage, (mut durations, errors))| {
+            durations.sort_unstable();
+            let total: u64 = durations.iter().sum();
+            StageStats {
+                stage: stage.to_string(),
+                count: durations.len(),
+                errors,
+                min_ms: durations[0],
+                p50_ms: percentile(&durations, 50.0),
+                p90_ms: percentile(&durations, 90.0),
+                p99_ms: percentile(&durations, 99.0),
+                max_ms: durations[durations.len() - 1],
+                mean_ms: total / durations.len() as u64,
+            }
+        })
+        .collect()
+}
+
+/// Записывает замер, начатый в `started`
+pub fn record(stage: impl Into<String>, tab: Option<u8>, started: Instant, ok: bool) {
+    if let Ok(mut metrics) = METRICS.lock() {
+        metrics.record(stage.into(), tab, started, ok);
+    }
+}
+
+/// Замер с несколькими точками выхода: `end(ok)` записывает результат,
+/// drop без `end` (ранний `return` / `?`) записывается как ошибка
+pub struct Span {
+    stage: String,
+    tab: Option<u8>,
+    started: Instant,
+    done: bool,
+}
+
+impl Span {
+    pub fn start(stage: impl Into<String>, tab: Option<u8>) -> Self {
+        Self { stage: stage.into(), tab, started: Instant::now(), done: false }
+    }
+
+    pub fn end(mut self, ok: bool) {
+        self.done = true;
+        record(std::mem::take(&mut self.stage), self.tab, self.started, ok);
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if !self.done {
+            record(std::mem::take(&mut self.stage), self.tab, self.started, false);
+        }
+    }
+}
+
+/// Замеряет future с результатом (`ok` — `Result::is_ok`)
+pub async fn timed<T, E, F>(stage: &str, tab: Option<u8>, fut: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    let span = Span::start(stage, tab);
+    let result = fut.await;
+    span.end(result.is_ok());
+    result
+}
+
+/// Начало навигации в табе Claude (`PageLoadEvent::Started`)
+pub fn navigation_started(tab: u8) {
+    if let Ok(mut metrics) = METRICS.lock() {
+        if let Some(slot) = metrics.navigation.get_mut((tab as usize).wrapping_sub(1)) {
+            *slot = Some(Instant::now());
+        }
+    }
+}
+
+/// Конец навигации в табе Claude (`PageLoadEvent::Finished`)
+pub fn navigation_finished(tab: u8, ok: bool) {
+    if let Ok(mut metrics) = METRICS.lock() {
+        let started = metrics.navigation.get_mut((tab as usize).wrapping_sub(1)).and_then(Option::take);
+        if let Some(started) = started {
+            metrics.record(stage::NAVIGATION.to_string(), Some(tab), started, ok);
+        }
+    }
+}
+
+/// Начинает новую сессию (замеры прошлой сбрасываются)
+pub fn reset() -> String {
+    match METRICS.lock() {
+        Ok(mut metrics) => {
+            *metrics = MetricsRecorder::default();
+            metrics.session_id.clone()
+        }
+        Err(_) => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(stage: &str, duration_ms: u64, ok: bool) -> MetricSpan {
+        MetricSpan { stage: stage.to_string(), tab: Some(1), start_ms: 0, duration_ms, ok }
+    }
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        let sorted: Vec<u64> = (1..=10).map(|n| n * 10).collect();
+        assert_eq!(percentile(&sorted, 50.0), 50);
+        assert_eq!(percentile(&sorted, 90.0), 90);
+        assert_eq!(percentile(&sorted, 99.0), 100);
+        assert_eq!(percentile(&sorted, 0.0), 10);
+        assert_eq!(percentile(&[7], 99.0), 7);
+        assert_eq!(percentile(&[], 50.0), 0);
+    }
+
+    #[test]
+    fn test_aggregate_by_stage() {
+        let spans = [
+            span("send.attach", 300, true),
+            span("cdp_eval", 20, true),
+            span("send.attach", 100, false),
+            span("cdp_eval", 40, true),
+            span("send.attach", 200, true),
+        ];
+        let stats = aggregate(spans.iter());
+
+        assert_eq!(stats.iter().map(|s| s.stage.as_str()).collect::<Vec<_>>(), vec!["cdp_eval", "send.attach"]);
+        let attach = &stats[1];
+        assert_eq!((attach.count, attach.errors), (3, 1));
+        assert_eq!((attach.min_ms, attach.p50_ms, attach.max_ms, attach.mean_ms), (100, 200, 300, 200));
+    }
+
+    #[test]
+    fn test_recorder_drops_oldest_and_filters_prefix() {
+        let mut recorder = MetricsRecorder::default();
+        recorder.push(span("cdp_eval", 10, true), 2);
+        recorder.push(span("send.insert", 20, true), 2);
+        recorder.push(span("send.click_send", 30, true), 2);
+
+        let timeline = recorder.timeline();
+        assert_eq!(timeline.dropped, 1);
+        assert_eq!(timeline.spans.iter().map(|s| s.stage.as_str()).collect::<Vec<_>>(), vec!["send.insert", "send.click_send"]);
+
+        let stats = recorder.stats(Some("send."));
+        assert_eq!(stats.spans, 2);
+        assert_eq!(stats.stages.len(), 2);
+        assert!(recorder.stats(Some("scraper_")).stages.is_empty());
+    }
+}
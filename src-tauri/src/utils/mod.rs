@@ -4,12 +4,24 @@
 //! - `mime` - определение MIME-типов
 //! - `platform` - платформо-зависимые функции
 //! - `dimensions` - работа с размерами окна
+//! - `window_state` - сохранение геометрии окна между запусками
+//! - `jsonl` - append-only JSONL логи с ротацией
+//! - `logger` - лог бэкенда с уровнями и ротацией файлов
+//! - `redact` - редактирование персональных данных в пакете диагностики
+//! - `crash` - отчёты о панике бэкенда
+//! - `metrics` - замеры времени этапов автоматизации
 
 pub mod mime;
 pub mod platform;
 pub mod dimensions;
+pub mod window_state;
+pub mod jsonl;
+pub mod logger;
+pub mod redact;
+pub mod crash;
+pub mod metrics;
 
 // Реэкспорт часто используемых функций
 pub use mime::get_mime_type;
 pub use platform::{set_window_icon_from_exe, open_file_in_system, open_directory_in_system};
-pub use dimensions::get_dimensions;
+pub use dimensions::{get_dimensions, get_window_dimensions};
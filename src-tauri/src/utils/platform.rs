@@ -132,3 +132,78 @@ pub fn open_directory_in_system(dir_path: &std::path::Path) -> Result<(), String
     
     Ok(())
 }
+
+/// Версия ОС (для пакета диагностики)
+///
+/// Использует:
+/// - Windows: `cmd /C ver` (`Microsoft Windows [Version 10.0.22631.4317]`)
+/// - macOS: `sw_vers -productVersion`
+/// - Linux: `PRETTY_NAME` из `/etc/os-release`
+///
+/// # Returns
+/// `None`, если версию определить не удалось
+pub fn os_version() -> Option<String> {
+    #[cfg(target_os = "windows")]
+    let version = {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW: без мелькающего окна консоли
+        Command::new("cmd")
+            .args(["/C", "ver"])
+            .creation_flags(0x0800_0000)
+            .output()
+            .ok()
+            .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+    };
+
+    #[cfg(target_os = "macos")]
+    let version = Command::new("sw_vers")
+        .arg("-productVersion")
+        .output()
+        .ok()
+        .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+        .filter(|v| !v.is_empty())
+        .map(|v| format!("macOS {}", v));
+
+    #[cfg(target_os = "linux")]
+    let version = std::fs::read_to_string("/etc/os-release").ok()
+        .and_then(|content| parse_os_release(&content));
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    let version: Option<String> = None;
+
+    version.filter(|v| !v.is_empty())
+}
+
+/// Название и версия дистрибутива из `/etc/os-release`
+///
+/// `PRETTY_NAME`, иначе `NAME VERSION_ID`
+#[cfg_attr(not(any(target_os = "linux", test)), allow(dead_code))]
+fn parse_os_release(content: &str) -> Option<String> {
+    let field = |key: &str| content.lines()
+        .filter_map(|line| line.split_once('='))
+        .find(|(k, _)| k.trim() == key)
+        .map(|(_, v)| v.trim().trim_matches(['"', '\'']).to_string())
+        .filter(|v| !v.is_empty());
+
+    field("PRETTY_NAME").or_else(|| {
+        let name = field("NAME")?;
+        Some(match field("VERSION_ID") {
+            Some(version) => format!("{} {}", name, version),
+            None => name,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_os_release() {
+        let content = "NAME=\"Ubuntu\"\nVERSION_ID=\"24.04\"\nPRETTY_NAME=\"Ubuntu 24.04.1 LTS\"\n";
+        assert_eq!(parse_os_release(content).as_deref(), Some("Ubuntu 24.04.1 LTS"));
+        assert_eq!(parse_os_release("NAME=Arch Linux\nID=arch\n").as_deref(), Some("Arch Linux"));
+        assert_eq!(parse_os_release("NAME=Fedora\nVERSION_ID=41\n").as_deref(), Some("Fedora 41"));
+        assert_eq!(parse_os_release("ID=unknown\n"), None);
+    }
+}
@@ -0,0 +1,447 @@
+//! Редактирование персональных данных в пакете диагностики
+//!
+//! Заменяет на метки (`<email-1>`, `<org-1>`, `<project-1>`, `<uuid-1>`,
+//! `<path-1>`):
+//! - email-адреса
+//! - UUID: после `organizations/` или `lastActiveOrg=` — org id, после
+//!   `project/` / `projects/` — проект, остальные (чаты, файлы) — `uuid`
+//! - пути к файлам: `C:\…`, `C:/…`, `\\server\…`, `~/…`, домашние и
+//!   временные папки Unix, а также известные пути (домашняя папка, загрузки).
+//!   Путь продолжается после пробела, если дальше идёт следующая папка
+//!   или имя файла с расширением (`C:\Users\John Smith\…`, `plan (1).md`)
+//!
+//! Одно и то же значение получает одну метку во всём пакете — связи между
+//! файлами пакета сохраняются. Сами значения в отчёт не попадают: только
+//! количество замен по файлам и категориям.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Категория заменённых данных
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RedactionKind {
+    Email,
+    Org,
+    Project,
+    Uuid,
+    Path,
+}
+
+impl RedactionKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Email => "email",
+            Self::Org => "org",
+            Self::Project => "project",
+            Self::Uuid => "uuid",
+            Self::Path => "path",
+        }
+    }
+}
+
+/// Отчёт о редактировании (`redaction_report.json` в пакете)
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct RedactionReport {
+    /// Правила замены
+    pub rules: Vec<String>,
+    /// Замен по файлам пакета и категориям
+    pub files: BTreeMap<String, BTreeMap<String, usize>>,
+    /// Разных значений по категориям
+    pub distinct: BTreeMap<String, usize>,
+}
+
+/// Корни Unix-путей, которые считаются путями к файлам
+const UNIX_ROOTS: &[&str] = &[
+    "/home/", "/Users/", "/root/", "/tmp/", "/var/", "/private/", "/mnt/", "/media/", "/Volumes/",
+];
+
+/// Сколько слов после пробела просматривается в поисках продолжения пути
+const PATH_SPACE_LOOKAHEAD: usize = 3;
+
+/// Символы, на которых заканчивается путь (кроме пробелов и `)`)
+fn ends_path(b: u8) -> bool {
+    b.is_ascii_whitespace() || b"\"'<>|*?,;]}".contains(&b)
+}
+
+/// Начинается ли слово с нового пути или URL (`C:\`, `\\`, `/`, `~`, `https://`)
+fn starts_new_path(word: &[u8]) -> bool {
+    matches!(word.first(), Some(b'/' | b'\\' | b'~'))
+        || (word.len() > 2 && word[0].is_ascii_alphabetic() && word[1] == b':' && matches!(word[2], b'\\' | b'/'))
+        || word.windows(3).any(|w| w == b"://")
+}
+
+/// Имя файла с расширением (`plan.md`, `(1).md:`)
+fn has_extension(word: &[u8]) -> bool {
+    let mut word = word;
+    while let [rest @ .., b'.' | b':' | b')'] = word {
+        word = rest;
+    }
+    word.iter().rposition(|c| *c == b'.').is_some_and(|dot| {
+        let ext = &word[dot + 1..];
+        dot > 0 && (1..=8).contains(&ext.len()) && ext.iter().all(u8::is_ascii_alphanumeric)
+    })
+}
+
+/// Продолжается ли путь после пробела с позиции `start`
+///
+/// Да, если следующее слово — имя файла с расширением, или одно из
+/// ближайших `PATH_SPACE_LOOKAHEAD` слов содержит разделитель папок
+/// (`John Smith\…`, `Program Files (x86)\…`) и не начинает новый путь.
+fn continues_path(b: &[u8], mut start: usize) -> bool {
+    for n in 0..PATH_SPACE_LOOKAHEAD {
+        let mut end = start;
+        while end < b.len() && !ends_path(b[end]) {
+            end += 1;
+        }
+        let word = &b[start..end];
+        if word.is_empty() || starts_new_path(word) {
+            return false;
+        }
+        if word.contains(&b'\\') || word.contains(&b'/') || (n == 0 && has_extension(word)) {
+            return true;
+        }
+        if b.get(end) != Some(&b' ') {
+            return false;
+        }
+        start = end + 1;
+    }
+    false
+}
+
+fn is_word(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+/// Может ли перед путём стоять этот символ
+fn path_boundary(text: &[u8], i: usize) -> bool {
+    i == 0 || b" \t\r\n\"'(=[:".contains(&text[i - 1])
+}
+
+/// Путь с позиции `i`: конец пути или `None`
+fn match_path(text: &str, i: usize, known: &[String]) -> Option<usize> {
+    let b = text.as_bytes();
+    let rest = &text[i..];
+
+    let start_len = if let Some(known) = known.iter().find(|k| rest.starts_with(k.as_str())) {
+        // Известный путь может содержать пробелы
+        (i == 0 || !is_word(b[i - 1])).then_some(known.len())?
+    } else if b.len() > i + 2
+        && b[i].is_ascii_alphabetic()
+        && b[i + 1] == b':'
+        && (b[i + 2] == b'\\' || b[i + 2] == b'/')
+        && (i == 0 || !is_word(b[i - 1]))
+    {
+        3
+    } else if (rest.starts_with("\\\\") && b.get(i + 2).is_some_and(|c| is_word(*c)))
+        || rest.starts_with("~/")
+        || rest.starts_with("~\\")
+    {
+        if !path_boundary(b, i) {
+            return None;
+        }
+        2
+    } else if UNIX_ROOTS.iter().any(|root| rest.starts_with(root)) && path_boundary(b, i) {
+        1
+    } else {
+        return None;
+    };
+
+    let mut end = i + start_len;
+    // Скобки внутри пути (`Program Files (x86)`, `plan (1).md`);
+    // непарная `)` — конец пути
+    let mut depth = 0usize;
+    while end < b.len() {
+        match b[end] {
+            b'(' => depth += 1,
+            b')' if depth == 0 => break,
+            b')' => depth -= 1,
+            b' ' if continues_path(b, end + 1) => {}
+            c if ends_path(c) => break,
+            _ => {}
+        }
+        end += 1;
+    }
+    // Точка или двоеточие в конце — знак препинания, а не часть пути
+    while end > i + start_len && matches!(b[end - 1], b'.' | b':') {
+        end -= 1;
+    }
+    Some(end)
+}
+
+/// Email с `@` в позиции `at`: (начало, конец)
+fn match_email(text: &str, at: usize) -> Option<(usize, usize)> {
+    let b = text.as_bytes();
+    let mut start = at;
+    while start > 0 && (b[start - 1].is_ascii_alphanumeric() || b"._%+-".contains(&b[start - 1])) {
+        start -= 1;
+    }
+    let mut end = at + 1;
+    while end < b.len() && (b[end].is_ascii_alphanumeric() || b[end] == b'.' || b[end] == b'-') {
+        end += 1;
+    }
+    while end > at + 1 && matches!(b[end - 1], b'.' | b'-') {
+        end -= 1;
+    }
+
+    let domain = &text[at + 1..end];
+    let tld = domain.rsplit_once('.')?.1;
+    (start < at && tld.len() >= 2 && tld.bytes().all(|c| c.is_ascii_alphabetic())).then_some((start, end))
+}
+
+/// UUID (8-4-4-4-12) с позиции `i`
+fn is_uuid_at(text: &str, i: usize) -> bool {
+    let b = text.as_bytes();
+    if b.len() < i + 36 || (i > 0 && (b[i - 1].is_ascii_alphanumeric() || b[i - 1] == b'-')) {
+        return false;
+    }
+    if b.get(i + 36).is_some_and(|c| c.is_ascii_alphanumeric() || *c == b'-') {
+        return false;
+    }
+    (0..36).all(|k| match k {
+        8 | 13 | 18 | 23 => b[i + k] == b'-',
+        _ => b[i + k].is_ascii_hexdigit(),
+    })
+}
+
+/// Категория UUID по тексту перед ним
+fn uuid_kind(before: &str) -> RedactionKind {
+    let tail = (before.len().saturating_sub(16)..=before.len())
+        .find(|&k| before.is_char_boundary(k))
+        .unwrap_or(before.len());
+    let before = before[tail..].to_ascii_lowercase();
+    if before.ends_with("organizations/") || before.ends_with("lastactiveorg=") {
+        RedactionKind::Org
+    } else if before.ends_with("project/") || before.ends_with("projects/") {
+        RedactionKind::Project
+    } else {
+        RedactionKind::Uuid
+    }
+}
+
+/// Заменяет персональные данные в тексте и строках JSON
+pub struct Redactor {
+    /// Известные пути (самые длинные первыми)
+    known_paths: Vec<String>,
+    /// Значение → категория и метка (категория — по первому появлению)
+    labels: HashMap<String, (RedactionKind, String)>,
+    next: HashMap<RedactionKind, usize>,
+    files: BTreeMap<String, BTreeMap<String, usize>>,
+}
+
+impl Redactor {
+    /// # Arguments
+    /// * `known_paths` - пути, которые заменяются целиком даже с пробелами
+    ///   (домашняя папка, папка загрузок, app data)
+    pub fn new(known_paths: Vec<String>) -> Self {
+        let mut known_paths: Vec<String> = known_paths.into_iter()
+            .map(|p| p.trim_end_matches(['/', '\\']).to_string())
+            .filter(|p| p.len() > 3)
+            .collect();
+        known_paths.sort_by_key(|p| std::cmp::Reverse(p.len()));
+        known_paths.dedup();
+
+        Self { known_paths, labels: HashMap::new(), next: HashMap::new(), files: BTreeMap::new() }
+    }
+
+    /// Метка значения (одна на значение во всём пакете)
+    fn label(&mut self, file: &str, kind: RedactionKind, value: &str) -> String {
+        let (kind, label) = match self.labels.get(value) {
+            Some(known) => known.clone(),
+            None => {
+                let n = self.next.entry(kind).or_insert(0);
+                *n += 1;
+                let label = format!("<{}-{}>", kind.as_str(), n);
+                self.labels.insert(value.to_string(), (kind, label.clone()));
+                (kind, label)
+            }
+        };
+
+        *self.files.entry(file.to_string()).or_default()
+            .entry(kind.as_str().to_string()).or_default() += 1;
+        label
+    }
+
+    /// Редактирует текст
+    ///
+    /// # Arguments
+    /// * `file` - файл пакета (для отчёта)
+    pub fn redact_text(&mut self, file: &str, text: &str) -> String {
+        let text = self.redact_paths(file, text);
+        let text = self.redact_emails(file, &text);
+        self.redact_uuids(file, &text)
+    }
+
+    fn redact_paths(&mut self, file: &str, text: &str) -> String {
+        let known = std::mem::take(&mut self.known_paths);
+        let mut out = String::with_capacity(text.len());
+        let (mut copied, mut i) = (0, 0);
+        while i < text.len() {
+            if !text.is_char_boundary(i) {
+                i += 1;
+                continue;
+            }
+            match match_path(text, i, &known) {
+                Some(end) => {
+                    out.push_str(&text[copied..i]);
+                    out.push_str(&self.label(file, RedactionKind::Path, &text[i..end]));
+                    copied = end;
+                    i = end;
+                }
+                None => i += 1,
+            }
+        }
+        out.push_str(&text[copied..]);
+        self.known_paths = known;
+        out
+    }
+
+    fn redact_emails(&mut self, file: &str, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut copied = 0;
+        for (at, _) in text.match_indices('@') {
+            if at < copied {
+                continue;
+            }
+            if let Some((start, end)) = match_email(text, at).filter(|(start, _)| *start >= copied) {
+                out.push_str(&text[copied..start]);
+                out.push_str(&self.label(file, RedactionKind::Email, &text[start..end]));
+                copied = end;
+            }
+        }
+        out.push_str(&text[copied..]);
+        out
+    }
+
+    fn redact_uuids(&mut self, file: &str, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let (mut copied, mut i) = (0, 0);
+        while i + 36 <= text.len() {
+            if text.is_char_boundary(i) && is_uuid_at(text, i) {
+                let kind = uuid_kind(&text[..i]);
+                out.push_str(&text[copied..i]);
+                out.push_str(&self.label(file, kind, &text[i..i + 36].to_ascii_lowercase()));
+                copied = i + 36;
+                i += 36;
+            } else {
+                i += 1;
+            }
+        }
+        out.push_str(&text[copied..]);
+        out
+    }
+
+    /// Редактирует все строки (и ключи) JSON-значения
+    pub fn redact_value(&mut self, file: &str, value: Value) -> Value {
+        match value {
+            Value::String(s) => Value::String(self.redact_text(file, &s)),
+            Value::Array(items) => Value::Array(items.into_iter().map(|v| self.redact_value(file, v)).collect()),
+            Value::Object(map) => Value::Object(map.into_iter()
+                .map(|(k, v)| (self.redact_text(file, &k), self.redact_value(file, v)))
+                .collect()),
+            other => other,
+        }
+    }
+
+    /// Отчёт: замены по файлам и количество разных значений
+    pub fn report(&self) -> RedactionReport {
+        let mut distinct = BTreeMap::new();
+        for (kind, _) in self.labels.values() {
+            *distinct.entry(kind.as_str().to_string()).or_default() += 1;
+        }
+
+        RedactionReport {
+            rules: vec![
+                "email: email addresses".to_string(),
+                "org: UUID after organizations/ or lastActiveOrg=".to_string(),
+                "project: UUID after project/ or projects/".to_string(),
+                "uuid: other UUIDs (chats, files)".to_string(),
+                "path: absolute file paths (Windows, UNC, ~/, home and temp folders, known app folders)".to_string(),
+            ],
+            files: self.files.clone(),
+            distinct,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORG: &str = "0b7e1a52-3c4d-4e5f-8a9b-0c1d2e3f4a5b";
+    const PROJECT: &str = "11111111-2222-4333-8444-555555555555";
+
+    #[test]
+    fn test_redacts_ids_and_emails_consistently() {
+        let mut redactor = Redactor::new(Vec::new());
+        let text = format!(
+            "GET https://claude.ai/api/organizations/{org}/projects/{project} for john.doe+test@mail.example.com; chat/{project} org {org_upper}",
+            org = ORG, project = PROJECT, org_upper = ORG.to_uppercase(),
+        );
+        assert_eq!(
+            redactor.redact_text("backend.log", &text),
+            "GET https://claude.ai/api/organizations/<org-1>/projects/<project-1> for <email-1>; chat/<project-1> org <org-1>",
+        );
+
+        // Та же организация в другом файле — та же метка
+        let value = serde_json::json!({ "url": format!("https://claude.ai/api/organizations/{}/usage", ORG), "n": 1 });
+        let redacted = redactor.redact_value("diagnostics.json", value);
+        assert_eq!(redacted["url"], "https://claude.ai/api/organizations/<org-1>/usage");
+        assert_eq!(redacted["n"], 1);
+
+        let report = redactor.report();
+        assert_eq!(report.files["backend.log"]["org"], 2);
+        assert_eq!(report.files["diagnostics.json"]["org"], 1);
+        assert_eq!(report.distinct["project"], 1);
+        // Не email: нет домена верхнего уровня
+        assert_eq!(redactor.redact_text("x", "user@localhost"), "user@localhost");
+    }
+
+    #[test]
+    fn test_redacts_paths() {
+        let mut redactor = Redactor::new(vec!["C:\\Users\\John Doe".to_string()]);
+
+        assert_eq!(
+            redactor.redact_text("x", "Failed to write C:\\Users\\John Doe\\Downloads\\plan (1).md: denied"),
+            "Failed to write <path-1>: denied",
+        );
+        assert_eq!(
+            redactor.redact_text("x", "saved to D:/Work/out.md. Next"),
+            "saved to <path-2>. Next",
+        );
+        assert_eq!(
+            redactor.redact_text("x", "\"file_path\":\"/home/john/Загрузки/a.md\""),
+            "\"file_path\":\"<path-3>\"",
+        );
+        // URL — не путь
+        assert_eq!(redactor.redact_text("x", "https://claude.ai/tmp/x"), "https://claude.ai/tmp/x");
+    }
+
+    #[test]
+    fn test_redacts_unknown_paths_with_spaces() {
+        let mut redactor = Redactor::new(Vec::new());
+
+        assert_eq!(
+            redactor.redact_text("x", "Failed to open C:\\Users\\John Smith\\My Notes\\plan v2.md for writing"),
+            "Failed to open <path-1> for writing",
+        );
+        assert_eq!(
+            redactor.redact_text("x", "(installed in C:\\Program Files (x86)\\App) ok"),
+            "(installed in <path-2>) ok",
+        );
+        assert_eq!(
+            redactor.redact_text("x", "moved /home/jane/old notes/a.md to /tmp/b.md."),
+            "moved <path-3> to <path-4>.",
+        );
+        // Следующий путь через пробел — отдельная метка
+        assert_eq!(
+            redactor.redact_text("x", "copy D:\\a.md D:\\b.md"),
+            "copy <path-5> <path-6>",
+        );
+        assert_eq!(
+            redactor.redact_text("x", "D:\\a.md https://claude.ai/x"),
+            "<path-5> https://claude.ai/x",
+        );
+    }
+}
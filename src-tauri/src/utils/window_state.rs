@@ -0,0 +1,211 @@
+//! Сохранение геометрии главного окна между запусками
+//!
+//! Этот модуль содержит:
+//! - Загрузку/сохранение `window_state.json` в app data
+//! - Отслеживание последней "нормальной" (не maximized) геометрии окна
+//! - Проверку, что сохранённая позиция попадает на существующий монитор
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+
+use tauri::{AppHandle, Manager, Monitor};
+
+use crate::downloads::paths::get_app_data_dir;
+use crate::state::{CLAUDE_VISIBLE, PANEL_RATIO, WINDOW_STATE};
+use crate::types::WindowState;
+
+/// Минимальный размер главного окна (совпадает с `min_inner_size`)
+pub const MIN_WIDTH: f64 = 800.0;
+pub const MIN_HEIGHT: f64 = 500.0;
+
+/// Рабочая область монитора в логических пикселях
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorArea {
+    pub name: Option<String>,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl MonitorArea {
+    /// Строит область из монитора Tauri (work area без панели задач)
+    pub fn from_monitor(monitor: &Monitor) -> Self {
+        let scale = monitor.scale_factor();
+        let area = monitor.work_area();
+        Self {
+            name: monitor.name().cloned(),
+            x: area.position.x as f64 / scale,
+            y: area.position.y as f64 / scale,
+            width: area.size.width as f64 / scale,
+            height: area.size.height as f64 / scale,
+        }
+    }
+
+    fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+}
+
+/// Подгоняет сохранённое состояние под текущие мониторы
+///
+/// Если сохранённого монитора больше нет или левый верхний угол окна
+/// не попадает ни на один монитор — позиция сбрасывается (окно будет
+/// отцентрировано), а размер ограничивается первым (основным) монитором.
+pub fn clamp_to_monitors(state: &WindowState, monitors: &[MonitorArea]) -> WindowState {
+    let mut result = state.clone();
+
+    let monitor_present = match &state.monitor {
+        Some(name) => monitors.iter().any(|m| m.name.as_ref() == Some(name)),
+        None => true,
+    };
+
+    let target = match (state.x, state.y) {
+        (Some(x), Some(y)) if monitor_present => monitors.iter().find(|m| m.contains(x, y)),
+        _ => None,
+    };
+
+    let bounds = match target {
+        Some(monitor) => monitor,
+        None => {
+            result.x = None;
+            result.y = None;
+            result.monitor = None;
+            match monitors.first() {
+                Some(monitor) => monitor,
+                None => return result,
+            }
+        }
+    };
+
+    result.width = state.width.min(bounds.width).max(MIN_WIDTH);
+    result.height = state.height.min(bounds.height).max(MIN_HEIGHT);
+
+    // Окно не должно вылезать за правый/нижний край монитора
+    if let (Some(x), Some(y)) = (result.x, result.y) {
+        result.x = Some(x.min(bounds.x + bounds.width - result.width).max(bounds.x));
+        result.y = Some(y.min(bounds.y + bounds.height - result.height).max(bounds.y));
+    }
+
+    result
+}
+
+/// Путь к файлу состояния окна
+fn get_window_state_path() -> Option<PathBuf> {
+    get_app_data_dir().map(|dir| dir.join("window_state.json"))
+}
+
+/// Загружает состояние окна (или значения по умолчанию)
+pub fn load_window_state() -> WindowState {
+    get_window_state_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Сохраняет состояние окна
+pub fn save_window_state(state: &WindowState) -> Result<(), String> {
+    let path = get_window_state_path().ok_or("Cannot get app data dir")?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let json = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Запоминает текущую геометрию главного окна
+///
+/// Вызывается на Moved/Resized. Геометрия maximized/minimized окна
+/// не запоминается — при восстановлении из maximized нужен прежний размер.
+pub fn track_window_geometry(app: &AppHandle) {
+    let Some(window) = app.get_window("main") else { return };
+
+    if window.is_maximized().unwrap_or(false) || window.is_minimized().unwrap_or(false) {
+        return;
+    }
+
+    let scale = window.scale_factor().unwrap_or(1.0);
+    let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) else { return };
+    let monitor = window.current_monitor().ok().flatten()
+        .and_then(|m| m.name().cloned());
+
+    if let Ok(mut state) = WINDOW_STATE.lock() {
+        state.x = Some(position.x as f64 / scale);
+        state.y = Some(position.y as f64 / scale);
+        state.width = size.width as f64 / scale;
+        state.height = size.height as f64 / scale;
+        state.monitor = monitor;
+    }
+}
+
+/// Сохраняет состояние окна на диск (при закрытии главного окна)
+pub fn persist_window_state(app: &AppHandle) -> Result<(), String> {
+    let maximized = app.get_window("main")
+        .and_then(|w| w.is_maximized().ok())
+        .unwrap_or(false);
+
+    let state = {
+        let mut state = WINDOW_STATE.lock().map_err(|e| e.to_string())?;
+        state.maximized = maximized;
+        state.panel_ratio = PANEL_RATIO.load(Ordering::SeqCst);
+        state.claude_visible = CLAUDE_VISIBLE.load(Ordering::SeqCst);
+        state.clone()
+    };
+
+    save_window_state(&state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(name: &str, x: f64) -> MonitorArea {
+        MonitorArea { name: Some(name.to_string()), x, y: 0.0, width: 1920.0, height: 1040.0 }
+    }
+
+    fn saved(monitor: &str, x: f64) -> WindowState {
+        WindowState {
+            width: 1200.0,
+            height: 800.0,
+            x: Some(x),
+            y: Some(100.0),
+            maximized: false,
+            monitor: Some(monitor.to_string()),
+            ..WindowState::default()
+        }
+    }
+
+    #[test]
+    fn test_keeps_position_on_present_monitor() {
+        let monitors = vec![monitor("A", 0.0), monitor("B", 1920.0)];
+        let result = clamp_to_monitors(&saved("B", 2000.0), &monitors);
+        assert_eq!(result.x, Some(2000.0));
+        assert_eq!(result.monitor.as_deref(), Some("B"));
+    }
+
+    #[test]
+    fn test_resets_position_when_monitor_missing() {
+        let monitors = vec![monitor("A", 0.0)];
+        let result = clamp_to_monitors(&saved("B", 2000.0), &monitors);
+        assert_eq!(result.x, None);
+        assert_eq!(result.y, None);
+        assert_eq!(result.monitor, None);
+    }
+
+    #[test]
+    fn test_clamps_size_and_position_to_monitor() {
+        let monitors = vec![MonitorArea { name: Some("A".into()), x: 0.0, y: 0.0, width: 1280.0, height: 700.0 }];
+        let mut state = saved("A", 500.0);
+        state.width = 2500.0;
+        let result = clamp_to_monitors(&state, &monitors);
+        assert_eq!(result.width, 1280.0);
+        assert_eq!(result.height, 700.0);
+        assert_eq!(result.x, Some(0.0));
+        assert_eq!(result.y, Some(0.0));
+    }
+}
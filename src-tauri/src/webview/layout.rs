@@ -0,0 +1,201 @@
+//! Раскладка Claude табов внутри панели
+//!
+//! Этот модуль содержит:
+//! - Геометрию панели (`PanelArea`)
+//! - Расчёт плиток для режимов single / columns / grid
+//! - Позиции toolbar и popup загрузок внутри плитки
+//! - Сохранение выбранного режима между запусками
+
+use std::fs;
+use std::path::PathBuf;
+
+use crate::downloads::paths::get_app_data_dir;
+use crate::types::{ClaudeLayoutMode, LayoutSettings};
+use crate::utils::dimensions::sizes;
+
+/// Прямоугольная область внутри окна-хоста (логические пиксели)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PanelArea {
+    /// Левая граница
+    pub x: f64,
+    /// Верхняя граница
+    pub y: f64,
+    /// Ширина
+    pub width: f64,
+    /// Высота
+    pub height: f64,
+}
+
+impl PanelArea {
+    /// X-координата "за экраном" для скрытых табов (DOM остаётся живым)
+    pub fn offscreen_x(&self) -> f64 {
+        (self.x + self.width) * 2.0
+    }
+}
+
+/// Позиция toolbar таба: по центру плитки, у нижнего края
+pub fn toolbar_position(tile: &PanelArea) -> (f64, f64) {
+    let x = tile.x + (tile.width - sizes::TOOLBAR_WIDTH) / 2.0;
+    let y = tile.y + tile.height - sizes::TOOLBAR_HEIGHT - sizes::TOOLBAR_BOTTOM_OFFSET;
+    (x, y)
+}
+
+/// Позиция popup загрузок: по центру плитки, над её toolbar
+pub fn downloads_position(tile: &PanelArea) -> (f64, f64) {
+    let (_, toolbar_y) = toolbar_position(tile);
+    let x = tile.x + (tile.width - sizes::DOWNLOADS_WIDTH) / 2.0;
+    let y = toolbar_y - sizes::DOWNLOADS_MARGIN - sizes::DOWNLOADS_HEIGHT;
+    (x, y)
+}
+
+/// Делит панель на `count` плиток согласно режиму
+///
+/// * `Single` — одна плитка на всю панель
+/// * `Columns` — вертикальные колонки равной ширины
+/// * `Grid` — сетка ceil(sqrt(n)) колонок; неполный последний ряд
+///   растягивается на всю ширину
+pub fn compute_tiles(area: &PanelArea, mode: ClaudeLayoutMode, count: usize) -> Vec<PanelArea> {
+    if count <= 1 || mode == ClaudeLayoutMode::Single {
+        return vec![*area];
+    }
+
+    match mode {
+        ClaudeLayoutMode::Single => vec![*area],
+        ClaudeLayoutMode::Columns => {
+            let width = area.width / count as f64;
+            (0..count)
+                .map(|i| PanelArea {
+                    x: area.x + width * i as f64,
+                    y: area.y,
+                    width,
+                    height: area.height,
+                })
+                .collect()
+        }
+        ClaudeLayoutMode::Grid => {
+            let cols = (count as f64).sqrt().ceil() as usize;
+            let rows = count.div_ceil(cols);
+            let height = area.height / rows as f64;
+            (0..count)
+                .map(|i| {
+                    let row = i / cols;
+                    let col = i % cols;
+                    let in_row = (count - row * cols).min(cols);
+                    let width = area.width / in_row as f64;
+                    PanelArea {
+                        x: area.x + width * col as f64,
+                        y: area.y + height * row as f64,
+                        width,
+                        height,
+                    }
+                })
+                .collect()
+        }
+    }
+}
+
+/// Возвращает табы, которые показываются одновременно
+///
+/// Активный таб всегда виден. В режиме `Single` — только он.
+/// Порядок — по номеру таба (слева направо, сверху вниз).
+pub fn visible_tabs(settings: &LayoutSettings, active_tab: u8, existing: &[u8]) -> Vec<u8> {
+    if settings.mode == ClaudeLayoutMode::Single {
+        return vec![active_tab];
+    }
+
+    let mut tabs: Vec<u8> = settings.visible_tabs.iter()
+        .copied()
+        .filter(|t| existing.contains(t))
+        .collect();
+    if !tabs.contains(&active_tab) {
+        tabs.push(active_tab);
+    }
+    tabs.sort_unstable();
+    tabs.dedup();
+    tabs
+}
+
+/// Путь к файлу настроек раскладки
+fn get_layout_settings_path() -> Option<PathBuf> {
+    get_app_data_dir().map(|dir| dir.join("claude_layout.json"))
+}
+
+/// Загружает настройки раскладки (или значения по умолчанию)
+pub fn load_layout_settings() -> LayoutSettings {
+    get_layout_settings_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Сохраняет настройки раскладки
+pub fn save_layout_settings(settings: &LayoutSettings) -> Result<(), String> {
+    let path = get_layout_settings_path().ok_or("Cannot get app data dir")?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area() -> PanelArea {
+        PanelArea { x: 600.0, y: 0.0, width: 900.0, height: 800.0 }
+    }
+
+    #[test]
+    fn test_single_uses_whole_area() {
+        let tiles = compute_tiles(&area(), ClaudeLayoutMode::Single, 3);
+        assert_eq!(tiles, vec![area()]);
+    }
+
+    #[test]
+    fn test_columns_split_width() {
+        let tiles = compute_tiles(&area(), ClaudeLayoutMode::Columns, 3);
+        assert_eq!(tiles.len(), 3);
+        assert_eq!(tiles[0].x, 600.0);
+        assert_eq!(tiles[1].x, 900.0);
+        assert_eq!(tiles[2].width, 300.0);
+        assert!(tiles.iter().all(|t| t.height == 800.0));
+    }
+
+    #[test]
+    fn test_grid_stretches_last_row() {
+        let tiles = compute_tiles(&area(), ClaudeLayoutMode::Grid, 3);
+        assert_eq!(tiles.len(), 3);
+        assert_eq!(tiles[0].width, 450.0);
+        assert_eq!(tiles[1].x, 1050.0);
+        assert_eq!(tiles[2].y, 400.0);
+        assert_eq!(tiles[2].width, 900.0);
+    }
+
+    #[test]
+    fn test_toolbar_and_downloads_follow_tile() {
+        let tiles = compute_tiles(&area(), ClaudeLayoutMode::Grid, 3);
+
+        // Toolbar по центру каждой плитки, у её нижнего края
+        assert_eq!(toolbar_position(&tiles[0]), (749.0, 339.0));
+        assert_eq!(toolbar_position(&tiles[1]), (1199.0, 339.0));
+        assert_eq!(toolbar_position(&tiles[2]), (974.0, 739.0));
+
+        // Popup загрузок — над toolbar той же плитки
+        assert_eq!(downloads_position(&tiles[2]), (890.0, 371.0));
+    }
+
+    #[test]
+    fn test_visible_tabs_always_include_active() {
+        let settings = LayoutSettings { mode: ClaudeLayoutMode::Columns, visible_tabs: vec![1, 2] };
+        assert_eq!(visible_tabs(&settings, 3, &[1, 2, 3]), vec![1, 2, 3]);
+        assert_eq!(visible_tabs(&settings, 1, &[1, 3]), vec![1]);
+
+        let single = LayoutSettings { mode: ClaudeLayoutMode::Single, visible_tabs: vec![1, 2, 3] };
+        assert_eq!(visible_tabs(&single, 2, &[1, 2, 3]), vec![2]);
+    }
+}
@@ -4,29 +4,115 @@
 //! - Создания Claude webview с обработчиками событий
 //! - Создания toolbar и управления z-order
 //! - Изменения размеров и позиций webview
+//! - Выноса панели Claude в отдельное окно (detached режим)
 
 use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::Ordering;
 use tauri::{
     AppHandle, Emitter, Manager,
-    WebviewBuilder, WebviewUrl,
+    WebviewBuilder, WebviewUrl, Window, WindowBuilder,
     LogicalPosition, LogicalSize,
 };
 
 use crate::state::{
-    CLAUDE_VISIBLE, ACTIVE_TAB, PANEL_RATIO,
-    WEBVIEW_CREATION_LOCK, TOOLBAR_CREATION_LOCK, DOWNLOADS_LOG_LOCK,
-    UPLOAD_COUNTERS,
+    CLAUDE_VISIBLE, CLAUDE_DETACHED, ACTIVE_TAB, PANEL_RATIO, CLAUDE_LAYOUT,
+    WEBVIEW_CREATION_LOCK, TOOLBAR_CREATION_LOCK,
+    UPLOAD_COUNTERS, CHAT_TITLES,
 };
-use crate::types::DownloadEntry;
-use crate::utils::get_dimensions;
+use crate::types::{DiagnosticEvent, DownloadDuplicates, DownloadEntry, DownloadsSettings, FailedDownload};
+use crate::utils::{get_dimensions, get_window_dimensions};
 use crate::utils::dimensions::sizes;
 use crate::downloads::paths::{
     get_custom_downloads_path, 
     get_unique_filepath,
-    get_downloads_log_path,
+    load_downloads_settings,
 };
+use crate::downloads::archive::{archive_folder_name, extract_archive, is_claude_archive};
+use crate::downloads::routing::{download_context, resolve_destination};
+use crate::downloads::dedup::{find_identical, hash_file};
+use crate::downloads::tracker::{finish_tracked_download, record_failed_download, record_failed_processing, start_download};
 use crate::webview::scripts::get_claude_init_script;
+use crate::webview::layout::{PanelArea, compute_tiles, toolbar_position, visible_tabs};
+use crate::webview::selectors::active_selectors_json;
+use crate::webview::plugins::inject_plugins;
+use crate::commands::autocontinue::push_auto_continue_config;
+use crate::commands::logs::{append_download_entry, write_diagnostic};
+use crate::commands::projects::project_name_for_url;
+use crate::utils::metrics;
+use crate::utils::crash::track_webview;
+
+/// Label отдельного окна Claude (detached режим)
+pub const CLAUDE_WINDOW_LABEL: &str = "claude_window";
+
+/// Webview, которые переезжают между главным окном и окном Claude
+const CLAUDE_PANEL_WEBVIEWS: [&str; 7] = [
+    "claude_1", "claude_2", "claude_3", "toolbar_1", "toolbar_2", "toolbar_3", "downloads",
+];
+
+/// Label toolbar таба (у каждого видимого таба — свой toolbar в его плитке)
+pub fn toolbar_label(tab: u8) -> String {
+    format!("toolbar_{}", tab)
+}
+
+/// Возвращает окно, в котором сейчас живут Claude webview и toolbar
+///
+/// В обычном режиме это главное окно, в detached — `claude_window`.
+pub fn claude_host_window(app: &AppHandle) -> Result<Window, String> {
+    if CLAUDE_DETACHED.load(Ordering::SeqCst) {
+        if let Some(window) = app.get_window(CLAUDE_WINDOW_LABEL) {
+            return Ok(window);
+        }
+    }
+    app.get_window("main").ok_or_else(|| "Window not found".to_string())
+}
+
+/// Вычисляет область панели Claude
+///
+/// * В главном окне — правая часть по `PANEL_RATIO`
+/// * В отдельном окне — всё окно целиком
+pub fn claude_panel_area(app: &AppHandle) -> Result<PanelArea, String> {
+    if CLAUDE_DETACHED.load(Ordering::SeqCst) {
+        if let Some(window) = app.get_window(CLAUDE_WINDOW_LABEL) {
+            let (width, height, _) = get_window_dimensions(&window)?;
+            return Ok(PanelArea { x: 0.0, y: 0.0, width, height });
+        }
+    }
+    
+    let (width, height, _) = get_dimensions(app)?;
+    let ratio = PANEL_RATIO.load(Ordering::SeqCst) as f64 / 100.0;
+    let x = width * ratio;
+    Ok(PanelArea { x, y: 0.0, width: width - x, height })
+}
+
+/// Раскладывает видимые табы по плиткам панели
+///
+/// Возвращает пары (таб, плитка). В режиме single — только активный таб
+/// на всю панель; в columns/grid — все видимые табы.
+fn claude_tiles(app: &AppHandle, area: &PanelArea, active_tab: u8) -> Vec<(u8, PanelArea)> {
+    let existing: Vec<u8> = (1u8..=3)
+        .filter(|i| app.get_webview(&format!("claude_{}", i)).is_some())
+        .collect();
+    
+    let settings = CLAUDE_LAYOUT.lock()
+        .map(|s| s.clone())
+        .unwrap_or_default();
+    
+    let tabs = visible_tabs(&settings, active_tab, &existing);
+    let tiles = compute_tiles(area, settings.mode, tabs.len());
+    tabs.into_iter().zip(tiles).collect()
+}
+
+/// Плитка таба (если таб не виден — вся панель); по ней позиционируется popup загрузок
+pub fn tab_tile_area(app: &AppHandle, tab: u8) -> Result<PanelArea, String> {
+    let area = claude_panel_area(app)?;
+    let active_tab = ACTIVE_TAB.load(Ordering::SeqCst);
+    Ok(claude_tiles(app, &area, active_tab)
+        .into_iter()
+        .find(|(t, _)| *t == tab)
+        .map(|(_, tile)| tile)
+        .unwrap_or(area))
+}
 
 /// Создаёт Claude webview если он ещё не существует
 ///
@@ -45,12 +131,9 @@ pub fn ensure_claude_webview(app: &AppHandle, tab: u8, url: Option<&str>) -> Res
     let created = create_claude_webview(app, tab, url)?;
     
     if created {
-        // Поднимаем z-order toolbar поверх нового Claude webview
-        if app.get_webview("toolbar").is_some() {
-            raise_toolbar_zorder(app);
-        } else {
-            ensure_toolbar(app)?;
-        }
+        // Toolbar нового таба + z-order остальных toolbar поверх нового Claude webview
+        ensure_toolbar(app)?;
+        raise_toolbar_zorder(app);
     }
     
     Ok(())
@@ -72,11 +155,8 @@ pub fn create_claude_webview(app: &AppHandle, tab: u8, url: Option<&str>) -> Res
         return Ok(false);
     }
     
-    let window = app.get_window("main").ok_or("Window not found")?;
-    let (width, height, _) = get_dimensions(app)?;
-    let ratio = PANEL_RATIO.load(Ordering::SeqCst) as f64 / 100.0;
-    let ui_width = width * ratio;
-    let claude_width = width - ui_width;
+    let window = claude_host_window(app)?;
+    let area = claude_panel_area(app)?;
     
     let target_url = url.unwrap_or("https://claude.ai/new");
     
@@ -90,17 +170,36 @@ pub fn create_claude_webview(app: &AppHandle, tab: u8, url: Option<&str>) -> Res
     
     // Скрипт который выполняется при каждой загрузке страницы
     let init_script = get_claude_init_script(tab);
+    // Селекторы, вшитые в init script (могут устареть после update_selectors)
+    let init_selectors = active_selectors_json();
     
     window.add_child(
         WebviewBuilder::new(&label, WebviewUrl::External(url_parsed))
             .initialization_script(&init_script)
             .disable_drag_drop_handler()
-            .on_page_load(move |_webview, payload| {
+            .on_page_load(move |webview, payload| {
                 use tauri::webview::PageLoadEvent;
+                if payload.event() == PageLoadEvent::Started {
+                    metrics::navigation_started(tab_for_page);
+                }
                 if payload.event() == PageLoadEvent::Finished {
                     let url = payload.url().to_string();
                     // Не эмитим событие для about:blank и других не-Claude страниц
                     if url.starts_with("https://claude.ai") {
+                        metrics::navigation_finished(tab_for_page, true);
+
+                        // Init script несёт селекторы на момент создания webview
+                        let selectors = active_selectors_json();
+                        if selectors != init_selectors {
+                            let _ = webview.eval(format!("window._s = {};", selectors));
+                        }
+                        
+                        // Настройки Auto-Continue из Rust
+                        push_auto_continue_config(&webview, tab_for_page);
+                        
+                        // Пользовательские плагины — после встроенных скриптов
+                        inject_plugins(&webview, tab_for_page);
+
                         let _ = app_handle_page.emit("claude-page-loaded", serde_json::json!({
                             "tab": tab_for_page,
                             "url": url
@@ -108,15 +207,26 @@ pub fn create_claude_webview(app: &AppHandle, tab: u8, url: Option<&str>) -> Res
                     }
                 }
             })
+            .on_document_title_changed(move |_webview, title| {
+                // Заголовок чата для правил загрузок ("Название - Claude")
+                let title = title.strip_suffix(" - Claude").unwrap_or(&title);
+                let title = if title == "Claude" { "" } else { title };
+                if let Ok(mut titles) = CHAT_TITLES.lock() {
+                    if let Some(slot) = titles.get_mut((tab as usize).wrapping_sub(1)) {
+                        *slot = title.trim().to_string();
+                    }
+                }
+            })
             .on_download(move |webview, event| {
                 handle_download_event(&app_handle, &webview, event, tab)
             }),
-        LogicalPosition::new(width * 2.0, 0.0),
-        LogicalSize::new(claude_width, height),
+        LogicalPosition::new(area.offscreen_x(), 0.0),
+        LogicalSize::new(area.width, area.height),
     ).map_err(|e| e.to_string())?;
+    track_webview(&label);
     
     // Скрываем при создании — layout_claude покажет через show() + позицию
-    // Создаём за экраном (width*2) чтобы избежать мелькания до hide()
+    // Создаём за экраном (offscreen_x) чтобы избежать мелькания до hide()
     if let Some(webview) = app.get_webview(&label) {
         let _ = webview.hide();
     }
@@ -124,6 +234,9 @@ pub fn create_claude_webview(app: &AppHandle, tab: u8, url: Option<&str>) -> Res
     // Регистрируем нативный перехватчик загрузок (Windows: WebResourceRequested)
     setup_native_upload_interceptor(app, tab);
     
+    // Прогресс и причины ошибок загрузок (Windows: DownloadStarting)
+    setup_native_download_tracker(app, tab);
+    
     Ok(true)
 }
 
@@ -182,6 +295,146 @@ fn setup_native_upload_interceptor(app: &AppHandle, tab: u8) {
     }
 }
 
+/// Регистрирует нативное отслеживание загрузок
+///
+/// На Windows: WebView2 `DownloadStarting` подписывается на `BytesReceivedChanged`
+/// (прогресс, событие `download-progress`) и `StateChanged` (причина прерывания).
+/// На других платформах: no-op — известны только начало и итог загрузки.
+fn setup_native_download_tracker(app: &AppHandle, tab: u8) {
+    #[cfg(windows)]
+    {
+        let label = format!("claude_{}", tab);
+        let app_handle = app.clone();
+        
+        if let Some(webview) = app.get_webview(&label) {
+            let _ = webview.with_webview(move |wv| {
+                unsafe {
+                    use windows_core::Interface;
+                    use webview2_com::Microsoft::Web::WebView2::Win32::*;
+                    use webview2_com::{
+                        BytesReceivedChangedEventHandler, DownloadStartingEventHandler,
+                        StateChangedEventHandler,
+                    };
+                    use crate::downloads::tracker::{record_interrupt_reason, update_download_progress};
+                    
+                    let core: ICoreWebView2 = wv.controller().CoreWebView2().unwrap();
+                    let Ok(core4) = core.cast::<ICoreWebView2_4>() else { return };
+                    
+                    let handler = DownloadStartingEventHandler::create(Box::new(
+                        move |_sender, args: Option<ICoreWebView2DownloadStartingEventArgs>| {
+                            let Some(args) = args else { return Ok(()) };
+                            let operation = args.DownloadOperation()?;
+                            
+                            let mut uri = windows_core::PWSTR::null();
+                            operation.Uri(&mut uri)?;
+                            let url = webview2_com::take_pwstr(uri);
+                            
+                            // Прогресс: не чаще раза в 250 мс на загрузку
+                            let app = app_handle.clone();
+                            let progress_url = url.clone();
+                            let mut last_emit: Option<std::time::Instant> = None;
+                            let progress = BytesReceivedChangedEventHandler::create(Box::new(
+                                move |operation: Option<ICoreWebView2DownloadOperation>, _| {
+                                    let Some(operation) = operation else { return Ok(()) };
+                                    let (mut received, mut total) = (0i64, 0i64);
+                                    operation.BytesReceived(&mut received)?;
+                                    operation.TotalBytesToReceive(&mut total)?;
+                                    
+                                    let total = (total > 0).then_some(total as u64);
+                                    let Some(download) = update_download_progress(
+                                        &progress_url, received.max(0) as u64, total,
+                                    ) else { return Ok(()) };
+                                    
+                                    let now = std::time::Instant::now();
+                                    if last_emit.is_some_and(|t| now.duration_since(t).as_millis() < 250) {
+                                        return Ok(());
+                                    }
+                                    last_emit = Some(now);
+                                    let _ = app.emit("download-progress", serde_json::json!({
+                                        "id": download.id,
+                                        "tab": download.tab,
+                                        "filename": download.filename,
+                                        "received_bytes": download.received_bytes,
+                                        "total_bytes": download.total_bytes,
+                                    }));
+                                    Ok(())
+                                }
+                            ));
+                            let mut token: i64 = 0;
+                            let _ = operation.add_BytesReceivedChanged(
+                                &progress,
+                                &mut token as *mut i64 as *mut _
+                            );
+                            
+                            // Причина прерывания
+                            let state_url = url;
+                            let state = StateChangedEventHandler::create(Box::new(
+                                move |operation: Option<ICoreWebView2DownloadOperation>, _| {
+                                    let Some(operation) = operation else { return Ok(()) };
+                                    let mut state = COREWEBVIEW2_DOWNLOAD_STATE_IN_PROGRESS;
+                                    operation.State(&mut state)?;
+                                    if state == COREWEBVIEW2_DOWNLOAD_STATE_INTERRUPTED {
+                                        let mut reason = COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_NONE;
+                                        operation.InterruptReason(&mut reason)?;
+                                        record_interrupt_reason(&state_url, interrupt_reason_name(reason));
+                                    }
+                                    Ok(())
+                                }
+                            ));
+                            let mut token: i64 = 0;
+                            let _ = operation.add_StateChanged(
+                                &state,
+                                &mut token as *mut i64 as *mut _
+                            );
+                            Ok(())
+                        }
+                    ));
+                    
+                    let mut token: i64 = 0;
+                    let _ = core4.add_DownloadStarting(
+                        &handler,
+                        &mut token as *mut i64 as *mut _
+                    );
+                }
+            });
+        }
+    }
+    
+    #[cfg(not(windows))]
+    {
+        let _ = (app, tab);
+    }
+}
+
+/// Имя причины прерывания WebView2 (snake_case)
+#[cfg(windows)]
+fn interrupt_reason_name(
+    reason: webview2_com::Microsoft::Web::WebView2::Win32::COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON,
+) -> &'static str {
+    use webview2_com::Microsoft::Web::WebView2::Win32::*;
+    
+    match reason {
+        COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_FILE_FAILED => "file_failed",
+        COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_FILE_ACCESS_DENIED => "file_access_denied",
+        COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_FILE_NO_SPACE => "file_no_space",
+        COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_FILE_NAME_TOO_LONG => "file_name_too_long",
+        COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_FILE_TOO_LARGE => "file_too_large",
+        COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_FILE_TRANSIENT_ERROR => "file_transient_error",
+        COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_NETWORK_FAILED => "network_failed",
+        COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_NETWORK_TIMEOUT => "network_timeout",
+        COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_NETWORK_DISCONNECTED => "network_disconnected",
+        COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_NETWORK_SERVER_DOWN => "network_server_down",
+        COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_SERVER_FAILED => "server_failed",
+        COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_SERVER_BAD_CONTENT => "server_bad_content",
+        COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_SERVER_UNAUTHORIZED => "server_unauthorized",
+        COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_SERVER_FORBIDDEN => "server_forbidden",
+        COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_SERVER_CERTIFICATE_PROBLEM => "server_certificate_problem",
+        COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_USER_CANCELED => "user_canceled",
+        COREWEBVIEW2_DOWNLOAD_INTERRUPT_REASON_USER_SHUTDOWN => "user_shutdown",
+        _ => "interrupted",
+    }
+}
+
 fn handle_download_event(
     app: &AppHandle,
     webview: &tauri::Webview,
@@ -198,11 +451,23 @@ fn handle_download_event(
             let filename = extract_filename_from_url(url_str);
             
             // Устанавливаем путь загрузки с уникальным именем
-            if let Some(custom_path) = get_custom_downloads_path() {
-                let dir = std::path::PathBuf::from(&custom_path);
-                *destination = get_unique_filepath(&dir, &filename);
-            } else if let Some(dir) = destination.parent() {
-                *destination = get_unique_filepath(dir, &filename);
+            // (подпапка — по правилам из downloads_settings.json)
+            let base = match get_custom_downloads_path() {
+                Some(custom_path) => Some(PathBuf::from(custom_path)),
+                None => destination.parent().map(Path::to_path_buf),
+            };
+            if let Some(base) = base {
+                let claude_url = webview.url().map(|u| u.to_string()).unwrap_or_default();
+                let rules = load_downloads_settings().rules;
+                let (target, _) = resolve_destination(&base, &rules, &download_context(tab, &claude_url), &filename);
+                
+                let dir = target.parent()
+                    .filter(|dir| fs::create_dir_all(dir).is_ok())
+                    .unwrap_or(&base);
+                let name = target.file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or(&filename);
+                *destination = get_unique_filepath(dir, name);
             }
             
             // Отправляем событие с итоговым именем файла
@@ -210,36 +475,58 @@ fn handle_download_event(
                 .and_then(|n| n.to_str())
                 .unwrap_or(&filename)
                 .to_string();
+            start_download(tab, url_str, &final_filename, &destination.to_string_lossy());
             let _ = app.emit("download-started", &final_filename);
         }
         
-        DownloadEvent::Finished { url: _, path, success } => {
-            let filename = path.as_ref()
-                .and_then(|p| p.file_name())
-                .and_then(|n| n.to_str())
-                .unwrap_or("file")
-                .to_string();
-            
-            let file_path = path.as_ref()
-                .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or_default();
-            
+        DownloadEvent::Finished { url, path, success } => {
             let claude_url = webview.url()
                 .map(|u| u.to_string())
                 .unwrap_or_default();
             
-            if success {
-                // Добавляем в лог загрузок
-                save_download_to_log(&filename, &file_path);
-                
-                let _ = app.emit("download-finished", serde_json::json!({
-                    "filename": filename,
-                    "tab": tab,
-                    "url": claude_url,
-                    "file_path": file_path
-                }));
-            } else {
-                let _ = app.emit("download-failed", &filename);
+            match path {
+                Some(path) if success => {
+                    finish_tracked_download(url.as_str());
+                    
+                    // Хэширование и распаковка — вне потока обработчика загрузок
+                    let app = app.clone();
+                    let url = url.to_string();
+                    tauri::async_runtime::spawn_blocking(move || {
+                        let settings = load_downloads_settings();
+                        let is_archive = path.file_name()
+                            .and_then(|n| n.to_str())
+                            .is_some_and(is_claude_archive);
+                        
+                        if settings.extract_archives && is_archive {
+                            finish_archive_download(&app, tab, &claude_url, &url, &path, &settings);
+                        } else {
+                            finish_download(&app, tab, &claude_url, &path, None, settings.duplicates);
+                        }
+                    });
+                }
+                path => {
+                    let filename = path.as_ref()
+                        .and_then(|p| p.file_name())
+                        .and_then(|n| n.to_str())
+                        .unwrap_or("file")
+                        .to_string();
+                    
+                    // WebView2 сообщает причину прерывания после Finished —
+                    // даём нативному обработчику записать её
+                    let app = app.clone();
+                    let url = url.to_string();
+                    tauri::async_runtime::spawn(async move {
+                        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+                        
+                        let failed = record_failed_download(finish_tracked_download(&url), tab, &url, &filename);
+                        let _ = write_diagnostic(DiagnosticEvent::DownloadFailed, serde_json::json!({
+                            "tab": tab,
+                            "filename": failed.filename,
+                            "reason": failed.reason,
+                        }));
+                        emit_download_failed(&app, &failed);
+                    });
+                }
             }
         }
         
@@ -249,6 +536,17 @@ fn handle_download_event(
     true // Разрешаем загрузку
 }
 
+/// Сообщает фронтенду о неудачной загрузке
+fn emit_download_failed(app: &AppHandle, failed: &FailedDownload) {
+    let _ = app.emit("download-failed", serde_json::json!({
+        "id": failed.id,
+        "filename": failed.filename,
+        "reason": failed.reason,
+        "tab": failed.tab,
+        "url": failed.url,
+    }));
+}
+
 /// Извлекает имя файла из URL загрузки Claude
 fn extract_filename_from_url(url_str: &str) -> String {
     if url_str.contains("path=") {
@@ -270,39 +568,121 @@ fn extract_filename_from_url(url_str: &str) -> String {
     }
 }
 
-/// Сохраняет запись о загрузке в лог
-fn save_download_to_log(filename: &str, file_path: &str) {
-    if let Ok(_guard) = DOWNLOADS_LOG_LOCK.lock() {
-        if let Some(log_path) = get_downloads_log_path() {
-            let mut entries: Vec<DownloadEntry> = if log_path.exists() {
-                fs::read_to_string(&log_path)
-                    .ok()
-                    .and_then(|c| serde_json::from_str(&c).ok())
-                    .unwrap_or_default()
-            } else {
-                Vec::new()
-            };
-            
-            // Проверяем дубликаты
-            if !entries.iter().any(|e| e.file_path == file_path) {
-                entries.push(DownloadEntry {
-                    timestamp: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-                    filename: filename.to_string(),
-                    file_path: file_path.to_string(),
-                });
-                
-                // Ограничиваем размер
-                if entries.len() > 500 {
-                    entries = entries.split_off(entries.len() - 500);
-                }
-                
-                if let Ok(json) = serde_json::to_string_pretty(&entries) {
-                    if let Err(e) = fs::write(&log_path, json) {
-                        eprintln!("[Downloads] Failed to write log: {}", e);
-                    }
+/// Логирует загруженный файл и эмитит `download-finished`
+///
+/// Если в той же папке уже есть более старый файл с тем же содержимым,
+/// в режиме `drop` новый файл удаляется и запись указывает на существующий, в режиме
+/// `keep` — файл остаётся с пометкой `duplicate_of`.
+///
+/// # Arguments
+/// * `archive` - имя архива, из которого распакован файл
+fn finish_download(
+    app: &AppHandle,
+    tab: u8,
+    claude_url: &str,
+    path: &Path,
+    archive: Option<&str>,
+    duplicates: DownloadDuplicates,
+) {
+    let hash = hash_file(path).ok();
+    let identical = hash.as_deref().and_then(|hash| find_identical(path, hash));
+    
+    let (path, duplicate_of, deduplicated) = match identical {
+        Some(existing) if duplicates == DownloadDuplicates::Drop => {
+            match fs::remove_file(path) {
+                Ok(()) => (existing, None, true),
+                Err(e) => {
+                    log::warn!("Failed to remove duplicate download: {}", e);
+                    (path.to_path_buf(), Some(existing), false)
                 }
             }
         }
+        identical => (path.to_path_buf(), identical, false),
+    };
+    let duplicate_of = duplicate_of.map(|p| p.to_string_lossy().to_string());
+    
+    let filename = path.file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("file")
+        .to_string();
+    let file_path = path.to_string_lossy().to_string();
+    
+    // Добавляем в лог загрузок
+    save_download_to_log(DownloadEntry {
+        timestamp: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        filename: filename.clone(),
+        file_path: file_path.clone(),
+        tab: Some(tab),
+        project: project_name_for_url(claude_url),
+        size: fs::metadata(&path).ok().map(|m| m.len()),
+        archive: archive.map(str::to_string),
+        hash,
+        duplicate_of: duplicate_of.clone(),
+    });
+    
+    let _ = app.emit("download-finished", serde_json::json!({
+        "filename": filename,
+        "tab": tab,
+        "url": claude_url,
+        "file_path": file_path,
+        "archive": archive,
+        "duplicate_of": duplicate_of,
+        "deduplicated": deduplicated
+    }));
+}
+
+/// Распаковывает скачанный `claude_files.zip` в папку проекта или чата
+///
+/// Каждый файл логируется с именем архива и получает свой `download-finished`,
+/// архив удаляется. Если распаковать не удалось, частично распакованное
+/// удаляется, архив остаётся на диске, а загрузка записывается в неудачные
+/// (`download-failed`) вместо лога загрузок.
+fn finish_archive_download(
+    app: &AppHandle,
+    tab: u8,
+    claude_url: &str,
+    download_url: &str,
+    archive_path: &Path,
+    settings: &DownloadsSettings,
+) {
+    let archive_name = archive_path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let archive_stem = archive_path.file_stem()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    
+    let ctx = download_context(tab, claude_url);
+    let folder = archive_folder_name(settings.extract_into, ctx.project.as_deref(), ctx.chat.as_deref(), &archive_stem);
+    let dest = archive_path.parent().unwrap_or(Path::new(".")).join(folder);
+    
+    match extract_archive(archive_path, &dest) {
+        Ok(files) => {
+            if let Err(e) = fs::remove_file(archive_path) {
+                log::warn!("Failed to remove extracted archive: {}", e);
+            }
+            for file in &files {
+                finish_download(app, tab, claude_url, file, Some(&archive_name), settings.duplicates);
+            }
+        }
+        Err(e) => {
+            let _ = write_diagnostic(
+                DiagnosticEvent::ArchiveExtractError,
+                serde_json::json!({ "tab": tab, "archive": archive_name, "error": e }),
+            );
+            let failed = record_failed_processing(tab, download_url, &archive_name, format!("extract: {}", e));
+            emit_download_failed(app, &failed);
+        }
+    }
+}
+
+/// Сохраняет запись о загрузке в лог
+///
+/// Запись для уже залогированного пути (дубль, указывающий на существующий
+/// файл) переносится в конец с новым временем.
+fn save_download_to_log(entry: DownloadEntry) {
+    if let Err(e) = append_download_entry(&entry) {
+        log::error!("Failed to write downloads log: {}", e);
     }
 }
 
@@ -328,8 +708,9 @@ pub fn raise_toolbar_zorder(app: &AppHandle) {
             SetWindowPos, SWP_NOMOVE, SWP_NOSIZE, SWP_NOACTIVATE, HWND_TOP,
         };
         
-        for label in &["toolbar", "downloads"] {
-            if let Some(webview) = app.get_webview(label) {
+        let labels = (1u8..=3).map(toolbar_label).chain(std::iter::once("downloads".to_string()));
+        for label in labels {
+            if let Some(webview) = app.get_webview(&label) {
                 let _ = webview.with_webview(move |wv| {
                     unsafe {
                         let mut hwnd = std::mem::zeroed();
@@ -354,28 +735,35 @@ pub fn raise_toolbar_zorder(app: &AppHandle) {
     }
 }
 
-/// Создаёт webview тулбара если он ещё не существует
+/// Создаёт toolbar для каждого существующего Claude таба и popup загрузок
 ///
 /// Вызывается ПОСЛЕ создания claude webview чтобы быть поверх.
+/// Toolbar таба получает `window.__TOOLBAR_TAB__` — кнопки действуют на свой таб.
 /// Потокобезопасная — использует мьютекс.
 pub fn ensure_toolbar(app: &AppHandle) -> Result<(), String> {
     // Блокируем для предотвращения race condition
     let _guard = TOOLBAR_CREATION_LOCK.lock()
         .map_err(|_| "Toolbar creation lock poisoned")?;
     
-    let window = app.get_window("main").ok_or("Main window not found")?;
+    let window = claude_host_window(app)?;
     
-    // Создаём toolbar если его нет
-    if app.get_webview("toolbar").is_none() {
+    // Создаём toolbar для табов, у которых его нет
+    for tab in 1u8..=3 {
+        let label = toolbar_label(tab);
+        if app.get_webview(&format!("claude_{}", tab)).is_none() || app.get_webview(&label).is_some() {
+            continue;
+        }
         window.add_child(
-            WebviewBuilder::new("toolbar", WebviewUrl::App("toolbar.html".into()))
+            WebviewBuilder::new(&label, WebviewUrl::App("toolbar.html".into()))
+                .initialization_script(format!("window.__TOOLBAR_TAB__ = {};", tab))
                 .transparent(true)
                 .background_color(tauri::webview::Color(0, 0, 0, 0)),
             LogicalPosition::new(0.0, 0.0),
             LogicalSize::new(sizes::TOOLBAR_WIDTH, sizes::TOOLBAR_HEIGHT),
         ).map_err(|e| e.to_string())?;
+        track_webview(&label);
         // Скрываем сразу — resize_webviews покажет когда нужно
-        if let Some(toolbar) = app.get_webview("toolbar") {
+        if let Some(toolbar) = app.get_webview(&label) {
             let _ = toolbar.hide();
         }
     }
@@ -389,6 +777,7 @@ pub fn ensure_toolbar(app: &AppHandle) -> Result<(), String> {
             LogicalPosition::new(0.0, 0.0),
             LogicalSize::new(sizes::DOWNLOADS_WIDTH, sizes::DOWNLOADS_HEIGHT),
         ).map_err(|e| e.to_string())?;
+        track_webview("downloads");
         // Скрываем сразу — show_downloads покажет когда нужно
         if let Some(downloads) = app.get_webview("downloads") {
             let _ = downloads.hide();
@@ -413,38 +802,24 @@ fn layout_ui(app: &AppHandle, width: f64, height: f64,
     Ok(())
 }
 
-/// Обновляет layout Claude табов (показывает активный, скрывает остальные)
-fn layout_claude(app: &AppHandle, width: f64, height: f64,
-                 is_visible: bool, active_tab: u8, ratio: f64) -> Result<(), String> {
-    if is_visible {
-        let claude_x = width * ratio;
-        let claude_width = width - claude_x;
-        
-        for i in 1u8..=3 {
-            let label = format!("claude_{}", i);
-            if let Some(webview) = app.get_webview(&label) {
-                // show() для всех — валидный HWND + IsVisible=TRUE + DOM живой
-                let _ = webview.show();
-                
-                if i == active_tab {
-                    webview.set_position(LogicalPosition::new(claude_x, 0.0))
-                        .map_err(|e| e.to_string())?;
-                    webview.set_size(LogicalSize::new(claude_width, height))
-                        .map_err(|e| e.to_string())?;
-                } else {
-                    // За экран — IsVisible=TRUE, DOM живой
-                    webview.set_position(LogicalPosition::new(width * 2.0, 0.0))
-                        .map_err(|e| e.to_string())?;
-                }
-            }
-        }
-    } else {
-        // Панель скрыта — show() + за экран (DOM живой для фоновой генерации)
-        for i in 1u8..=3 {
-            let label = format!("claude_{}", i);
-            if let Some(webview) = app.get_webview(&label) {
-                let _ = webview.show();
-                webview.set_position(LogicalPosition::new(width * 2.0, 0.0))
+/// Обновляет layout Claude табов (показывает видимые по плиткам, скрывает остальные)
+fn layout_claude(app: &AppHandle, area: &PanelArea, tiles: &[(u8, PanelArea)],
+                 is_visible: bool) -> Result<(), String> {
+    for i in 1u8..=3 {
+        let label = format!("claude_{}", i);
+        if let Some(webview) = app.get_webview(&label) {
+            // show() для всех — валидный HWND + IsVisible=TRUE + DOM живой
+            let _ = webview.show();
+            
+            let tile = tiles.iter().find(|(tab, _)| *tab == i).map(|(_, tile)| tile);
+            if let (true, Some(tile)) = (is_visible, tile) {
+                webview.set_position(LogicalPosition::new(tile.x, tile.y))
+                    .map_err(|e| e.to_string())?;
+                webview.set_size(LogicalSize::new(tile.width, tile.height))
+                    .map_err(|e| e.to_string())?;
+            } else {
+                // За экран — IsVisible=TRUE, DOM живой (фоновая генерация)
+                webview.set_position(LogicalPosition::new(area.offscreen_x(), 0.0))
                     .map_err(|e| e.to_string())?;
             }
         }
@@ -454,25 +829,28 @@ fn layout_claude(app: &AppHandle, width: f64, height: f64,
 }
 
 /// Обновляет layout overlay-элементов (toolbar, downloads)
-fn layout_overlay(app: &AppHandle, width: f64, height: f64,
-                  is_visible: bool, ratio: f64) -> Result<(), String> {
-    if is_visible {
-        let claude_x = width * ratio;
-        let claude_width = width - claude_x;
-        
-        if let Some(toolbar) = app.get_webview("toolbar") {
-            let toolbar_x = claude_x + (claude_width - sizes::TOOLBAR_WIDTH) / 2.0;
-            let toolbar_y = height - sizes::TOOLBAR_HEIGHT - sizes::TOOLBAR_BOTTOM_OFFSET;
-            toolbar.set_position(LogicalPosition::new(toolbar_x, toolbar_y))
-                .map_err(|e| e.to_string())?;
-            toolbar.set_size(LogicalSize::new(sizes::TOOLBAR_WIDTH, sizes::TOOLBAR_HEIGHT))
-                .map_err(|e| e.to_string())?;
-            let _ = toolbar.show();
-        }
-    } else {
-        if let Some(toolbar) = app.get_webview("toolbar") {
-            let _ = toolbar.hide();
+///
+/// У каждого видимого таба свой toolbar в его плитке; toolbar скрытых табов скрыты.
+fn layout_overlay(app: &AppHandle, tiles: &[(u8, PanelArea)], is_visible: bool) -> Result<(), String> {
+    for tab in 1u8..=3 {
+        let Some(toolbar) = app.get_webview(&toolbar_label(tab)) else { continue };
+        let tile = tiles.iter().find(|(t, _)| *t == tab).map(|(_, tile)| tile);
+        match (is_visible, tile) {
+            (true, Some(tile)) => {
+                let (x, y) = toolbar_position(tile);
+                toolbar.set_position(LogicalPosition::new(x, y))
+                    .map_err(|e| e.to_string())?;
+                toolbar.set_size(LogicalSize::new(sizes::TOOLBAR_WIDTH, sizes::TOOLBAR_HEIGHT))
+                    .map_err(|e| e.to_string())?;
+                let _ = toolbar.show();
+            }
+            _ => {
+                let _ = toolbar.hide();
+            }
         }
+    }
+    
+    if !is_visible {
         if let Some(downloads) = app.get_webview("downloads") {
             let _ = downloads.hide();
         }
@@ -481,23 +859,138 @@ fn layout_overlay(app: &AppHandle, width: f64, height: f64,
     Ok(())
 }
 
+/// Показывает или сворачивает отдельное окно Claude (detached режим)
+///
+/// Скрытая панель = свёрнутое окно (а не hide()), чтобы webview
+/// оставались живыми для фоновой генерации. Вызывается явно из
+/// toggle/switch, а не из resize — иначе ручное сворачивание окна
+/// пользователем тут же отменялось бы.
+pub fn set_claude_window_visible(app: &AppHandle, is_visible: bool) {
+    if let Some(window) = app.get_window(CLAUDE_WINDOW_LABEL) {
+        if is_visible {
+            let _ = window.unminimize();
+            let _ = window.show();
+            let _ = window.set_focus();
+        } else {
+            let _ = window.minimize();
+        }
+    }
+}
+
 /// Обновляет размеры и позиции всех webview
 ///
 /// Вызывается при:
-/// - Изменении размера окна
+/// - Изменении размера окна (главного или окна Claude)
 /// - Переключении видимости Claude
 /// - Изменении соотношения панелей
 /// - Переключении табов
+/// - Переключении режима панели (в окне / отдельно)
 pub fn resize_webviews(app: &AppHandle) -> Result<(), String> {
     let (width, height, _) = get_dimensions(app)?;
     let is_visible = CLAUDE_VISIBLE.load(Ordering::SeqCst);
+    let is_detached = CLAUDE_DETACHED.load(Ordering::SeqCst);
     let active_tab = ACTIVE_TAB.load(Ordering::SeqCst);
     let ratio = PANEL_RATIO.load(Ordering::SeqCst) as f64 / 100.0;
     
-    layout_ui(app, width, height, is_visible, ratio)?;
-    layout_claude(app, width, height, is_visible, active_tab, ratio)?;
-    layout_overlay(app, width, height, is_visible, ratio)?;
+    // В detached режиме UI занимает всё главное окно
+    layout_ui(app, width, height, is_visible && !is_detached, ratio)?;
+    
+    let area = claude_panel_area(app)?;
+    let tiles = claude_tiles(app, &area, active_tab);
+    
+    layout_claude(app, &area, &tiles, is_visible)?;
+    layout_overlay(app, &tiles, is_visible)?;
+    
+    Ok(())
+}
+
+/// Выносит панель Claude в отдельное окно
+///
+/// Создаёт окно `claude_window` (если его нет) и переносит в него
+/// Claude табы, toolbar и downloads через `reparent`. Закрытие окна
+/// пользователем возвращает панель обратно в главное окно.
+pub fn detach_claude_panel(app: &AppHandle) -> Result<(), String> {
+    if CLAUDE_DETACHED.load(Ordering::SeqCst) {
+        return Ok(());
+    }
+    
+    let window = match app.get_window(CLAUDE_WINDOW_LABEL) {
+        Some(window) => window,
+        None => create_claude_window(app)?,
+    };
+    
+    reparent_claude_panel(app, &window)?;
+    CLAUDE_DETACHED.store(true, Ordering::SeqCst);
+    CLAUDE_VISIBLE.store(true, Ordering::SeqCst);
+    
+    set_claude_window_visible(app, true);
+    raise_toolbar_zorder(app);
+    resize_webviews(app)?;
+    
+    let _ = app.emit("claude-panel-mode-changed", serde_json::json!({ "detached": true }));
+    Ok(())
+}
+
+/// Возвращает панель Claude в главное окно и закрывает окно Claude
+pub fn attach_claude_panel(app: &AppHandle) -> Result<(), String> {
+    if !CLAUDE_DETACHED.load(Ordering::SeqCst) {
+        return Ok(());
+    }
+    
+    let main = app.get_window("main").ok_or("Main window not found")?;
+    reparent_claude_panel(app, &main)?;
+    CLAUDE_DETACHED.store(false, Ordering::SeqCst);
+    
+    if let Some(window) = app.get_window(CLAUDE_WINDOW_LABEL) {
+        let _ = window.destroy();
+    }
+    
+    raise_toolbar_zorder(app);
+    resize_webviews(app)?;
+    
+    let _ = app.emit("claude-panel-mode-changed", serde_json::json!({ "detached": false }));
+    Ok(())
+}
+
+/// Создаёт отдельное окно для панели Claude со своим layout pass
+fn create_claude_window(app: &AppHandle) -> Result<Window, String> {
+    let window = WindowBuilder::new(app, CLAUDE_WINDOW_LABEL)
+        .title("Claude — AI Prompts Manager")
+        .inner_size(900.0, 800.0)
+        .min_inner_size(500.0, 400.0)
+        .build()
+        .map_err(|e| e.to_string())?;
+    
+    crate::utils::set_window_icon_from_exe(&window);
+    
+    let app_handle = app.clone();
+    window.on_window_event(move |event| match event {
+        tauri::WindowEvent::Resized(_) => {
+            let _ = resize_webviews(&app_handle);
+        }
+        tauri::WindowEvent::CloseRequested { api, .. } => {
+            // Закрытие окна = вернуть панель в главное окно (табы не теряются)
+            api.prevent_close();
+            let app_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = attach_claude_panel(&app_handle) {
+                    log::error!("Failed to attach Claude panel: {}", e);
+                }
+            });
+        }
+        _ => {}
+    });
     
+    Ok(window)
+}
+
+/// Переносит все webview панели Claude в указанное окно
+fn reparent_claude_panel(app: &AppHandle, window: &Window) -> Result<(), String> {
+    for label in CLAUDE_PANEL_WEBVIEWS {
+        if let Some(webview) = app.get_webview(label) {
+            webview.reparent(window).map_err(|e| format!("Failed to move {}: {}", label, e))?;
+        }
+    }
     Ok(())
 }
 
@@ -3,9 +3,15 @@
 //! Этот модуль объединяет функциональность для:
 //! - JavaScript скриптов для инжекции (`scripts`)
 //! - Управления webview (создание, resize) (`manager`)
+//! - Раскладки Claude табов внутри панели (`layout`)
+//! - Селекторов Claude.ai с override из app data (`selectors`)
+//! - Пользовательских плагинов для Claude табов (`plugins`)
 
 pub mod scripts;
 pub mod manager;
+pub mod layout;
+pub mod selectors;
+pub mod plugins;
 
 // Реэкспорт часто используемых элементов
 pub use scripts::{
@@ -24,4 +30,24 @@
     resume_claude_tab,
     resize_webviews,
     allow_claude_multiple_downloads,
+    claude_host_window,
+    claude_panel_area,
+    detach_claude_panel,
+    attach_claude_panel,
+    set_claude_window_visible,
+    tab_tile_area,
+    toolbar_label,
+    CLAUDE_WINDOW_LABEL,
+};
+
+pub use selectors::{
+    SelectorsInfo,
+    load_selectors,
+    selectors_info,
+};
+
+pub use layout::{
+    PanelArea,
+    load_layout_settings,
+    save_layout_settings,
 };
@@ -0,0 +1,377 @@
+//! Пользовательские плагины для Claude табов
+//!
+//! Плагин — папка в `{app_data}/plugins/` с манифестом `plugin.json`,
+//! JS-файлом и (опционально) CSS:
+//!
+//! ```text
+//! plugins/
+//! └── my-tweak/
+//!     ├── plugin.json   {"name": "...", "version": "1.0.0", "script": "plugin.js", "style": "plugin.css"}
+//!     ├── plugin.js
+//!     └── plugin.css
+//! ```
+//!
+//! Новые плагины выключены, включаются командой `set_plugin_enabled`
+//! (состояние — в `plugins_settings.json`). Каждый плагин инжектится
+//! отдельным eval после загрузки страницы: исходник передаётся JSON-строкой
+//! и запускается через `new Function(src)()` внутри try-блока, поэтому и
+//! синтаксическая, и runtime ошибка одного плагина ловятся и не ломают
+//! встроенные скрипты и соседей. Ошибки загрузки и выполнения пишутся в
+//! диагностику как `plugin_error`.
+//!
+//! `id` из манифеста должен быть уникальным: плагин с уже занятым id
+//! не загружается (ошибка загрузки в списке плагинов).
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::Webview;
+
+use crate::commands::logs::write_diagnostic;
+use crate::types::DiagnosticEvent;
+use crate::downloads::paths::get_app_data_dir;
+
+/// Манифест плагина (`plugin.json`)
+#[derive(Deserialize, Debug)]
+struct PluginManifest {
+    /// Идентификатор (по умолчанию — имя папки)
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    /// JS-файл относительно папки плагина
+    #[serde(default = "default_script")]
+    script: String,
+    /// CSS-файл относительно папки плагина
+    #[serde(default)]
+    style: Option<String>,
+}
+
+fn default_script() -> String { "plugin.js".to_string() }
+
+/// Информация о плагине (для UI)
+#[derive(Serialize, Clone, Debug)]
+pub struct PluginInfo {
+    pub id: String,
+    pub name: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub enabled: bool,
+    pub has_style: bool,
+    pub path: String,
+    /// Ошибка загрузки (плагин не инжектится)
+    pub error: Option<String>,
+}
+
+/// Загруженный плагин
+struct LoadedPlugin {
+    info: PluginInfo,
+    script: String,
+    style: Option<String>,
+}
+
+/// Кэш плагинов (перечитывается командой reload_plugins)
+static PLUGINS: Lazy<Mutex<Vec<LoadedPlugin>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Папка плагинов
+pub fn get_plugins_dir() -> Option<PathBuf> {
+    get_app_data_dir().map(|dir| dir.join("plugins"))
+}
+
+/// Путь к файлу включённых/выключенных плагинов
+fn get_plugins_settings_path() -> Option<PathBuf> {
+    get_app_data_dir().map(|dir| dir.join("plugins_settings.json"))
+}
+
+/// Загружает состояние плагинов (id → enabled)
+fn load_plugins_settings() -> HashMap<String, bool> {
+    get_plugins_settings_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Сохраняет состояние плагинов
+fn save_plugins_settings(settings: &HashMap<String, bool>) -> Result<(), String> {
+    let path = get_plugins_settings_path().ok_or("Cannot get app data dir")?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Читает файл плагина, не выпуская путь за пределы его папки
+fn read_plugin_file(dir: &Path, relative: &str) -> Result<String, String> {
+    let path = dir.join(relative);
+    let canonical = path.canonicalize().map_err(|e| format!("{}: {}", relative, e))?;
+    let root = dir.canonicalize().map_err(|e| e.to_string())?;
+    if !canonical.starts_with(&root) {
+        return Err(format!("{}: path outside plugin folder", relative));
+    }
+    fs::read_to_string(&canonical).map_err(|e| format!("{}: {}", relative, e))
+}
+
+/// Разбирает `plugin.json`
+fn parse_manifest(content: &str) -> Result<PluginManifest, String> {
+    serde_json::from_str::<PluginManifest>(content).map_err(|e| format!("plugin.json: {}", e))
+}
+
+/// Помечает ошибкой плагины с уже занятым id (первый по порядку папок остаётся)
+fn reject_duplicate_ids(plugins: &mut [LoadedPlugin]) {
+    let mut seen: HashMap<String, String> = HashMap::new();
+    for plugin in plugins.iter_mut() {
+        match seen.get(&plugin.info.id) {
+            Some(first_path) => {
+                plugin.info.error = Some(format!("Duplicate plugin id '{}' (already used by {})", plugin.info.id, first_path));
+                plugin.info.enabled = false;
+                plugin.script.clear();
+                plugin.style = None;
+            }
+            None => {
+                seen.insert(plugin.info.id.clone(), plugin.info.path.clone());
+            }
+        }
+    }
+}
+
+/// Загружает один плагин из папки
+fn load_plugin(dir: &Path, settings: &HashMap<String, bool>) -> LoadedPlugin {
+    let folder = dir.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let manifest = read_plugin_file(dir, "plugin.json")
+        .and_then(|content| parse_manifest(&content));
+
+    let mut info = PluginInfo {
+        id: folder.clone(),
+        name: folder,
+        version: None,
+        description: None,
+        enabled: false,
+        has_style: false,
+        path: dir.to_string_lossy().to_string(),
+        error: None,
+    };
+
+    let manifest = match manifest {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            info.error = Some(e);
+            return LoadedPlugin { info, script: String::new(), style: None };
+        }
+    };
+
+    if let Some(id) = manifest.id.filter(|id| !id.trim().is_empty()) {
+        info.id = id;
+    }
+    if let Some(name) = manifest.name {
+        info.name = name;
+    }
+    info.version = manifest.version;
+    info.description = manifest.description;
+    info.enabled = settings.get(&info.id).copied().unwrap_or(false);
+    info.has_style = manifest.style.is_some();
+
+    let script = read_plugin_file(dir, &manifest.script);
+    let style = manifest.style.as_deref().map(|s| read_plugin_file(dir, s)).transpose();
+
+    match (script, style) {
+        (Ok(script), Ok(style)) => LoadedPlugin { info, script, style },
+        (Err(e), _) | (_, Err(e)) => {
+            info.error = Some(e);
+            LoadedPlugin { info, script: String::new(), style: None }
+        }
+    }
+}
+
+/// Перечитывает папку плагинов
+///
+/// Ошибки загрузки включённых плагинов пишутся в диагностику.
+pub fn reload_plugins() -> Vec<PluginInfo> {
+    let settings = load_plugins_settings();
+
+    let mut dirs: Vec<PathBuf> = get_plugins_dir()
+        .and_then(|dir| fs::read_dir(dir).ok())
+        .map(|entries| entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.is_dir())
+            .collect())
+        .unwrap_or_default();
+    dirs.sort();
+
+    let mut plugins: Vec<LoadedPlugin> = dirs.iter()
+        .map(|dir| load_plugin(dir, &settings))
+        .collect();
+    let enabled_before: Vec<bool> = plugins.iter().map(|p| p.info.enabled).collect();
+    reject_duplicate_ids(&mut plugins);
+
+    for (plugin, enabled) in plugins.iter().zip(enabled_before) {
+        if let (true, Some(error)) = (enabled, &plugin.info.error) {
+            let _ = write_diagnostic(
+                DiagnosticEvent::PluginError,
+                serde_json::json!({
+                    "plugin": plugin.info.id,
+                    "stage": "load",
+                    "error": error,
+                }),
+            );
+        }
+    }
+
+    let infos = plugins.iter().map(|p| p.info.clone()).collect();
+    if let Ok(mut cache) = PLUGINS.lock() {
+        *cache = plugins;
+    }
+    infos
+}
+
+/// Список плагинов из кэша
+pub fn list_plugins() -> Vec<PluginInfo> {
+    PLUGINS.lock()
+        .map(|plugins| plugins.iter().map(|p| p.info.clone()).collect())
+        .unwrap_or_default()
+}
+
+/// Включает/выключает плагин
+///
+/// Применяется при следующей загрузке страницы Claude (reload таба).
+pub fn set_plugin_enabled(id: &str, enabled: bool) -> Result<PluginInfo, String> {
+    let mut cache = PLUGINS.lock().map_err(|e| e.to_string())?;
+    let plugin = cache.iter_mut()
+        .find(|p| p.info.id == id)
+        .ok_or_else(|| format!("Plugin not found: {}", id))?;
+
+    let mut settings = load_plugins_settings();
+    settings.insert(id.to_string(), enabled);
+    save_plugins_settings(&settings)?;
+
+    plugin.info.enabled = enabled;
+    Ok(plugin.info.clone())
+}
+
+/// Формирует скрипт инжекции одного плагина
+///
+/// Повторная инжекция на той же странице пропускается (`window._apmPlugins`).
+/// Исходник — JSON-строка для `new Function`: синтаксическая ошибка плагина
+/// не ломает разбор обёртки, а ловится вместе с runtime ошибками и уходит
+/// в диагностику через `write_diagnostic`.
+fn build_plugin_script(id: &str, script: &str, style: Option<&str>, tab: u8) -> String {
+    let id_json = serde_json::to_string(id).unwrap_or_default();
+    let style_block = style
+        .map(|css| format!(r#"
+                var _ps = document.createElement('style');
+                _ps.dataset.apmPlugin = {id};
+                _ps.textContent = {css};
+                document.head.appendChild(_ps);"#,
+            id = id_json,
+            css = serde_json::to_string(css).unwrap_or_default()))
+        .unwrap_or_default();
+
+    format!(r#"
+        (function() {{
+            window._apmPlugins = window._apmPlugins || {{}};
+            if (window._apmPlugins[{id}]) return;
+            window._apmPlugins[{id}] = true;
+            try {{{style}
+                new Function({script})();
+            }} catch(e) {{
+                console.error('[APM][Plugin:' + {id} + ']', e);
+                if (window._inv) {{
+                    window._inv('write_diagnostic', {{
+                        eventType: 'plugin_error',
+                        details: {{ plugin: {id}, stage: 'run', tab: {tab}, error: String(e && e.message || e) }}
+                    }});
+                }}
+            }}
+        }})();
+    "#, id = id_json, style = style_block, script = serde_json::to_string(script).unwrap_or_default(), tab = tab)
+}
+
+/// Инжектит включённые плагины в Claude webview (после загрузки страницы)
+///
+/// Каждый плагин — отдельный eval, поэтому синтаксическая ошибка
+/// в одном из них не мешает остальным.
+pub fn inject_plugins(webview: &Webview, tab: u8) {
+    let Ok(plugins) = PLUGINS.lock() else { return };
+
+    for plugin in plugins.iter().filter(|p| p.info.enabled && p.info.error.is_none()) {
+        let script = build_plugin_script(&plugin.info.id, &plugin.script, plugin.style.as_deref(), tab);
+        if let Err(e) = webview.eval(script) {
+            let _ = write_diagnostic(
+                DiagnosticEvent::PluginError,
+                serde_json::json!({
+                    "plugin": plugin.info.id,
+                    "stage": "inject",
+                    "tab": tab,
+                    "error": e.to_string(),
+                }),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_plugin(root: &Path, folder: &str, manifest: &str) -> PathBuf {
+        let dir = root.join(folder);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("plugin.json"), manifest).unwrap();
+        fs::write(dir.join("plugin.js"), "console.log('hi')").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_parse_manifest_defaults() {
+        let manifest = parse_manifest(r#"{"name": "Tweak", "style": "a.css"}"#).unwrap();
+        assert_eq!(manifest.script, "plugin.js");
+        assert_eq!(manifest.style.as_deref(), Some("a.css"));
+        assert!(manifest.id.is_none());
+        assert!(parse_manifest("{").unwrap_err().starts_with("plugin.json:"));
+    }
+
+    #[test]
+    fn test_id_fallback_and_duplicate_rejection() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = HashMap::from([("shared".to_string(), true)]);
+
+        let folder = load_plugin(&write_plugin(root.path(), "folder-id", r#"{"id": " "}"#), &settings);
+        assert_eq!(folder.info.id, "folder-id");
+
+        let mut plugins = vec![
+            load_plugin(&write_plugin(root.path(), "a", r#"{"id": "shared"}"#), &settings),
+            load_plugin(&write_plugin(root.path(), "b", r#"{"id": "shared"}"#), &settings),
+            folder,
+        ];
+        reject_duplicate_ids(&mut plugins);
+
+        assert!(plugins[0].info.error.is_none() && plugins[0].info.enabled);
+        let duplicate = &plugins[1];
+        assert!(duplicate.info.error.as_deref().unwrap().starts_with("Duplicate plugin id 'shared'"));
+        assert!(!duplicate.info.enabled && duplicate.script.is_empty());
+        assert!(plugins[2].info.error.is_none());
+    }
+
+    #[test]
+    fn test_plugin_source_is_json_string() {
+        let script = build_plugin_script("p", "var x = ;\n'quote'", None, 2);
+        assert!(script.contains(r#"new Function("var x = ;\n'quote'")();"#));
+        assert!(!script.contains("var x = ;\n"));
+    }
+}
@@ -3,6 +3,11 @@
 //! Этот модуль содержит:
 //! - Константы с JS кодом (helpers, селекторы)
 //! - Функцию генерации init script для каждого таба
+//!
+//! Селекторы в скрипты подставляются из `webview::selectors` —
+//! встроенный набор или override из app data.
+
+use crate::webview::selectors::active_selectors_json;
 
 /// Общие JS функции для инжектирования в Claude.ai
 ///
@@ -13,6 +18,13 @@
 /// - Работы с ProseMirror редактором
 pub const CLAUDE_HELPERS_JS: &str = include_str!("../../scripts/claude_helpers.js");
 
+/// Поиск редактора, вставка текста и поиск кнопки Send
+///
+/// Загружается из `scripts/claude_editor.js`; подставляется внутрь
+/// скриптов вставки (`insert_text_to_claude`) и этапов отправки
+/// (`send_to_claude`), чтобы селекторы и fallback были в одном месте.
+pub const CLAUDE_EDITOR_JS: &str = include_str!("../../scripts/claude_editor.js");
+
 /// Claude Counter — переработанный плагин для показа usage/tokens
 ///
 /// Загружается из `scripts/claude_counter.js`
@@ -28,11 +40,14 @@
 /// Без monkey-patching: поллинг DOM + button.click()
 pub const CLAUDE_AUTOCONTINUE_JS: &str = include_str!("../../scripts/claude_autocontinue.js");
 
-/// Централизованные селекторы для Claude.ai
+/// Централизованные селекторы для Claude.ai (встроенный набор)
 ///
 /// Загружается из внешнего файла `scripts/selectors.json`
 /// Это единый источник правды для всех CSS селекторов.
 /// При обновлении Claude.ai править ТОЛЬКО этот файл!
+///
+/// Без релиза набор обновляется через фид промптов (override в app data),
+/// см. `webview::selectors`.
 pub const CLAUDE_SELECTORS_JSON: &str = include_str!("../../scripts/selectors.json");
 
 /// Генерирует JavaScript код для инициализации Claude WebView
@@ -126,7 +141,7 @@ pub fn get_claude_init_script(tab: u8) -> String {
             }} catch(e) {{ console.error('[APM][AutoContinue]', e); }}
         }});
     }})();
-    "##, tab = tab, selectors = CLAUDE_SELECTORS_JSON, helpers = CLAUDE_HELPERS_JS,
+    "##, tab = tab, selectors = active_selectors_json(), helpers = CLAUDE_HELPERS_JS,
         counter_css = CLAUDE_COUNTER_CSS.replace('`', "\\`"),
         counter_js = CLAUDE_COUNTER_JS,
         autocontinue_js = CLAUDE_AUTOCONTINUE_JS)
@@ -167,5 +182,5 @@ pub fn get_generation_monitor_script() -> String {
             
             initClaudeUI();
         }})()
-    "#, selectors = CLAUDE_SELECTORS_JSON, helpers = CLAUDE_HELPERS_JS)
+    "#, selectors = active_selectors_json(), helpers = CLAUDE_HELPERS_JS)
 }
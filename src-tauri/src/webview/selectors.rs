@@ -0,0 +1,277 @@
+//! Селекторы Claude.ai с переопределением без релиза
+//!
+//! Этот модуль содержит:
+//! - Встроенный набор селекторов (`scripts/selectors.json`, fallback)
+//! - Override-файл `selectors.json` в app data (приходит из фида промптов)
+//! - Валидацию схемы и сравнение `_meta.version`
+//! - Переинжекцию селекторов в открытые Claude табы
+//!
+//! Override применяется только если его версия новее встроенной —
+//! после обновления приложения свежий встроенный набор снова побеждает.
+
+use std::cmp::Ordering;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use crate::downloads::paths::get_app_data_dir;
+use crate::webview::scripts::CLAUDE_SELECTORS_JSON;
+
+/// Источник активного набора селекторов
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectorSource {
+    /// Встроенный в бинарник `scripts/selectors.json`
+    Embedded,
+    /// `selectors.json` в app data
+    Override,
+}
+
+/// Информация об активном наборе селекторов (для `get_selectors_info`)
+#[derive(Serialize, Clone, Debug)]
+pub struct SelectorsInfo {
+    pub source: SelectorSource,
+    pub version: String,
+    /// `_meta.updated` активного набора
+    pub updated: Option<String>,
+    pub embedded_version: String,
+    /// Путь к override-файлу (если он есть на диске)
+    pub override_path: Option<String>,
+}
+
+/// Активный набор селекторов
+struct ActiveSelectors {
+    json: String,
+    version: String,
+    updated: Option<String>,
+    source: SelectorSource,
+}
+
+static ACTIVE_SELECTORS: Lazy<Mutex<ActiveSelectors>> = Lazy::new(|| {
+    let (version, updated) = parse_meta(CLAUDE_SELECTORS_JSON);
+    Mutex::new(ActiveSelectors {
+        json: CLAUDE_SELECTORS_JSON.to_string(),
+        version,
+        updated,
+        source: SelectorSource::Embedded,
+    })
+});
+
+/// Сравнивает версии вида "1.2.0" покомпонентно (нечисловые части = 0)
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parse = |v: &str| -> Vec<u64> {
+        v.trim().split('.').map(|p| p.parse().unwrap_or(0)).collect()
+    };
+    let (a, b) = (parse(a), parse(b));
+    let len = a.len().max(b.len());
+    for i in 0..len {
+        let (x, y) = (a.get(i).copied().unwrap_or(0), b.get(i).copied().unwrap_or(0));
+        match x.cmp(&y) {
+            Ordering::Equal => continue,
+            other => return other,
+        }
+    }
+    Ordering::Equal
+}
+
+/// Проверяет схему набора селекторов
+///
+/// Требования:
+/// - `_meta.version` — непустая строка
+/// - есть все секции встроенного набора, каждая — объект
+/// - все ключи встроенного набора присутствуют в секциях
+/// - значения — строка или непустой массив строк (`_comment` не проверяется)
+///
+/// # Returns
+/// Версию набора
+pub fn validate_selectors(json: &str) -> Result<String, String> {
+    let value: Value = serde_json::from_str(json).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let embedded: Value = serde_json::from_str(CLAUDE_SELECTORS_JSON).map_err(|e| e.to_string())?;
+
+    let root = value.as_object().ok_or("Selectors must be a JSON object")?;
+
+    let version = root.get("_meta")
+        .and_then(|m| m.get("version"))
+        .and_then(|v| v.as_str())
+        .filter(|v| !v.trim().is_empty())
+        .ok_or("Missing _meta.version")?;
+
+    for (section, expected) in embedded.as_object().into_iter().flatten() {
+        if section == "_meta" {
+            continue;
+        }
+        let actual = root.get(section)
+            .and_then(|s| s.as_object())
+            .ok_or_else(|| format!("Missing section '{}'", section))?;
+        for key in expected.as_object().into_iter().flat_map(|o| o.keys()) {
+            if key != "_comment" && !actual.contains_key(key) {
+                return Err(format!("Missing selector '{}.{}'", section, key));
+            }
+        }
+    }
+
+    for (section, entries) in root {
+        if section == "_meta" {
+            continue;
+        }
+        for (key, selector) in entries.as_object().into_iter().flatten() {
+            if key == "_comment" {
+                continue;
+            }
+            let valid = match selector {
+                Value::String(s) => !s.trim().is_empty(),
+                Value::Array(items) => !items.is_empty()
+                    && items.iter().all(|i| i.as_str().is_some_and(|s| !s.trim().is_empty())),
+                _ => false,
+            };
+            if !valid {
+                return Err(format!("Invalid selector '{}.{}'", section, key));
+            }
+        }
+    }
+
+    Ok(version.to_string())
+}
+
+/// Достаёт `_meta.version` и `_meta.updated` (без валидации)
+fn parse_meta(json: &str) -> (String, Option<String>) {
+    let value: Value = serde_json::from_str(json).unwrap_or(Value::Null);
+    let meta = &value["_meta"];
+    (
+        meta["version"].as_str().unwrap_or("0.0.0").to_string(),
+        meta["updated"].as_str().map(String::from),
+    )
+}
+
+/// Путь к override-файлу селекторов
+fn get_selectors_override_path() -> Option<PathBuf> {
+    get_app_data_dir().map(|dir| dir.join("selectors.json"))
+}
+
+/// JSON активного набора селекторов (для init script и переинжекции)
+pub fn active_selectors_json() -> String {
+    ACTIVE_SELECTORS.lock()
+        .map(|s| s.json.clone())
+        .unwrap_or_else(|_| CLAUDE_SELECTORS_JSON.to_string())
+}
+
+/// Загружает override при старте, если он валиден и новее встроенного набора
+pub fn load_selectors() {
+    let Some(json) = get_selectors_override_path().and_then(|p| fs::read_to_string(p).ok()) else {
+        return;
+    };
+
+    let version = match validate_selectors(&json) {
+        Ok(version) => version,
+        Err(e) => {
+            log::warn!("Ignoring invalid selectors override: {}", e);
+            return;
+        }
+    };
+
+    let (embedded_version, _) = parse_meta(CLAUDE_SELECTORS_JSON);
+    if compare_versions(&version, &embedded_version) != Ordering::Greater {
+        return;
+    }
+
+    if let Ok(mut active) = ACTIVE_SELECTORS.lock() {
+        let (_, updated) = parse_meta(&json);
+        *active = ActiveSelectors { json, version, updated, source: SelectorSource::Override };
+    }
+}
+
+/// Применяет новый набор селекторов
+///
+/// Набор валидируется и принимается только если он новее активного.
+/// Сохраняется в app data и переинжектится в открытые Claude табы.
+///
+/// # Returns
+/// `true` если набор применён, `false` если версия не новее активной
+pub fn apply_selectors(app: &AppHandle, json: &str) -> Result<bool, String> {
+    let version = validate_selectors(json)?;
+
+    {
+        let mut active = ACTIVE_SELECTORS.lock().map_err(|e| e.to_string())?;
+        if compare_versions(&version, &active.version) != Ordering::Greater {
+            return Ok(false);
+        }
+
+        let path = get_selectors_override_path().ok_or("Cannot get app data dir")?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::write(&path, json).map_err(|e| e.to_string())?;
+
+        let (_, updated) = parse_meta(json);
+        *active = ActiveSelectors {
+            json: json.to_string(),
+            version,
+            updated,
+            source: SelectorSource::Override,
+        };
+    }
+
+    reinject_selectors(app);
+    Ok(true)
+}
+
+/// Устанавливает активные селекторы (`window._s`) во всех открытых Claude табах
+pub fn reinject_selectors(app: &AppHandle) {
+    let script = format!("window._s = {};", active_selectors_json());
+    for tab in 1u8..=3 {
+        if let Some(webview) = app.get_webview(&format!("claude_{}", tab)) {
+            let _ = webview.eval(&script);
+        }
+    }
+}
+
+/// Информация об активном наборе селекторов
+pub fn selectors_info() -> SelectorsInfo {
+    let (embedded_version, _) = parse_meta(CLAUDE_SELECTORS_JSON);
+    let override_path = get_selectors_override_path()
+        .filter(|p| p.exists())
+        .map(|p| p.to_string_lossy().to_string());
+
+    let (source, version, updated) = ACTIVE_SELECTORS.lock()
+        .map(|s| (s.source, s.version.clone(), s.updated.clone()))
+        .unwrap_or((SelectorSource::Embedded, embedded_version.clone(), None));
+
+    SelectorsInfo { source, version, updated, embedded_version, override_path }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("1.2.0", "1.2.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.10.0", "1.9.3"), Ordering::Greater);
+        assert_eq!(compare_versions("1.2", "1.2.1"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_embedded_selectors_are_valid() {
+        assert!(validate_selectors(CLAUDE_SELECTORS_JSON).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_missing_section_and_bad_values() {
+        let mut value: Value = serde_json::from_str(CLAUDE_SELECTORS_JSON).unwrap();
+        value.as_object_mut().unwrap().remove("input");
+        assert!(validate_selectors(&value.to_string()).unwrap_err().contains("input"));
+
+        let mut value: Value = serde_json::from_str(CLAUDE_SELECTORS_JSON).unwrap();
+        value["generation"]["stopButton"] = serde_json::json!([]);
+        assert!(validate_selectors(&value.to_string()).is_err());
+
+        let mut value: Value = serde_json::from_str(CLAUDE_SELECTORS_JSON).unwrap();
+        value["_meta"]["version"] = Value::Null;
+        assert!(validate_selectors(&value.to_string()).is_err());
+    }
+}